@@ -0,0 +1,413 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::boards::{canonical_flops, flop_name, generated_subset, parse_flop, Flop, Game, Subset};
+use crate::discovery::get_exe_dir;
+use crate::config::Profile;
+use crate::json_out::action::is_aggressive;
+use crate::json_out::parse::{node_strategies, range_frequencies};
+use crate::solver::range_weights;
+
+// Multi-flop batch runs. Everything lives in one output directory:
+//
+//   batch.json       the profile and flop source the run was started with
+//   progress.jsonl   one line per solved flop (FlopResult::to_json)
+//   trees/<flop>.json  the dumped tree of each flop
+//   summary.csv      aggregate frequencies, rewritten at the end of a run
+//
+// A flop counts as done once its progress line is written, so an
+// interrupted run picks up where it stopped when started again on the same
+// directory.
+
+pub const MANIFEST_FILE: &str = "batch.json";
+pub const PROGRESS_FILE: &str = "progress.jsonl";
+pub const SUMMARY_FILE: &str = "summary.csv";
+pub const TREES_DIR: &str = "trees";
+
+// Range-level strategy at one node.
+pub struct NodeFrequencies {
+    pub actions: Vec<String>,
+    pub frequencies: Vec<f64>,
+}
+
+impl NodeFrequencies {
+    // Bets, raises and all-ins together.
+    pub fn aggressive(&self) -> f64 {
+        self.actions
+            .iter()
+            .zip(&self.frequencies)
+            .filter(|(a, _)| is_aggressive(a))
+            .map(|(_, f)| f)
+            .sum()
+    }
+
+    pub fn of(&self, action: &str) -> f64 {
+        self.actions
+            .iter()
+            .position(|a| a == action)
+            .map(|i| self.frequencies[i])
+            .unwrap_or(0.0)
+    }
+
+    fn to_json(&self) -> Value {
+        json!({ "actions": self.actions, "frequencies": self.frequencies })
+    }
+
+    fn from_json(v: &Value) -> Option<NodeFrequencies> {
+        let actions = v["actions"].as_array()?.iter().filter_map(|a| a.as_str().map(String::from)).collect();
+        let frequencies = v["frequencies"].as_array()?.iter().filter_map(|f| f.as_f64()).collect();
+        Some(NodeFrequencies { actions, frequencies })
+    }
+}
+
+// One solved flop: OOP's first action and IP's response to a check.
+pub struct FlopResult {
+    pub flop: String,
+    // How many raw flops this one stands for (1 unless the list says).
+    pub weight: f64,
+    pub oop: Option<NodeFrequencies>,
+    pub ip_vs_check: Option<NodeFrequencies>,
+    pub elapsed_seconds: f64,
+    // % of pot, as the solver last reported it.
+    pub exploitability: Option<f64>,
+    // Relative to the batch directory.
+    pub tree_file: Option<String>,
+}
+
+impl FlopResult {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "flop": self.flop,
+            "weight": self.weight,
+            "oop": self.oop.as_ref().map(NodeFrequencies::to_json),
+            "ip_vs_check": self.ip_vs_check.as_ref().map(NodeFrequencies::to_json),
+            "elapsed_seconds": self.elapsed_seconds,
+            "exploitability_pct": self.exploitability,
+            "tree_file": self.tree_file,
+        })
+    }
+
+    pub fn from_json(v: &Value) -> Option<FlopResult> {
+        Some(FlopResult {
+            flop: v["flop"].as_str()?.to_string(),
+            weight: v["weight"].as_f64().unwrap_or(1.0),
+            oop: NodeFrequencies::from_json(&v["oop"]),
+            ip_vs_check: NodeFrequencies::from_json(&v["ip_vs_check"]),
+            elapsed_seconds: v["elapsed_seconds"].as_f64().unwrap_or(0.0),
+            exploitability: v["exploitability_pct"].as_f64(),
+            tree_file: v["tree_file"].as_str().map(String::from),
+        })
+    }
+}
+
+// The flops to solve, with weights: "all" for every canonical flop (or,
+// with `subset`, a reduced set), otherwise a file with one flop per line,
+// optionally followed by its weight ("As,Kh,Qd 24", the format `flops`
+// exports). Blank lines and '#' comments are ignored; repeats are dropped.
+// Every flop must be dealt from `game`'s deck; subsets are hold'em only.
+pub fn flop_list(source: &str, subset: Option<Subset>, game: Game) -> Result<Vec<(Flop, f64)>, Box<dyn Error>> {
+    if source == "all" {
+        return match subset {
+            Some(_) if game != Game::Holdem => Err("flop subsets are only available for hold'em".into()),
+            Some(s) => subset_flops(s),
+            None => Ok(canonical_flops(game).into_iter().map(|(f, w)| (f, w as f64)).collect()),
+        };
+    }
+    if subset.is_some() {
+        return Err("a subset picks from all flops; use `all` instead of a file".into());
+    }
+    read_flop_file(source, game)
+}
+
+// The reduced sets in resources/flopsets/, built in. A file of the same name
+// next to the executable replaces one.
+const STANDARD_LISTS: [(usize, &str); 4] = [
+    (25, include_str!("../resources/flopsets/25.txt")),
+    (49, include_str!("../resources/flopsets/49.txt")),
+    (95, include_str!("../resources/flopsets/95.txt")),
+    (184, include_str!("../resources/flopsets/184.txt")),
+];
+
+// resources/flopsets/<n>.txt next to the executable.
+pub fn standard_subset_path(n: usize) -> PathBuf {
+    get_exe_dir().join("resources/flopsets").join(format!("{}.txt", n))
+}
+
+// The flops of a reduced set, with weights.
+pub fn subset_flops(subset: Subset) -> Result<Vec<(Flop, f64)>, Box<dyn Error>> {
+    match subset {
+        Subset::Generated(n) => Ok(generated_subset(n).into_iter().map(|(f, w)| (f, w as f64)).collect()),
+        Subset::Standard(n) => {
+            let path = standard_subset_path(n);
+            if path.is_file() {
+                return read_flop_file(&path.to_string_lossy(), Game::Holdem);
+            }
+            let (_, text) = STANDARD_LISTS
+                .iter()
+                .find(|(size, _)| *size == n)
+                .ok_or_else(|| format!("there is no standard {}-flop set", n))?;
+            parse_flop_list(text, &format!("flopsets/{}.txt", n), Game::Holdem)
+        }
+    }
+}
+
+fn read_flop_file(source: &str, game: Game) -> Result<Vec<(Flop, f64)>, Box<dyn Error>> {
+    let text = fs::read_to_string(source).map_err(|e| format!("cannot read flop list {}: {}", source, e))?;
+    parse_flop_list(&text, source, game)
+}
+
+// `source` names the list in errors.
+fn parse_flop_list(text: &str, source: &str, game: Game) -> Result<Vec<(Flop, f64)>, Box<dyn Error>> {
+    let mut flops: Vec<(Flop, f64)> = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        // A trailing number is the weight; cards never parse as one.
+        let (flop_text, weight) = match line.rsplit_once([' ', '\t', ',']) {
+            Some((rest, last)) if last.parse::<f64>().is_ok() => (rest, last.parse::<f64>().unwrap_or(1.0)),
+            _ => (line, 1.0),
+        };
+        if weight <= 0.0 {
+            return Err(format!("{}:{}: weight must be positive", source, n + 1).into());
+        }
+        let flop = parse_flop(flop_text).map_err(|e| format!("{}:{}: {}", source, n + 1, e))?;
+        if !flop.iter().all(|&c| game.has_card(c)) {
+            return Err(format!("{}:{}: {} is not a short deck flop (6 to A)", source, n + 1, flop_text).into());
+        }
+        if !flops.iter().any(|(f, _)| *f == flop) {
+            flops.push((flop, weight));
+        }
+    }
+    if flops.is_empty() {
+        return Err(format!("{} lists no flops", source).into());
+    }
+    Ok(flops)
+}
+
+// One line per flop, readable back by `flop_list`.
+pub fn format_flop_list(flops: &[(Flop, f64)]) -> String {
+    flops.iter().map(|(f, w)| format!("{} {}\n", flop_name(f), w)).collect()
+}
+
+// Settings that change the solution; a run can only be resumed with the same.
+fn solve_settings(profile: &Profile) -> Value {
+    let mut v = profile.to_json();
+    if let Some(obj) = v.as_object_mut() {
+        for key in ["description", "thread_num", "print_interval", "big_blind"] {
+            obj.remove(key);
+        }
+    }
+    v
+}
+
+// Create the batch directory, or check that an existing one was started with
+// the same settings.
+pub fn open_dir(dir: &Path, profile: &Profile, source: &str) -> Result<(), Box<dyn Error>> {
+    let manifest_path = dir.join(MANIFEST_FILE);
+    let settings = solve_settings(profile);
+
+    if manifest_path.is_file() {
+        let manifest: Value = serde_json::from_str(&fs::read_to_string(&manifest_path)?)?;
+        if manifest["settings"] != settings {
+            return Err(format!(
+                "{} was started with different solver settings (profile '{}'); use another output directory",
+                dir.display(),
+                manifest["profile"].as_str().unwrap_or("?")
+            )
+            .into());
+        }
+        return Ok(());
+    }
+
+    fs::create_dir_all(dir.join(TREES_DIR))?;
+    let manifest = json!({ "profile": profile.name, "source": source, "settings": settings });
+    fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+    Ok(())
+}
+
+// Results already in the directory, by flop. Unreadable lines are skipped,
+// which just means those flops are solved again.
+pub fn load_progress(dir: &Path) -> Result<HashMap<String, FlopResult>, Box<dyn Error>> {
+    Ok(read_progress(dir)?.into_iter().map(|r| (r.flop.clone(), r)).collect())
+}
+
+// The readable lines of progress.jsonl, in the order written.
+fn read_progress(dir: &Path) -> Result<Vec<FlopResult>, Box<dyn Error>> {
+    let path = dir.join(PROGRESS_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(fs::read_to_string(&path)?
+        .lines()
+        .filter_map(|l| serde_json::from_str::<Value>(l).ok())
+        .filter_map(|v| FlopResult::from_json(&v))
+        .collect())
+}
+
+pub fn append_progress(dir: &Path, result: &FlopResult) -> Result<(), Box<dyn Error>> {
+    let mut file = OpenOptions::new().create(true).append(true).open(dir.join(PROGRESS_FILE))?;
+    writeln!(file, "{}", result.to_json())?;
+    Ok(())
+}
+
+// "Qs,Jh,2h" -> trees/QsJh2h.json
+pub fn tree_file_name(flop: &str) -> String {
+    format!("{}/{}.json", TREES_DIR, flop.replace(',', ""))
+}
+
+// Weighted average of `f` over the results that have the node.
+pub fn weighted_average(results: &[&FlopResult], f: impl Fn(&FlopResult) -> Option<f64>) -> Option<f64> {
+    let (sum, weight) = results
+        .iter()
+        .filter_map(|r| f(r).map(|v| (v * r.weight, r.weight)))
+        .fold((0.0, 0.0), |(s, w), (v, rw)| (s + v, w + rw));
+    (weight > 0.0).then(|| sum / weight)
+}
+
+pub fn tree_path(dir: &Path, result: &FlopResult) -> Option<PathBuf> {
+    result.tree_file.as_ref().map(|f| dir.join(f))
+}
+
+// Save the tree and boil it down to range-level frequencies.
+pub fn record_flop(
+    dir: &Path,
+    flop: &Flop,
+    weight: f64,
+    tree: &Value,
+    profile: &Profile,
+    elapsed_seconds: f64,
+    exploitability: Option<f64>,
+) -> Result<FlopResult, Box<dyn Error>> {
+    let name = flop_name(flop);
+    let tree_file = tree_file_name(&name);
+    fs::write(dir.join(&tree_file), tree.to_string())?;
+
+    let mut result = summarize_tree(name, weight, tree, profile, elapsed_seconds, Some(tree_file));
+    result.exploitability = exploitability;
+    append_progress(dir, &result)?;
+    Ok(result)
+}
+
+fn summarize_tree(
+    flop: String,
+    weight: f64,
+    tree: &Value,
+    profile: &Profile,
+    elapsed_seconds: f64,
+    tree_file: Option<String>,
+) -> FlopResult {
+    let frequencies = |node: &Value, range: &str| {
+        node_strategies(node).map(|s| NodeFrequencies {
+            frequencies: range_frequencies(&s, Some(&range_weights(range))),
+            actions: s.actions,
+        })
+    };
+    FlopResult {
+        oop: frequencies(tree, &profile.range_oop),
+        ip_vs_check: tree
+            .get("childrens")
+            .and_then(|c| c.get("CHECK"))
+            .and_then(|n| frequencies(n, &profile.range_ip)),
+        flop,
+        weight,
+        elapsed_seconds,
+        exploitability: None,
+        tree_file,
+    }
+}
+
+// The profile a batch directory was solved with (solver settings only; the
+// description, thread count and big blind are not kept).
+pub fn load_profile(dir: &Path) -> Result<Profile, Box<dyn Error>> {
+    let path = dir.join(MANIFEST_FILE);
+    let text = fs::read_to_string(&path).map_err(|e| format!("{} is not a batch directory: {}", dir.display(), e))?;
+    let manifest: Value = serde_json::from_str(&text)?;
+    let name = manifest["profile"].as_str().unwrap_or("batch");
+    Ok(Profile::from_json(name, &manifest["settings"]).map_err(|e| format!("{}: {}", path.display(), e))?)
+}
+
+// Every result in a batch directory, in the order solved. A flop whose
+// progress line has no frequencies is summarized again from its saved tree.
+pub fn load_results(dir: &Path, profile: &Profile) -> Result<Vec<FlopResult>, Box<dyn Error>> {
+    let mut results = Vec::new();
+    for mut r in read_progress(dir)? {
+        if r.oop.is_none()
+            && let Some(tree) = tree_path(dir, &r).and_then(|p| fs::read_to_string(p).ok())
+            && let Ok(tree) = serde_json::from_str::<Value>(&tree)
+        {
+            let exploitability = r.exploitability;
+            r = summarize_tree(r.flop, r.weight, &tree, profile, r.elapsed_seconds, r.tree_file);
+            r.exploitability = exploitability;
+        }
+        // A flop solved twice (e.g. after a crash mid-write) keeps its last result.
+        results.retain(|x: &FlopResult| x.flop != r.flop);
+        results.push(r);
+    }
+    Ok(results)
+}
+
+// flop,weight,oop_check,oop_bet,ip_check,ip_bet,exploitability_pct, then one
+// column per action ("oop:BET 25.000000", ...). Frequencies are fractions of
+// 1; exploitability_pct is % of the pot, blank when the solver did not say.
+pub fn summary_csv(results: &[&FlopResult]) -> String {
+    let mut oop_actions: Vec<&str> = Vec::new();
+    let mut ip_actions: Vec<&str> = Vec::new();
+    for r in results {
+        for (node, list) in [(&r.oop, &mut oop_actions), (&r.ip_vs_check, &mut ip_actions)] {
+            for a in node.iter().flat_map(|n| n.actions.iter()) {
+                if !list.contains(&a.as_str()) {
+                    list.push(a);
+                }
+            }
+        }
+    }
+
+    let mut header = vec!["flop".to_string(), "weight".into(), "oop_check".into(), "oop_bet".into(), "ip_check".into(), "ip_bet".into(), "exploitability_pct".into()];
+    header.extend(oop_actions.iter().map(|a| format!("oop:{}", a)));
+    header.extend(ip_actions.iter().map(|a| format!("ip:{}", a)));
+    let mut out = header.join(",") + "\n";
+
+    let cell = |v: Option<f64>| v.map(|f| format!("{:.4}", f)).unwrap_or_default();
+    for r in results {
+        let mut row = vec![
+            format!("\"{}\"", r.flop),
+            r.weight.to_string(),
+            cell(r.oop.as_ref().map(|n| n.of("CHECK"))),
+            cell(r.oop.as_ref().map(NodeFrequencies::aggressive)),
+            cell(r.ip_vs_check.as_ref().map(|n| n.of("CHECK"))),
+            cell(r.ip_vs_check.as_ref().map(NodeFrequencies::aggressive)),
+            cell(r.exploitability),
+        ];
+        row.extend(oop_actions.iter().map(|a| cell(r.oop.as_ref().map(|n| n.of(a)))));
+        row.extend(ip_actions.iter().map(|a| cell(r.ip_vs_check.as_ref().map(|n| n.of(a)))));
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boards::{canonical, STANDARD_SUBSETS};
+
+    #[test]
+    fn standard_subsets_are_canonical_and_weighted() {
+        for &n in STANDARD_SUBSETS {
+            let flops = subset_flops(Subset::Standard(n)).unwrap();
+            assert_eq!(flops.len(), n, "{}-flop set", n);
+            for (flop, weight) in &flops {
+                assert_eq!(canonical(flop), *flop, "{} in the {}-flop set", flop_name(flop), n);
+                assert!(*weight > 0.0, "{} in the {}-flop set", flop_name(flop), n);
+            }
+            assert_eq!(flops.iter().map(|(_, w)| w).sum::<f64>(), 22_100.0, "{}-flop set", n);
+        }
+    }
+}
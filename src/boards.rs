@@ -0,0 +1,269 @@
+use std::collections::BTreeMap;
+
+// Board enumeration. A card is an index 0..52 = rank * 4 + suit, with ranks
+// 2..A as 0..12 and suits in the order s, h, d, c.
+//
+// Two flops are strategically identical when one becomes the other by
+// renaming suits; the canonical form of a flop is the smallest of its 24
+// suit relabellings, which gives the 1755 distinct flops. Canonical flops
+// are written with suits assigned in order of appearance (spades first), so
+// a rainbow A-K-Q reads "As,Kh,Qd" and a monotone one "As,Ks,Qs".
+//
+// The weight of a canonical flop is how many of the 22100 raw flops it
+// stands for (4 for a monotone flop, 24 for an unpaired rainbow one, ...).
+
+pub const RANKS: &[u8] = b"23456789TJQKA";
+// High to low: the order hand classes and range grids are listed in (AA,
+// AKs, AKo, ... 22).
+pub const RANK_ORDER: &str = "AKQJT98765432";
+const SUITS: &[u8] = b"shdc";
+
+pub type Flop = [u8; 3];
+
+// Which deck is dealt. Short deck (6+ hold'em) drops the 2s to 5s, leaving
+// 36 cards, and ranks hands differently (see evaluator.rs).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Game {
+    #[default]
+    Holdem,
+    ShortDeck,
+}
+
+impl Game {
+    // Also the mode console_solver and the solver library take.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Game::Holdem => "holdem",
+            Game::ShortDeck => "shortdeck",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Game> {
+        match s {
+            "holdem" => Some(Game::Holdem),
+            "shortdeck" => Some(Game::ShortDeck),
+            _ => None,
+        }
+    }
+
+    // Index of the lowest rank in the deck, 2 = 0.
+    pub fn lowest_rank(&self) -> usize {
+        match self {
+            Game::Holdem => 0,
+            Game::ShortDeck => 4,
+        }
+    }
+
+    pub fn has_rank(&self, rank: char) -> bool {
+        RANKS.iter().position(|&r| r as char == rank.to_ascii_uppercase()).is_some_and(|r| r >= self.lowest_rank())
+    }
+
+    pub fn has_card(&self, card: u8) -> bool {
+        (card / 4) as usize >= self.lowest_rank()
+    }
+}
+
+// Every card is a card of `game`'s deck and none repeats.
+pub fn check_cards(game: Game, cards: &[&str]) -> Result<(), String> {
+    let mut seen = Vec::with_capacity(cards.len());
+    for text in cards {
+        let card = parse_card(text).ok_or_else(|| format!("'{}' is not a card (e.g. Ah, Td, 7c)", text))?;
+        if !game.has_card(card) {
+            return Err(format!("{} is not in the short deck (6 to A)", text));
+        }
+        if seen.contains(&card) {
+            return Err(format!("{} appears twice", text));
+        }
+        seen.push(card);
+    }
+    Ok(())
+}
+
+pub fn card_name(card: u8) -> String {
+    format!("{}{}", RANKS[(card / 4) as usize] as char, SUITS[(card % 4) as usize] as char)
+}
+
+pub fn parse_card(s: &str) -> Option<u8> {
+    let bytes = s.as_bytes();
+    if bytes.len() != 2 {
+        return None;
+    }
+    let rank = RANKS.iter().position(|&r| r == bytes[0].to_ascii_uppercase())?;
+    let suit = SUITS.iter().position(|&c| c == bytes[1].to_ascii_lowercase())?;
+    Some((rank * 4 + suit) as u8)
+}
+
+// "Qs,Jh,2h"
+pub fn flop_name(flop: &Flop) -> String {
+    flop.iter().map(|&c| card_name(c)).collect::<Vec<_>>().join(",")
+}
+
+// Accepts "Qs,Jh,2h", "Qs Jh 2h" or "QsJh2h" (any case).
+pub fn parse_flop(s: &str) -> Result<Flop, String> {
+    let compact: String = s.chars().filter(|c| !c.is_whitespace() && *c != ',').collect();
+    if compact.len() != 6 {
+        return Err(format!("'{}' is not a flop (need 3 cards like Qs,Jh,2h)", s.trim()));
+    }
+    let mut flop = [0u8; 3];
+    for (i, slot) in flop.iter_mut().enumerate() {
+        let text = &compact[i * 2..i * 2 + 2];
+        *slot = parse_card(text).ok_or_else(|| format!("'{}' in '{}' is not a card", text, s.trim()))?;
+    }
+    if flop[0] == flop[1] || flop[0] == flop[2] || flop[1] == flop[2] {
+        return Err(format!("'{}' repeats a card", s.trim()));
+    }
+    Ok(flop)
+}
+
+// Highest rank first, suits in s-h-d-c order within a rank.
+fn sorted(mut flop: Flop) -> Flop {
+    flop.sort_by_key(|&c| (std::cmp::Reverse(c / 4), c % 4));
+    flop
+}
+
+fn suit_permutations() -> Vec<[u8; 4]> {
+    let mut perms = Vec::with_capacity(24);
+    for a in 0..4u8 {
+        for b in (0..4).filter(|&b| b != a) {
+            for c in (0..4).filter(|&c| c != a && c != b) {
+                let d = 6 - a - b - c;
+                perms.push([a, b, c, d]);
+            }
+        }
+    }
+    perms
+}
+
+// The representative of `flop`'s suit-isomorphism class.
+pub fn canonical(flop: &Flop) -> Flop {
+    suit_permutations()
+        .iter()
+        .map(|perm| sorted(flop.map(|c| (c / 4) * 4 + perm[(c % 4) as usize])))
+        .min_by_key(sort_key)
+        .unwrap_or(*flop)
+}
+
+pub const RAW_FLOPS: u32 = 22_100;
+// Sizes of the well-known reduced flop sets used for flop studies.
+pub const STANDARD_SUBSETS: &[usize] = &[25, 49, 95, 184];
+
+// A reduced set of flops: one of the well-known lists, read from
+// resources/flopsets/<N>.txt, or one generated by `generated_subset`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Subset {
+    Standard(usize),
+    Generated(usize),
+}
+
+fn sort_key(flop: &Flop) -> [(std::cmp::Reverse<u8>, u8); 3] {
+    flop.map(|c| (std::cmp::Reverse(c / 4), c % 4))
+}
+
+// Every canonical flop (1755 of them, 573 in short deck) with its weight,
+// highest cards first.
+pub fn canonical_flops(game: Game) -> Vec<(Flop, u32)> {
+    let mut counts = BTreeMap::new();
+    let low = game.lowest_rank() as u8 * 4;
+    for a in low..52u8 {
+        for b in a + 1..52 {
+            for c in b + 1..52 {
+                *counts.entry(sort_key(&canonical(&[a, b, c]))).or_insert(0) += 1;
+            }
+        }
+    }
+    counts
+        .into_iter()
+        .map(|(key, n)| (key.map(|(std::cmp::Reverse(rank), suit)| rank * 4 + suit), n))
+        .collect()
+}
+
+// Suits on the board: 1 = monotone, 2 = two-tone, 3 = rainbow.
+pub fn suit_count(flop: &Flop) -> usize {
+    let mut suits: Vec<u8> = flop.iter().map(|c| c % 4).collect();
+    suits.sort_unstable();
+    suits.dedup();
+    suits.len()
+}
+
+// Distinct ranks on the board: 1 = trips, 2 = paired, 3 = unpaired.
+pub fn rank_count(flop: &Flop) -> usize {
+    let mut ranks: Vec<u8> = flop.iter().map(|c| c / 4).collect();
+    ranks.sort_unstable();
+    ranks.dedup();
+    ranks.len()
+}
+
+// A reduced set of `n` canonical flops whose weights still add up to 22100.
+//
+// The flops are ordered so that similar textures sit together (suitedness,
+// then pairedness, then ranks from the top), cut into `n` runs of equal
+// total weight, and each run is represented by its heaviest flop carrying
+// the run's whole weight. The result keeps the full set's mix of suitedness,
+// pairedness and high cards. A single flop never spans two runs, so very
+// large `n` can give slightly fewer flops. These are not the well-known
+// 25/49/95/184 lists (see `Subset::Standard`) and do not pick the same flops.
+pub fn generated_subset(n: usize) -> Vec<(Flop, u32)> {
+    let mut all = canonical_flops(Game::Holdem);
+    if n >= all.len() {
+        return all;
+    }
+    all.sort_by_key(|(f, _)| (std::cmp::Reverse(suit_count(f)), std::cmp::Reverse(rank_count(f)), sort_key(f)));
+
+    let mut subset = Vec::with_capacity(n);
+    let mut run_weight = 0;
+    let mut best: Option<(Flop, u32)> = None;
+    let mut cumulative = 0u64;
+    let mut next_cut = 1;
+    for (flop, weight) in all {
+        cumulative += weight as u64;
+        run_weight += weight;
+        if best.is_none_or(|(_, w)| weight > w) {
+            best = Some((flop, weight));
+        }
+        // The run ends once the cumulative weight reaches next_cut/n of the total.
+        if cumulative * n as u64 >= next_cut as u64 * RAW_FLOPS as u64 {
+            if let Some((flop, _)) = best.take() {
+                subset.push((flop, run_weight));
+            }
+            run_weight = 0;
+            while cumulative * n as u64 >= next_cut as u64 * RAW_FLOPS as u64 {
+                next_cut += 1;
+            }
+        }
+    }
+    subset.sort_by_key(|(f, _)| sort_key(f));
+    subset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(flops: &[(Flop, u32)]) -> u32 {
+        flops.iter().map(|(_, w)| w).sum()
+    }
+
+    #[test]
+    fn holdem_has_1755_canonical_flops() {
+        let flops = canonical_flops(Game::Holdem);
+        assert_eq!(flops.len(), 1755);
+        assert_eq!(total(&flops), RAW_FLOPS);
+    }
+
+    #[test]
+    fn short_deck_has_573_canonical_flops() {
+        let flops = canonical_flops(Game::ShortDeck);
+        assert_eq!(flops.len(), 573);
+        // C(36, 3)
+        assert_eq!(total(&flops), 7140);
+    }
+
+    #[test]
+    fn generated_subsets_keep_the_total_weight() {
+        for n in [1, 25, 49, 95, 184, 300] {
+            let flops = generated_subset(n);
+            assert_eq!(flops.len(), n);
+            assert_eq!(total(&flops), RAW_FLOPS, "{} flops", n);
+        }
+    }
+}
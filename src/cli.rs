@@ -0,0 +1,84 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use crate::boards::{Subset, STANDARD_SUBSETS};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+// Command line flags shared by every mode. Anything that is not a flag is
+// kept, in order, in `positional` (hero hand, flop, turn, river, or a
+// subcommand and its arguments).
+#[derive(Debug, Default)]
+pub struct CliArgs {
+    pub profile: Option<String>,
+    pub config_path: Option<PathBuf>,
+    pub format: OutputFormat,
+    // export-csv: one file per card under a chance node.
+    pub per_card: bool,
+    // batch-flops / flops: a reduced flop set, standard or generated.
+    pub subset: Option<Subset>,
+    // train: whose records to read and write.
+    pub user: Option<String>,
+    pub positional: Vec<String>,
+}
+
+pub fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<CliArgs, Box<dyn Error>> {
+    let mut out = CliArgs::default();
+
+    while let Some(arg) = args.next() {
+        // Accept both "--flag value" and "--flag=value".
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((f, v)) if f.starts_with("--") => (f.to_string(), Some(v.to_string())),
+            _ => (arg.clone(), None),
+        };
+        let mut value = |name: &str| -> Result<String, Box<dyn Error>> {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} needs a value", name).into())
+        };
+
+        match flag.as_str() {
+            "--profile" | "-p" => out.profile = Some(value("--profile")?),
+            "--format" => {
+                out.format = match value("--format")?.as_str() {
+                    "text" => OutputFormat::Text,
+                    "json" => OutputFormat::Json,
+                    other => return Err(format!("unknown format '{}' (expected text or json)", other).into()),
+                }
+            }
+            "--per-card" => out.per_card = true,
+            "--subset" => {
+                let raw = value("--subset")?;
+                match raw.parse::<usize>() {
+                    Ok(n) if STANDARD_SUBSETS.contains(&n) => out.subset = Some(Subset::Standard(n)),
+                    _ => {
+                        return Err(format!(
+                            "--subset takes a standard set size ({:?}), got '{}'; use --generated-subset N for other sizes",
+                            STANDARD_SUBSETS, raw
+                        )
+                        .into())
+                    }
+                }
+            }
+            "--generated-subset" => {
+                let raw = value("--generated-subset")?;
+                match raw.parse::<usize>() {
+                    Ok(n) if n > 0 => out.subset = Some(Subset::Generated(n)),
+                    _ => return Err(format!("--generated-subset needs a positive number of flops, got '{}'", raw).into()),
+                }
+            }
+            "--user" => out.user = Some(value("--user")?),
+            "--config" => out.config_path = Some(PathBuf::from(value("--config")?)),
+            _ if flag.starts_with("--") => return Err(format!("unknown option '{}'", flag).into()),
+            _ => out.positional.push(arg),
+        }
+    }
+
+    Ok(out)
+}
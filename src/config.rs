@@ -0,0 +1,886 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::boards::{Game, RANK_ORDER};
+use crate::sizing::{Resolved, SizeExpr, Sizing};
+use crate::solver::{RANGE_IP, RANGE_OOP};
+
+// Configuration lives in JSON files holding named profiles. A profile bundles
+// the tree settings (pot, stack, bet sizes), the ranges and the solver options
+// that used to be compiled into `build_job_content`.
+//
+// Files are read in this order, later files overriding earlier ones profile by
+// profile:
+//   1. built-in "default" profile (the historical constants)
+//   2. per-user file:      $XDG_CONFIG_HOME/texas_solver_tui/config.json
+//                          (or ~/.config/texas_solver_tui/config.json)
+//   3. per-directory file: ./texas_solver_tui.json
+//   4. explicit file:      --config <path>
+//
+// Example:
+// {
+//   "default_profile": "srp-btn-bb-100bb",
+//   "profiles": {
+//     "srp-btn-bb-100bb": {
+//       "description": "Single raised pot, BTN vs BB",
+//       "pot": 50,
+//       "effective_stack": 200,
+//       "bet_sizes": {
+//         "ip":  { "flop": { "bet": [33, 75], "raise": [60] }, "turn": { "bet": ["geo2"] } },
+//         "oop": { "flop": { "raise": ["3x"] }, "turn": { "donk": ["25c"] }, "river": { "allin": true } }
+//       }
+//     },
+//     "3bet-pot-quick": { "pot": 180, "effective_stack": 910, "max_iteration": 10 },
+//     "ip-always-stabs": {
+//       "locks": [ { "path": "CHECK", "strategy": { "BET": 1.0 } } ]
+//     },
+//     "6plus-srp": { "game": "shortdeck", "pot": 20, "effective_stack": 200 }
+//   }
+// }
+//
+// Bet sizes are % of pot, or expressions such as "2.5x", "25c", "3bb",
+// "geo2" and "allin", resolved to % of pot when the profile is read (see
+// sizing.rs). Profiles written back out carry the resolved percentages.
+//
+// `locks` fix a node's strategy before solving (library backend only): at
+// `path` (node_at_path syntax, "CHECK" = IP after OOP checks) every combo
+// plays the given frequencies, which must add up to 1.
+//
+// `game` is "holdem" (default) or "shortdeck" (36 cards, 6 to A). A short
+// deck profile that sets no ranges gets the built-in ones without hands
+// holding a 2 to 5; ranges it does set may not contain such hands.
+//
+// `raise_limit` (default 3) caps the raises per street after the first bet.
+// It is sent to the solver so the size estimate shown before a solve (see
+// tree.rs) describes the same tree.
+//
+// Any key a profile omits is taken from the built-in default profile.
+//
+// Besides profiles, the top level may set where the solver lives:
+//   "solver_path":  "/opt/texassolver/console_solver",
+//   "resource_dir": "/opt/texassolver/resources",
+//   "backend":      "process" | "library",
+//   "library_path": "/opt/texassolver/libtexassolver.so"
+// and "max_exploitability" (% of pot, default 1.0): solves that end above it
+// are reported with a warning. "history_trees" (default 50) is how many
+// dumped trees of past runs are kept (see history.rs); 0 keeps none.
+// Relative paths are resolved against the directory of the config file.
+
+pub const DEFAULT_PROFILE: &str = "default";
+const USER_CONFIG_REL_PATH: &str = "texas_solver_tui/config.json";
+const DIR_CONFIG_FILE: &str = "texas_solver_tui.json";
+pub const DEFAULT_MAX_EXPLOITABILITY: f64 = 1.0;
+pub const DEFAULT_HISTORY_TREES: usize = 50;
+// What the solver's sample parameters use.
+pub const DEFAULT_RAISE_LIMIT: u32 = 3;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StreetBetSizes {
+    pub bet: Vec<f64>,
+    pub raise: Vec<f64>,
+    pub donk: Vec<f64>,
+    pub allin: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayerBetSizes {
+    pub flop: StreetBetSizes,
+    pub turn: StreetBetSizes,
+    pub river: StreetBetSizes,
+}
+
+impl PlayerBetSizes {
+    pub fn streets(&self) -> [(&'static str, &StreetBetSizes); 3] {
+        [("flop", &self.flop), ("turn", &self.turn), ("river", &self.river)]
+    }
+
+    fn street_mut(&mut self, street: &str) -> Option<&mut StreetBetSizes> {
+        match street {
+            "flop" => Some(&mut self.flop),
+            "turn" => Some(&mut self.turn),
+            "river" => Some(&mut self.river),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeLock {
+    pub path: String,
+    // (action, frequency); actions match child keys like path segments do.
+    pub strategy: Vec<(String, f64)>,
+}
+
+impl NodeLock {
+    // {"BET": 1.0}, as passed to ts_lock_node.
+    pub fn strategy_json(&self) -> Value {
+        Value::Object(self.strategy.iter().map(|(a, f)| (a.clone(), json!(f))).collect())
+    }
+
+    fn to_json(&self) -> Value {
+        json!({ "path": self.path, "strategy": self.strategy_json() })
+    }
+}
+
+impl fmt::Display for NodeLock {
+    // "CHECK: BET 100%"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() { "(root)" } else { &self.path };
+        let strategy: Vec<String> = self.strategy.iter().map(|(a, p)| format!("{} {:.0}%", a, p * 100.0)).collect();
+        write!(f, "{}: {}", path, strategy.join(", "))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    pub description: String,
+    pub pot: f64,
+    pub effective_stack: f64,
+    pub range_ip: String,
+    pub range_oop: String,
+    pub bet_sizes_ip: PlayerBetSizes,
+    pub bet_sizes_oop: PlayerBetSizes,
+    pub allin_threshold: f64,
+    // Raises allowed per street after the first bet.
+    pub raise_limit: u32,
+    pub thread_num: u32,
+    pub accuracy: f64,
+    pub max_iteration: u32,
+    pub print_interval: u32,
+    pub use_isomorphism: bool,
+    // Chips per big blind. Only used to show EVs in bb (200-chip stacks are
+    // 100bb by default).
+    pub big_blind: f64,
+    pub locks: Vec<NodeLock>,
+    pub game: Game,
+}
+
+impl Default for Profile {
+    // The values the TUI always used before profiles existed, plus small OOP
+    // leads on the flop and turn so the tree has lines where IP faces a bet.
+    fn default() -> Self {
+        let bet_50 = StreetBetSizes { bet: vec![50.0], ..Default::default() };
+        let lead_33 = StreetBetSizes { bet: vec![33.0], donk: vec![33.0], ..Default::default() };
+        Profile {
+            name: DEFAULT_PROFILE.to_string(),
+            description: "BTN open vs BB call, 100bb (built-in)".to_string(),
+            pot: 50.0,
+            effective_stack: 200.0,
+            range_ip: RANGE_IP.to_string(),
+            range_oop: RANGE_OOP.to_string(),
+            bet_sizes_ip: PlayerBetSizes {
+                flop: bet_50.clone(),
+                turn: bet_50,
+                river: StreetBetSizes::default(),
+            },
+            bet_sizes_oop: PlayerBetSizes {
+                flop: StreetBetSizes { donk: Vec::new(), ..lead_33.clone() },
+                turn: lead_33,
+                river: StreetBetSizes::default(),
+            },
+            allin_threshold: 0.8,
+            raise_limit: DEFAULT_RAISE_LIMIT,
+            thread_num: 8,
+            accuracy: 5.0,
+            max_iteration: 10,
+            print_interval: 10,
+            use_isomorphism: true,
+            big_blind: 2.0,
+            locks: Vec::new(),
+            game: Game::Holdem,
+        }
+    }
+}
+
+// How a spot gets solved: spawn console_solver (the default) or call into a
+// shared library implementing the ABI documented in library.rs.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Backend {
+    #[default]
+    Process,
+    Library,
+}
+
+impl Backend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Backend::Process => "process",
+            Backend::Library => "library",
+        }
+    }
+}
+
+// Where to find console_solver (or the solver library) and its `-r`
+// resources directory. `None` means "discover it" (see discovery.rs).
+#[derive(Debug, Clone, Default)]
+pub struct SolverSettings {
+    pub backend: Backend,
+    pub solver_path: Option<PathBuf>,
+    pub library_path: Option<PathBuf>,
+    pub resource_dir: Option<PathBuf>,
+    // Ask the solver for per-action EVs in the dump, where the backend can.
+    pub dump_evs: bool,
+    // Warn above this exploitability (% pot); None means the default.
+    pub max_exploitability: Option<f64>,
+}
+
+impl SolverSettings {
+    pub fn max_exploitability(&self) -> f64 {
+        self.max_exploitability.unwrap_or(DEFAULT_MAX_EXPLOITABILITY)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub default_profile: Option<String>,
+    pub solver: SolverSettings,
+    pub profiles: BTreeMap<String, Profile>,
+    // Trees of past runs kept under resources/outputs/runs.
+    pub history_trees: usize,
+    // Files that were actually read, in load order.
+    pub sources: Vec<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let mut profiles = BTreeMap::new();
+        profiles.insert(DEFAULT_PROFILE.to_string(), Profile::default());
+        Config {
+            default_profile: None,
+            solver: SolverSettings::default(),
+            profiles,
+            history_trees: DEFAULT_HISTORY_TREES,
+            sources: Vec::new(),
+        }
+    }
+}
+
+// Error raised while reading a config file. `key` is the dotted path of the
+// offending value, e.g. `profiles.3bet-pot-quick.bet_sizes.ip.flop.bet[1]`.
+#[derive(Debug)]
+pub struct ConfigError {
+    pub file: PathBuf,
+    pub key: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.key.is_empty() {
+            write!(f, "{}: {}", self.file.display(), self.message)
+        } else {
+            write!(f, "{}: `{}`: {}", self.file.display(), self.key, self.message)
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+impl Config {
+    pub fn load(explicit: Option<&Path>) -> Result<Config, Box<dyn Error>> {
+        Config::load_from(&candidate_paths(), explicit)
+    }
+
+    // The built-in defaults, then each of `candidates` that exists, then
+    // `explicit`: later files override earlier ones key by key.
+    fn load_from(candidates: &[PathBuf], explicit: Option<&Path>) -> Result<Config, Box<dyn Error>> {
+        let mut config = Config::default();
+        for path in candidates {
+            if path.is_file() {
+                config.merge_file(path)?;
+            }
+        }
+        if let Some(path) = explicit {
+            if !path.is_file() {
+                return Err(format!("config file not found: {}", path.display()).into());
+            }
+            config.merge_file(path)?;
+        }
+
+        if let Some(name) = &config.default_profile
+            && !config.profiles.contains_key(name)
+        {
+            return Err(format!(
+                "`default_profile` names unknown profile '{}' (available: {})",
+                name,
+                config.profile_names().join(", ")
+            )
+            .into());
+        }
+        Ok(config)
+    }
+
+    pub fn merge_file(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let data = fs::read_to_string(path)?;
+        let root: Value = serde_json::from_str(&data)
+            .map_err(|e| ConfigError { file: path.to_path_buf(), key: String::new(), message: e.to_string() })?;
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+        self.merge_value(&root, base_dir)
+            .map_err(|(key, message)| ConfigError { file: path.to_path_buf(), key, message })?;
+        self.sources.push(path.to_path_buf());
+        Ok(())
+    }
+
+    fn merge_value(&mut self, root: &Value, base_dir: &Path) -> Result<(), (String, String)> {
+        let obj = root
+            .as_object()
+            .ok_or_else(|| (String::new(), "top level must be a JSON object".to_string()))?;
+
+        for (key, value) in obj {
+            match key.as_str() {
+                "default_profile" => {
+                    let name = value
+                        .as_str()
+                        .ok_or_else(|| (key.clone(), "expected a string".to_string()))?;
+                    self.default_profile = Some(name.to_string());
+                }
+                "solver_path" => {
+                    let p = expect_str(value, key)?;
+                    self.solver.solver_path = Some(base_dir.join(p));
+                }
+                "library_path" => {
+                    let p = expect_str(value, key)?;
+                    self.solver.library_path = Some(base_dir.join(p));
+                }
+                "backend" => {
+                    self.solver.backend = match expect_str(value, key)? {
+                        "process" => Backend::Process,
+                        "library" => Backend::Library,
+                        other => {
+                            return Err((key.clone(), format!("unknown backend '{}' (expected process or library)", other)));
+                        }
+                    };
+                }
+                "resource_dir" => {
+                    let p = expect_str(value, key)?;
+                    self.solver.resource_dir = Some(base_dir.join(p));
+                }
+                "dump_evs" => self.solver.dump_evs = expect_bool(value, key)?,
+                "max_exploitability" => self.solver.max_exploitability = Some(expect_positive(value, key)?),
+                "history_trees" => {
+                    self.history_trees = value
+                        .as_u64()
+                        .map(|n| n as usize)
+                        .ok_or_else(|| (key.clone(), format!("expected a number of runs (0 or more), got {}", value)))?;
+                }
+                "profiles" => {
+                    let profiles = value
+                        .as_object()
+                        .ok_or_else(|| (key.clone(), "expected an object of named profiles".to_string()))?;
+                    for (name, body) in profiles {
+                        let path = format!("profiles.{}", name);
+                        let profile = parse_profile(name, body, &path)?;
+                        self.profiles.insert(name.clone(), profile);
+                    }
+                }
+                _ => return Err((key.clone(), "unknown key".to_string())),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn profile_names(&self) -> Vec<String> {
+        self.profiles.keys().cloned().collect()
+    }
+
+    // Resolve a profile by name; `None` falls back to `default_profile`, then
+    // to the built-in default.
+    pub fn profile(&self, name: Option<&str>) -> Result<&Profile, Box<dyn Error>> {
+        let name = name
+            .or(self.default_profile.as_deref())
+            .unwrap_or(DEFAULT_PROFILE);
+        self.profiles.get(name).ok_or_else(|| {
+            format!(
+                "unknown profile '{}' (available: {})",
+                name,
+                self.profile_names().join(", ")
+            )
+            .into()
+        })
+    }
+}
+
+impl StreetBetSizes {
+    pub fn to_json(&self) -> Value {
+        json!({ "bet": self.bet, "raise": self.raise, "donk": self.donk, "allin": self.allin })
+    }
+}
+
+impl PlayerBetSizes {
+    pub fn to_json(&self) -> Value {
+        json!({ "flop": self.flop.to_json(), "turn": self.turn.to_json(), "river": self.river.to_json() })
+    }
+}
+
+impl Profile {
+    // Rebuild a profile from `to_json` output (e.g. a history record). Keys
+    // that are not profile keys, such as "profile", are ignored.
+    pub fn from_json(name: &str, value: &Value) -> Result<Profile, String> {
+        let mut body = value.clone();
+        if let Some(obj) = body.as_object_mut() {
+            obj.remove("profile");
+        }
+        parse_profile(name, &body, name).map_err(|(key, message)| format!("`{}`: {}", key, message))
+    }
+
+    // The profile with the same keys a config file uses, so the output can be
+    // pasted back under `profiles`.
+    pub fn to_json(&self) -> Value {
+        let mut v = json!({
+            "description": self.description,
+            "pot": self.pot,
+            "effective_stack": self.effective_stack,
+            "range_ip": self.range_ip,
+            "range_oop": self.range_oop,
+            "bet_sizes": { "ip": self.bet_sizes_ip.to_json(), "oop": self.bet_sizes_oop.to_json() },
+            "allin_threshold": self.allin_threshold,
+            "thread_num": self.thread_num,
+            "accuracy": self.accuracy,
+            "max_iteration": self.max_iteration,
+            "print_interval": self.print_interval,
+            "use_isomorphism": self.use_isomorphism,
+            "big_blind": self.big_blind,
+        });
+        // Only locked profiles carry the key, so batch manifests written
+        // before locks existed still match.
+        if !self.locks.is_empty() {
+            v["locks"] = self.locks.iter().map(NodeLock::to_json).collect();
+        }
+        // Likewise for hold'em, the only game before short deck, and the
+        // raise limit, which was not configurable before.
+        if self.game != Game::Holdem {
+            v["game"] = json!(self.game.as_str());
+        }
+        if self.raise_limit != DEFAULT_RAISE_LIMIT {
+            v["raise_limit"] = json!(self.raise_limit);
+        }
+        v
+    }
+}
+
+// The config files read without `--config`, lowest priority first.
+fn candidate_paths() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(p) = user_config_path() {
+        candidates.push(p);
+    }
+    candidates.push(PathBuf::from(DIR_CONFIG_FILE));
+    candidates
+}
+
+pub fn user_config_path() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir).join(USER_CONFIG_REL_PATH));
+    }
+    env::var_os("HOME")
+        .filter(|d| !d.is_empty())
+        .map(|home| PathBuf::from(home).join(".config").join(USER_CONFIG_REL_PATH))
+}
+
+fn parse_profile(name: &str, value: &Value, path: &str) -> Result<Profile, (String, String)> {
+    let obj = value
+        .as_object()
+        .ok_or_else(|| (path.to_string(), "expected an object".to_string()))?;
+
+    let mut p = Profile {
+        name: name.to_string(),
+        description: String::new(),
+        ..Profile::default()
+    };
+
+    let mut bet_sizes = None;
+    for (key, v) in obj {
+        let key_path = format!("{}.{}", path, key);
+        match key.as_str() {
+            "description" => p.description = expect_str(v, &key_path)?.to_string(),
+            "pot" => p.pot = expect_positive(v, &key_path)?,
+            "effective_stack" => p.effective_stack = expect_positive(v, &key_path)?,
+            "range_ip" => p.range_ip = expect_range(v, &key_path)?,
+            "range_oop" => p.range_oop = expect_range(v, &key_path)?,
+            // Resolved below, once pot, stack and big blind are known.
+            "bet_sizes" => bet_sizes = Some((v, key_path)),
+            "allin_threshold" => {
+                let t = expect_f64(v, &key_path)?;
+                if !(t > 0.0 && t <= 1.0) {
+                    return Err((key_path, format!("must be in (0, 1], got {}", t)));
+                }
+                p.allin_threshold = t;
+            }
+            "raise_limit" => p.raise_limit = expect_count(v, &key_path)?,
+            "thread_num" => p.thread_num = expect_count(v, &key_path)?,
+            "accuracy" => p.accuracy = expect_positive(v, &key_path)?,
+            "max_iteration" => p.max_iteration = expect_count(v, &key_path)?,
+            "print_interval" => p.print_interval = expect_count(v, &key_path)?,
+            "use_isomorphism" => p.use_isomorphism = expect_bool(v, &key_path)?,
+            "big_blind" => p.big_blind = expect_positive(v, &key_path)?,
+            "locks" => p.locks = parse_locks(v, &key_path)?,
+            "game" => {
+                let game = expect_str(v, &key_path)?;
+                p.game = Game::parse(game)
+                    .ok_or_else(|| (key_path, format!("expected \"holdem\" or \"shortdeck\", got \"{}\"", game)))?;
+            }
+            _ => return Err((key_path, "unknown key".to_string())),
+        }
+    }
+    if let Some((v, key_path)) = bet_sizes {
+        parse_bet_sizes(&mut p, v, &key_path)?;
+    }
+
+    if p.game == Game::ShortDeck {
+        for (key, range) in [("range_ip", &mut p.range_ip), ("range_oop", &mut p.range_oop)] {
+            if !obj.contains_key(key) {
+                *range = in_deck_hands(range, p.game);
+            } else if let Some(hand) = range.split(',').map(str::trim).find(|t| !hand_in_deck(t, p.game)) {
+                return Err((format!("{}.{}", path, key), format!("'{}' is not a short deck hand (6 to A)", hand)));
+            }
+        }
+    }
+    Ok(p)
+}
+
+// `range` without the hands that need cards `game` does not deal.
+fn in_deck_hands(range: &str, game: Game) -> String {
+    range.split(',').map(str::trim).filter(|t| hand_in_deck(t, game)).collect::<Vec<_>>().join(",")
+}
+
+fn hand_in_deck(token: &str, game: Game) -> bool {
+    token.chars().take(2).all(|c| game.has_rank(c))
+}
+
+fn parse_locks(value: &Value, path: &str) -> Result<Vec<NodeLock>, (String, String)> {
+    let arr = value
+        .as_array()
+        .ok_or_else(|| (path.to_string(), "expected a list of {\"path\", \"strategy\"} objects".to_string()))?;
+    let mut locks = Vec::new();
+    for (i, item) in arr.iter().enumerate() {
+        let item_path = format!("{}[{}]", path, i);
+        let obj = item
+            .as_object()
+            .ok_or_else(|| (item_path.clone(), "expected an object".to_string()))?;
+        let mut lock = NodeLock { path: String::new(), strategy: Vec::new() };
+        for (key, v) in obj {
+            let key_path = format!("{}.{}", item_path, key);
+            match key.as_str() {
+                "path" => lock.path = expect_str(v, &key_path)?.trim_matches('/').to_string(),
+                "strategy" => {
+                    let actions = v
+                        .as_object()
+                        .ok_or_else(|| (key_path.clone(), "expected an object of action -> frequency".to_string()))?;
+                    for (action, f) in actions {
+                        let f_path = format!("{}.{}", key_path, action);
+                        let f = expect_f64(f, &f_path)?;
+                        if !(0.0..=1.0).contains(&f) {
+                            return Err((f_path, format!("must be in [0, 1], got {}", f)));
+                        }
+                        lock.strategy.push((action.clone(), f));
+                    }
+                }
+                _ => return Err((key_path, "unknown key (expected path or strategy)".to_string())),
+            }
+        }
+        let total: f64 = lock.strategy.iter().map(|(_, f)| f).sum();
+        if (total - 1.0).abs() > 1e-6 {
+            return Err((format!("{}.strategy", item_path), format!("frequencies must add up to 1, got {}", total)));
+        }
+        if locks.iter().any(|l: &NodeLock| l.path == lock.path) {
+            return Err((format!("{}.path", item_path), format!("'{}' is locked twice", lock.path)));
+        }
+        locks.push(lock);
+    }
+    Ok(locks)
+}
+
+// Sizes are expressions (see sizing.rs), resolved to % of pot against the
+// profile's pot and stack. Bets and donks go first, so that raises can be
+// resolved against the bet they face.
+fn parse_bet_sizes(p: &mut Profile, value: &Value, path: &str) -> Result<(), (String, String)> {
+    let obj = value
+        .as_object()
+        .ok_or_else(|| (path.to_string(), "expected an object with `ip` and/or `oop`".to_string()))?;
+    let sizing = Sizing { pot: p.pot, stack: p.effective_stack, big_blind: p.big_blind };
+
+    let mut raises = Vec::new();
+    for (player, streets) in obj {
+        let player_path = format!("{}.{}", path, player);
+        let ip = match player.as_str() {
+            "ip" => true,
+            "oop" => false,
+            _ => return Err((player_path, "unknown player (expected `ip` or `oop`)".to_string())),
+        };
+        let streets = streets
+            .as_object()
+            .ok_or_else(|| (player_path.clone(), "expected an object keyed by street".to_string()))?;
+
+        for (street, body) in streets {
+            let street_path = format!("{}.{}", player_path, street);
+            let index = ["flop", "turn", "river"]
+                .iter()
+                .position(|s| s == street)
+                .ok_or_else(|| (street_path.clone(), "unknown street (expected flop, turn or river)".to_string()))?;
+            let (sizes, raise) = parse_street_sizes(body, &street_path, &sizing, index)?;
+            // A street given in the file replaces that street entirely.
+            let target = if ip { &mut p.bet_sizes_ip } else { &mut p.bet_sizes_oop };
+            *target.street_mut(street).expect("street checked above") = sizes;
+            raises.push((ip, street.clone(), raise));
+        }
+    }
+
+    for (ip, street, raise) in raises {
+        let opponent = if ip { &p.bet_sizes_oop } else { &p.bet_sizes_ip };
+        let faced = opponent.streets().iter().find(|(s, _)| *s == street).and_then(|(_, s)| s.bet.first().or(s.donk.first()).copied());
+        let target = if ip { &mut p.bet_sizes_ip } else { &mut p.bet_sizes_oop };
+        let sizes = target.street_mut(&street).expect("street checked above");
+        for (expr, item_path) in raise {
+            match sizing.raise(expr, faced).map_err(|e| (item_path, e))? {
+                Resolved::Pct(pct) => sizes.raise.push(pct),
+                Resolved::AllIn => sizes.allin = true,
+            }
+        }
+    }
+    Ok(())
+}
+
+// The street's bets and donks resolved, and its raise expressions (with
+// their key paths) left for parse_bet_sizes.
+#[allow(clippy::type_complexity)]
+fn parse_street_sizes(
+    value: &Value,
+    path: &str,
+    sizing: &Sizing,
+    street: usize,
+) -> Result<(StreetBetSizes, Vec<(SizeExpr, String)>), (String, String)> {
+    let obj = value
+        .as_object()
+        .ok_or_else(|| (path.to_string(), "expected an object".to_string()))?;
+
+    let mut s = StreetBetSizes::default();
+    let mut raise = Vec::new();
+    for (key, v) in obj {
+        let key_path = format!("{}.{}", path, key);
+        match key.as_str() {
+            "bet" | "donk" => {
+                for (expr, item_path) in expect_sizes(v, &key_path)? {
+                    match sizing.bet(expr, street).map_err(|e| (item_path, e))? {
+                        Resolved::Pct(pct) if key == "bet" => s.bet.push(pct),
+                        Resolved::Pct(pct) => s.donk.push(pct),
+                        Resolved::AllIn => s.allin = true,
+                    }
+                }
+            }
+            "raise" => raise = expect_sizes(v, &key_path)?,
+            "allin" => s.allin |= expect_bool(v, &key_path)?,
+            _ => return Err((key_path, "unknown key (expected bet, raise, donk or allin)".to_string())),
+        }
+    }
+    Ok((s, raise))
+}
+
+fn expect_str<'a>(v: &'a Value, path: &str) -> Result<&'a str, (String, String)> {
+    v.as_str().ok_or_else(|| (path.to_string(), format!("expected a string, got {}", v)))
+}
+
+fn expect_bool(v: &Value, path: &str) -> Result<bool, (String, String)> {
+    v.as_bool().ok_or_else(|| (path.to_string(), format!("expected true or false, got {}", v)))
+}
+
+fn expect_f64(v: &Value, path: &str) -> Result<f64, (String, String)> {
+    v.as_f64().ok_or_else(|| (path.to_string(), format!("expected a number, got {}", v)))
+}
+
+fn expect_positive(v: &Value, path: &str) -> Result<f64, (String, String)> {
+    let f = expect_f64(v, path)?;
+    if f <= 0.0 {
+        return Err((path.to_string(), format!("must be greater than 0, got {}", f)));
+    }
+    Ok(f)
+}
+
+fn expect_count(v: &Value, path: &str) -> Result<u32, (String, String)> {
+    match v.as_u64() {
+        Some(n) if n >= 1 && n <= u32::MAX as u64 => Ok(n as u32),
+        _ => Err((path.to_string(), format!("expected a positive integer, got {}", v))),
+    }
+}
+
+fn expect_sizes(v: &Value, path: &str) -> Result<Vec<(SizeExpr, String)>, (String, String)> {
+    let arr = v
+        .as_array()
+        .ok_or_else(|| (path.to_string(), format!("expected a list of sizes (50, \"33%\", \"2.5x\", \"25c\", \"geo2\", \"allin\"), got {}", v)))?;
+    arr.iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let item_path = format!("{}[{}]", path, i);
+            SizeExpr::parse(item).map(|expr| (expr, item_path.clone())).map_err(|e| (item_path, e))
+        })
+        .collect()
+}
+
+// Ranges use the solver's own syntax: "AA:1.0,AKs:0.5,KQo" (weight optional).
+fn expect_range(v: &Value, path: &str) -> Result<String, (String, String)> {
+    let range = expect_str(v, path)?;
+    let mut tokens = 0;
+    for token in range.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        tokens += 1;
+        let mut parts = token.split(':');
+        let hand = parts.next().unwrap_or("");
+        if !is_hand_class(hand) {
+            return Err((path.to_string(), format!("'{}' is not a hand like AA, AKs or T9o", token)));
+        }
+        if let Some(weight) = parts.next() {
+            match weight.parse::<f64>() {
+                Ok(w) if (0.0..=1.0).contains(&w) => {}
+                _ => return Err((path.to_string(), format!("'{}' has a weight outside 0..1", token))),
+            }
+        }
+        if parts.next().is_some() {
+            return Err((path.to_string(), format!("'{}' has more than one ':'", token)));
+        }
+    }
+    if tokens == 0 {
+        return Err((path.to_string(), "range is empty".to_string()));
+    }
+    Ok(range.to_string())
+}
+
+fn is_hand_class(hand: &str) -> bool {
+    let chars: Vec<char> = hand.chars().collect();
+    let is_rank = |c: char| RANK_ORDER.contains(c);
+    match chars.len() {
+        2 => is_rank(chars[0]) && chars[0] == chars[1],
+        3 => is_rank(chars[0]) && is_rank(chars[1]) && chars[0] != chars[1] && (chars[2] == 's' || chars[2] == 'o'),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A scratch directory per test, so tests running in parallel don't share
+    // files.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("texas_solver_tui-config-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(dir: &Path, file: &str, body: Value) -> PathBuf {
+        let path = dir.join(file);
+        fs::write(&path, body.to_string()).unwrap();
+        path
+    }
+
+    fn merge_error(root: Value) -> (String, String) {
+        Config::default().merge_value(&root, Path::new(".")).unwrap_err()
+    }
+
+    #[test]
+    fn profile_round_trips_through_json() {
+        let body = json!({
+            "description": "SRP, 3 sizes",
+            "pot": 60,
+            "effective_stack": 170,
+            "range_ip": "AA,KK:0.5,AKs",
+            "range_oop": "QQ,JJ,AQo:0.25",
+            "bet_sizes": {
+                "ip": { "flop": { "bet": ["33%", 75], "raise": ["3x"] }, "river": { "bet": [100], "allin": true } },
+                "oop": { "turn": { "bet": [50], "donk": [25] } }
+            },
+            "raise_limit": 2,
+            "thread_num": 4,
+            "locks": [{ "path": "CHECK", "strategy": { "CHECK": 0.25, "BET 20.000000": 0.75 } }]
+        });
+        let profile = parse_profile("srp", &body, "profiles.srp").unwrap();
+        assert_eq!(profile.bet_sizes_ip.flop.bet, vec![33.0, 75.0]);
+        assert_eq!(profile.bet_sizes_oop.turn.donk, vec![25.0]);
+
+        let back = Profile::from_json("srp", &profile.to_json()).unwrap();
+        assert_eq!(back, profile);
+        assert_eq!(Profile::from_json(DEFAULT_PROFILE, &Profile::default().to_json()).unwrap(), Profile::default());
+    }
+
+    #[test]
+    fn bad_values_name_their_key_path() {
+        let (key, message) = merge_error(json!({
+            "profiles": { "x": { "bet_sizes": { "ip": { "flop": { "bet": [33, "lots"] } } } } }
+        }));
+        assert_eq!(key, "profiles.x.bet_sizes.ip.flop.bet[1]");
+        assert!(!message.is_empty());
+
+        let (key, message) = merge_error(json!({ "profiles": { "x": { "range_oop": "AA,KQz" } } }));
+        assert_eq!(key, "profiles.x.range_oop");
+        assert!(message.contains("KQz"), "{}", message);
+
+        let (key, _) = merge_error(json!({ "profiles": { "x": { "range_ip": "AA:1.5" } } }));
+        assert_eq!(key, "profiles.x.range_ip");
+
+        let (key, _) = merge_error(json!({ "profiles": { "x": { "bet_sizes": { "oop": { "turn": { "raise": ["3x", -1] } } } } } }));
+        assert_eq!(key, "profiles.x.bet_sizes.oop.turn.raise[1]");
+    }
+
+    #[test]
+    fn config_error_reports_file_and_key() {
+        let dir = scratch_dir("error");
+        let path = write(&dir, "bad.json", json!({ "profiles": { "x": { "range_ip": "" } } }));
+        let err = Config::default().merge_file(&path).unwrap_err();
+        let err = err.downcast_ref::<ConfigError>().expect("a ConfigError");
+        assert_eq!(err.file, path);
+        assert_eq!(err.key, "profiles.x.range_ip");
+        assert!(err.to_string().contains("`profiles.x.range_ip`"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn later_files_override_earlier_ones() {
+        let dir = scratch_dir("order");
+        let user = write(&dir, "user.json", json!({
+            "history_trees": 10,
+            "dump_evs": true,
+            "default_profile": "mine",
+            "profiles": { "mine": { "pot": 10 } }
+        }));
+        let local = write(&dir, "texas_solver_tui.json", json!({
+            "history_trees": 20,
+            "profiles": { "mine": { "pot": 20 } }
+        }));
+        let explicit = write(&dir, "explicit.json", json!({ "history_trees": 30 }));
+        let missing = dir.join("missing.json");
+
+        // Nothing found: the built-in defaults.
+        let config = Config::load_from(std::slice::from_ref(&missing), None).unwrap();
+        assert_eq!(config.history_trees, DEFAULT_HISTORY_TREES);
+        assert!(config.sources.is_empty());
+
+        let config = Config::load_from(&[user.clone(), missing, local.clone()], None).unwrap();
+        assert_eq!(config.history_trees, 20);
+        assert_eq!(config.profile(None).unwrap().pot, 20.0);
+        // Keys the later file leaves out keep the earlier file's value.
+        assert!(config.solver.dump_evs);
+        assert_eq!(config.sources, vec![user.clone(), local.clone()]);
+
+        let config = Config::load_from(&[user.clone(), local.clone()], Some(&explicit)).unwrap();
+        assert_eq!(config.history_trees, 30);
+        assert_eq!(config.profile(None).unwrap().pot, 20.0);
+        assert_eq!(config.sources, vec![user, local, explicit]);
+
+        assert!(Config::load_from(&[], Some(&dir.join("nope.json"))).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn user_file_is_read_before_the_directory_file() {
+        let candidates = candidate_paths();
+        assert_eq!(candidates.last(), Some(&PathBuf::from(DIR_CONFIG_FILE)));
+        if let Some(user) = user_config_path() {
+            assert_eq!(candidates, vec![user, PathBuf::from(DIR_CONFIG_FILE)]);
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+
+use crate::json_out::action::is_aggressive;
+use crate::json_out::parse::{node_strategies, range_frequencies};
+
+// Comparing two solved trees, typically the same spot before and after a
+// change to bet sizes or ranges.
+//
+// Nodes are aligned by their path from the root: action children by key
+// ("BET 25.000000"), chance children by the dealt card. A subtree only one
+// side has is listed once, by its root. At an aligned node actions are
+// matched by label; an action only one side has counts as 0% on the other,
+// so a changed bet size shows up as one size vanishing and another appearing.
+//
+// A combo's change is the total variation distance between its two
+// strategies: half the summed absolute frequency differences, 0 (same) to 1
+// (no overlap).
+
+// One tree and, when known, the ranges it was solved with (hand class ->
+// weight) to weight combos by in overall frequencies.
+pub struct Side<'a> {
+    pub tree: &'a Value,
+    pub range_oop: Option<HashMap<String, f64>>,
+    pub range_ip: Option<HashMap<String, f64>>,
+}
+
+impl Side<'_> {
+    fn weights(&self, player: &str) -> Option<&HashMap<String, f64>> {
+        if player == "OOP" { self.range_oop.as_ref() } else { self.range_ip.as_ref() }
+    }
+}
+
+pub struct NodeDiff {
+    pub path: String,
+    pub player: &'static str,
+    // Union of both sides' actions, A's order first.
+    pub actions: Vec<String>,
+    pub before: Vec<f64>,
+    pub after: Vec<f64>,
+}
+
+impl NodeDiff {
+    // Largest single-action change, in frequency (0..1).
+    pub fn max_change(&self) -> f64 {
+        self.before.iter().zip(&self.after).map(|(b, a)| (a - b).abs()).fold(0.0, f64::max)
+    }
+
+    // Change in total bet/raise frequency, which survives a bet size change.
+    pub fn aggression_change(&self) -> f64 {
+        self.actions
+            .iter()
+            .zip(self.before.iter().zip(&self.after))
+            .filter(|(a, _)| is_aggressive(a))
+            .map(|(_, (b, a))| a - b)
+            .sum()
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "path": self.path,
+            "player": self.player,
+            "actions": actions_json(&self.actions, &self.before, &self.after),
+            "max_change": self.max_change(),
+            "aggression_change": self.aggression_change(),
+        })
+    }
+}
+
+pub struct ComboDiff {
+    pub path: String,
+    pub player: &'static str,
+    pub combo: String,
+    pub actions: Vec<String>,
+    pub before: Vec<f64>,
+    pub after: Vec<f64>,
+    pub distance: f64,
+}
+
+impl ComboDiff {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "path": self.path,
+            "player": self.player,
+            "combo": self.combo,
+            "distance": self.distance,
+            "actions": actions_json(&self.actions, &self.before, &self.after),
+        })
+    }
+}
+
+fn actions_json(actions: &[String], before: &[f64], after: &[f64]) -> Vec<Value> {
+    actions
+        .iter()
+        .zip(before.iter().zip(after))
+        .map(|(action, (b, a))| json!({ "action": action, "before": b, "after": a, "change": a - b }))
+        .collect()
+}
+
+pub struct TreeDiff {
+    // Every aligned action node, largest change first.
+    pub nodes: Vec<NodeDiff>,
+    // The combos that changed most over all nodes, largest first.
+    pub combos: Vec<ComboDiff>,
+    pub only_in_a: Vec<String>,
+    pub only_in_b: Vec<String>,
+}
+
+pub fn diff_trees(a: &Side, b: &Side, top_combos: usize) -> TreeDiff {
+    let mut diff = TreeDiff { nodes: Vec::new(), combos: Vec::new(), only_in_a: Vec::new(), only_in_b: Vec::new() };
+    walk(a, b, a.tree, b.tree, &mut Vec::new(), top_combos, &mut diff);
+    diff.nodes.sort_by(|x, y| y.max_change().total_cmp(&x.max_change()).then_with(|| x.path.cmp(&y.path)));
+    sort_combos(&mut diff.combos, top_combos);
+    diff
+}
+
+fn walk(
+    a: &Side,
+    b: &Side,
+    node_a: &Value,
+    node_b: &Value,
+    path: &mut Vec<String>,
+    top_combos: usize,
+    diff: &mut TreeDiff,
+) {
+    compare_node(a, b, node_a, node_b, &path.join("/"), top_combos, diff);
+
+    for key in ["childrens", "dealcards"] {
+        let empty = serde_json::Map::new();
+        let children_a = node_a.get(key).and_then(Value::as_object).unwrap_or(&empty);
+        let children_b = node_b.get(key).and_then(Value::as_object).unwrap_or(&empty);
+        for (child, sub_a) in children_a {
+            path.push(child.clone());
+            match children_b.get(child) {
+                Some(sub_b) => walk(a, b, sub_a, sub_b, path, top_combos, diff),
+                None => diff.only_in_a.push(path.join("/")),
+            }
+            path.pop();
+        }
+        for child in children_b.keys().filter(|k| !children_a.contains_key(*k)) {
+            path.push(child.clone());
+            diff.only_in_b.push(path.join("/"));
+            path.pop();
+        }
+    }
+}
+
+fn compare_node(a: &Side, b: &Side, node_a: &Value, node_b: &Value, path: &str, top_combos: usize, diff: &mut TreeDiff) {
+    let (Some(sa), Some(sb)) = (node_strategies(node_a), node_strategies(node_b)) else {
+        return;
+    };
+    let player = if node_a["player"].as_u64() == Some(1) { "OOP" } else { "IP" };
+
+    let mut actions = sa.actions.clone();
+    actions.extend(sb.actions.iter().filter(|x| !sa.actions.contains(x)).cloned());
+    // Frequencies of one side laid out on the union of actions.
+    let align = |side_actions: &[String], probs: &[f64]| -> Vec<f64> {
+        actions
+            .iter()
+            .map(|x| side_actions.iter().position(|s| s == x).and_then(|i| probs.get(i)).copied().unwrap_or(0.0))
+            .collect()
+    };
+
+    diff.nodes.push(NodeDiff {
+        path: path.to_string(),
+        player,
+        before: align(&sa.actions, &range_frequencies(&sa, a.weights(player))),
+        after: align(&sb.actions, &range_frequencies(&sb, b.weights(player))),
+        actions: actions.clone(),
+    });
+
+    let combos_b: HashMap<&str, &Vec<f64>> = sb.combos.iter().map(|(c, p)| (c.as_str(), p)).collect();
+    for (combo, probs_a) in &sa.combos {
+        let Some(probs_b) = combos_b.get(combo.as_str()) else { continue };
+        let before = align(&sa.actions, probs_a);
+        let after = align(&sb.actions, probs_b);
+        let distance = before.iter().zip(&after).map(|(x, y)| (x - y).abs()).sum::<f64>() / 2.0;
+        if distance > 0.0 {
+            diff.combos.push(ComboDiff {
+                path: path.to_string(),
+                player,
+                combo: combo.clone(),
+                actions: actions.clone(),
+                before,
+                after,
+                distance,
+            });
+        }
+    }
+    // Big trees have millions of combo strategies; only the top ones are kept.
+    if diff.combos.len() > top_combos * 8 + 1024 {
+        sort_combos(&mut diff.combos, top_combos);
+    }
+}
+
+fn sort_combos(combos: &mut Vec<ComboDiff>, top: usize) {
+    combos.sort_by(|x, y| y.distance.total_cmp(&x.distance).then_with(|| x.path.cmp(&y.path)));
+    combos.truncate(top);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(player: u64, actions: &[&str], combos: Value, children: Value) -> Value {
+        json!({
+            "node_type": "action_node",
+            "player": player,
+            "actions": actions,
+            "strategy": { "actions": actions, "strategy": combos },
+            "childrens": children,
+        })
+    }
+
+    // OOP checks or bets `size`; after a check IP checks or bets 25, with
+    // AhKd betting `ip_bet` of the time.
+    fn tree(size: &str, ip_bet: f64) -> Value {
+        let leaf = json!({ "node_type": "action_node", "player": 0, "actions": [] });
+        let after_check = node(
+            0,
+            &["CHECK", "BET 25.000000"],
+            json!({ "AhKd": [1.0 - ip_bet, ip_bet], "QsQd": [1.0, 0.0], "7c6c": [0.0, 1.0] }),
+            json!({ "CHECK": leaf.clone(), "BET 25.000000": leaf.clone() }),
+        );
+        node(
+            1,
+            &["CHECK", size],
+            json!({ "AhKd": [0.5, 0.5], "QsQd": [1.0, 0.0], "7c6c": [0.0, 1.0] }),
+            json!({ "CHECK": after_check, size: leaf }),
+        )
+    }
+
+    fn side(tree: &Value) -> Side<'_> {
+        Side { tree, range_oop: None, range_ip: None }
+    }
+
+    fn close(x: &[f64], y: &[f64]) -> bool {
+        x.len() == y.len() && x.iter().zip(y).all(|(a, b)| (a - b).abs() < 1e-9)
+    }
+
+    #[test]
+    fn subtrees_on_one_side_are_listed_by_root() {
+        let (a, b) = (tree("BET 25.000000", 0.2), tree("BET 33.000000", 0.8));
+        let diff = diff_trees(&side(&a), &side(&b), 10);
+        assert_eq!(diff.only_in_a, vec!["BET 25.000000"]);
+        assert_eq!(diff.only_in_b, vec!["BET 33.000000"]);
+
+        let same = diff_trees(&side(&a), &side(&a), 10);
+        assert!(same.only_in_a.is_empty() && same.only_in_b.is_empty() && same.combos.is_empty());
+    }
+
+    #[test]
+    fn actions_are_aligned_on_their_union() {
+        let (a, b) = (tree("BET 25.000000", 0.2), tree("BET 33.000000", 0.8));
+        let diff = diff_trees(&side(&a), &side(&b), 10);
+        let paths: Vec<&str> = diff.nodes.iter().map(|n| n.path.as_str()).collect();
+        assert_eq!(paths, vec!["", "CHECK"]);
+
+        let root = &diff.nodes[0];
+        assert_eq!(root.player, "OOP");
+        assert_eq!(root.actions, vec!["CHECK", "BET 25.000000", "BET 33.000000"]);
+        // Equal combo weights: half the range bets either way.
+        assert!(close(&root.before, &[0.5, 0.5, 0.0]), "{:?}", root.before);
+        assert!(close(&root.after, &[0.5, 0.0, 0.5]), "{:?}", root.after);
+        assert!(root.aggression_change().abs() < 1e-9);
+
+        let check = &diff.nodes[1];
+        assert_eq!(check.player, "IP");
+        assert_eq!(check.actions, vec!["CHECK", "BET 25.000000"]);
+        assert!((check.max_change() - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn combos_by_total_variation_distance() {
+        let (a, b) = (tree("BET 25.000000", 0.2), tree("BET 33.000000", 0.8));
+        let diff = diff_trees(&side(&a), &side(&b), 10);
+        let combos: Vec<(&str, &str, f64)> = diff.combos.iter().map(|c| (c.path.as_str(), c.combo.as_str(), c.distance)).collect();
+        assert_eq!(combos.len(), 3, "{:?}", combos);
+        // 7c6c moves all its bets to the new size; QsQd never changes.
+        assert_eq!((combos[0].0, combos[0].1), ("", "7c6c"));
+        assert!((combos[0].2 - 1.0).abs() < 1e-9);
+        assert_eq!((combos[1].0, combos[1].1), ("CHECK", "AhKd"));
+        assert!((combos[1].2 - 0.6).abs() < 1e-9);
+        assert_eq!((combos[2].0, combos[2].1), ("", "AhKd"));
+        assert!((combos[2].2 - 0.5).abs() < 1e-9);
+        assert!(close(&diff.combos[2].before, &[0.5, 0.5, 0.0]));
+        assert!(close(&diff.combos[2].after, &[0.5, 0.0, 0.5]));
+    }
+
+    #[test]
+    fn keeps_only_the_top_combos() {
+        let (a, b) = (tree("BET 25.000000", 0.2), tree("BET 33.000000", 0.8));
+        let diff = diff_trees(&side(&a), &side(&b), 2);
+        let combos: Vec<&str> = diff.combos.iter().map(|c| c.combo.as_str()).collect();
+        assert_eq!(combos, vec!["7c6c", "AhKd"]);
+        assert_eq!(diff.combos[1].path, "CHECK");
+        assert!(diff_trees(&side(&a), &side(&b), 0).combos.is_empty());
+    }
+}
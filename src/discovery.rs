@@ -0,0 +1,314 @@
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config::SolverSettings;
+
+// Finding the console_solver binary and the resources directory it needs.
+//
+// Binary lookup order (first hit wins):
+//   1. `solver_path` in the config file
+//   2. $TEXAS_SOLVER_BIN
+//   3. `console_solver` on $PATH
+//   4. known bundle layouts, relative to the executable and then to the CWD
+//
+// Explicit choices (1 and 2) never fall back: a wrong path there is an error.
+// Bundle layouts built for another OS (e.g. the macOS bundle on Linux) are
+// skipped so a Linux build further down the list can still be found.
+
+pub const SOLVER_BIN_ENV: &str = "TEXAS_SOLVER_BIN";
+pub const RESOURCE_DIR_ENV: &str = "TEXAS_SOLVER_RESOURCES";
+pub const SOLVER_LIB_ENV: &str = "TEXAS_SOLVER_LIB";
+const SOLVER_BIN_NAME: &str = "console_solver";
+const KNOWN_LAYOUTS: &[&str] = &[
+    "TexasSolver-v0.2.0-MacOs/console_solver",
+    "TexasSolver-v0.2.0-Linux/console_solver",
+    "TexasSolver-v0.2.0-linux/console_solver",
+    "TexasSolver-Linux/console_solver",
+    "TexasSolver/console_solver",
+    "TexasSolver/build/console_solver",
+    "console_solver",
+];
+const RESOURCE_DIR_REL: &str = "resources";
+// Files console_solver loads from the directory given with `-r`.
+pub const HOLDEM_COMPAIRER: &str = "compairer/card5_dic_sorted.txt";
+pub const SHORTDECK_COMPAIRER: &str = "compairer/card5_dic_sorted_shortdeck.txt";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    Config,
+    Env,
+    SearchPath,
+    Layout,
+    Default,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Source::Config => "config file",
+            Source::Env => "environment variable",
+            Source::SearchPath => "PATH lookup",
+            Source::Layout => "known bundle layout",
+            Source::Default => "default location",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Found {
+    pub path: PathBuf,
+    pub source: Source,
+}
+
+// Every candidate looked at, with the reason it was rejected (None = chosen).
+#[derive(Debug, Default)]
+pub struct Report {
+    pub found: Option<Found>,
+    pub tried: Vec<(PathBuf, Option<String>)>,
+}
+
+pub fn get_exe_dir() -> PathBuf {
+    env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+pub fn find_solver(settings: &SolverSettings) -> Result<Found, Box<dyn Error>> {
+    let report = discover_solver(settings);
+    if let Some(found) = report.found {
+        return Ok(found);
+    }
+    let tried = report
+        .tried
+        .iter()
+        .map(|(p, why)| format!("  {} ({})", p.display(), why.as_deref().unwrap_or("?")))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Err(format!(
+        "could not find {} (set `solver_path` in the config or ${}); tried:\n{}",
+        SOLVER_BIN_NAME, SOLVER_BIN_ENV, tried
+    )
+    .into())
+}
+
+pub fn discover_solver(settings: &SolverSettings) -> Report {
+    let mut report = Report::default();
+
+    if let Some(p) = &settings.solver_path {
+        check_explicit(&mut report, p.clone(), Source::Config);
+        return report;
+    }
+    if let Some(p) = env::var_os(SOLVER_BIN_ENV).filter(|v| !v.is_empty()) {
+        check_explicit(&mut report, PathBuf::from(p), Source::Env);
+        return report;
+    }
+
+    if let Some(paths) = env::var_os("PATH") {
+        for dir in env::split_paths(&paths) {
+            let candidate = dir.join(SOLVER_BIN_NAME);
+            if candidate.is_file() && try_candidate(&mut report, candidate, Source::SearchPath) {
+                return report;
+            }
+        }
+    }
+
+    let mut bases = vec![get_exe_dir()];
+    if let Ok(cwd) = env::current_dir()
+        && !bases.contains(&cwd)
+    {
+        bases.push(cwd);
+    }
+    for base in &bases {
+        for layout in KNOWN_LAYOUTS {
+            let candidate = base.join(layout);
+            if !candidate.exists() {
+                report.tried.push((candidate, Some("not found".to_string())));
+                continue;
+            }
+            if try_candidate(&mut report, candidate, Source::Layout) {
+                return report;
+            }
+        }
+    }
+
+    report
+}
+
+fn check_explicit(report: &mut Report, path: PathBuf, source: Source) {
+    if !path.is_file() {
+        report.tried.push((path, Some(format!("from {}: file not found", source))));
+        return;
+    }
+    // An explicit binary is used even if it looks foreign; `doctor` will warn.
+    report.tried.push((path.clone(), None));
+    report.found = Some(Found { path, source });
+}
+
+fn try_candidate(report: &mut Report, path: PathBuf, source: Source) -> bool {
+    if let Some(why) = unusable_reason(&path) {
+        report.tried.push((path, Some(why)));
+        return false;
+    }
+    report.tried.push((path.clone(), None));
+    report.found = Some(Found { path, source });
+    true
+}
+
+fn unusable_reason(path: &Path) -> Option<String> {
+    if !path.is_file() {
+        return Some("not a file".to_string());
+    }
+    if !is_executable(path) {
+        return Some("not executable".to_string());
+    }
+    let format = binary_format(path);
+    if !format_matches_host(format) {
+        return Some(format!("{} binary, host is {}", format, env::consts::OS));
+    }
+    None
+}
+
+#[cfg(unix)]
+pub fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .map(|m| m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+pub fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+// Sniff the executable format from the magic bytes.
+pub fn binary_format(path: &Path) -> &'static str {
+    let mut magic = [0u8; 4];
+    let read = fs::File::open(path).and_then(|mut f| f.read_exact(&mut magic));
+    if read.is_err() {
+        return "unknown";
+    }
+    match magic {
+        [0x7f, b'E', b'L', b'F'] => "ELF",
+        [0xcf, 0xfa, 0xed, 0xfe] | [0xce, 0xfa, 0xed, 0xfe] | [0xca, 0xfe, 0xba, 0xbe] => "Mach-O",
+        [b'M', b'Z', _, _] => "PE",
+        [b'#', b'!', _, _] => "script",
+        _ => "unknown",
+    }
+}
+
+pub fn format_matches_host(format: &str) -> bool {
+    match (format, env::consts::OS) {
+        ("ELF", os) => os != "macos" && os != "windows",
+        ("Mach-O", os) => os == "macos",
+        ("PE", os) => os == "windows",
+        // Scripts and unknown formats get the benefit of the doubt.
+        _ => true,
+    }
+}
+
+// The shared library for the in-process backend: `library_path` in the config,
+// then $TEXAS_SOLVER_LIB. There is no search; the library is opt-in.
+pub fn find_library(settings: &SolverSettings) -> Result<PathBuf, Box<dyn Error>> {
+    let path = settings
+        .library_path
+        .clone()
+        .or_else(|| env::var_os(SOLVER_LIB_ENV).filter(|v| !v.is_empty()).map(PathBuf::from))
+        .ok_or_else(|| format!("backend is \"library\" but neither `library_path` nor ${} is set", SOLVER_LIB_ENV))?;
+    if !path.is_file() {
+        return Err(format!("solver library not found: {}", path.display()).into());
+    }
+    Ok(path)
+}
+
+// The directory passed to console_solver with `-r`.
+//   1. `resource_dir` in the config file
+//   2. $TEXAS_SOLVER_RESOURCES
+//   3. the first of <exe>/resources, <solver dir>/resources, ./resources that
+//      holds the hand-ranking dictionary
+//   4. <exe>/resources, even if incomplete (the solver reports what's missing)
+pub fn find_resource_dir(settings: &SolverSettings, solver: Option<&Path>) -> Found {
+    if let Some(p) = &settings.resource_dir {
+        return Found { path: p.clone(), source: Source::Config };
+    }
+    if let Some(p) = env::var_os(RESOURCE_DIR_ENV).filter(|v| !v.is_empty()) {
+        return Found { path: PathBuf::from(p), source: Source::Env };
+    }
+
+    let default = get_exe_dir().join(RESOURCE_DIR_REL);
+    let mut candidates = vec![default.clone()];
+    if let Some(dir) = solver.and_then(Path::parent) {
+        candidates.push(dir.join(RESOURCE_DIR_REL));
+    }
+    if let Ok(cwd) = env::current_dir() {
+        candidates.push(cwd.join(RESOURCE_DIR_REL));
+    }
+    for c in candidates {
+        if c.join(HOLDEM_COMPAIRER).is_file() {
+            return Found { path: c, source: Source::Layout };
+        }
+    }
+    Found { path: default, source: Source::Default }
+}
+
+// Run the binary briefly to capture its banner. console_solver has no
+// --version flag; with an unknown flag it prints its usage/banner and exits.
+// The process is killed if it is still running after `timeout`.
+pub fn version_banner(path: &Path, timeout: Duration) -> Result<String, Box<dyn Error>> {
+    let mut child = Command::new(path)
+        .arg("--help")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Read both pipes while waiting, so a banner bigger than the pipe buffer
+    // can't stall the child until the timeout.
+    let drain = |pipe: Option<Box<dyn Read + Send>>| {
+        thread::spawn(move || {
+            let mut text = String::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_string(&mut text);
+            }
+            text
+        })
+    };
+    let stdout = drain(child.stdout.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+    let stderr = drain(child.stderr.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        if child.try_wait()?.is_some() {
+            break;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            break;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    let text = stdout.join().unwrap_or_default() + &stderr.join().unwrap_or_default();
+    let banner = text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .take(3)
+        .collect::<Vec<_>>()
+        .join(" | ");
+    if banner.is_empty() {
+        Ok("(no output)".to_string())
+    } else {
+        Ok(banner)
+    }
+}
@@ -1,11 +1,24 @@
-pub fn evaluate_hand(hero_hand: &str, board: &str) -> String {
+use std::collections::HashMap;
+
+use crate::boards::Game;
+
+// Short deck (Game::ShortDeck) changes two things here: a flush beats a full
+// house, and A-6-7-8-9 is the lowest straight (9 high) in place of the wheel.
+
+pub fn evaluate_hand(hero_hand: &str, board: &str, game: Game) -> String {
+    match evaluate_hand_parts(hero_hand, board, game) {
+        Some((desc, score)) => format!("{} (Strength: {}/100)", desc, score),
+        None => "Unknown".to_string(),
+    }
+}
+
+// Same as evaluate_hand but unformatted: (description, strength 0-100).
+pub fn evaluate_hand_parts(hero_hand: &str, board: &str, game: Game) -> Option<(String, u8)> {
     let cards = parse_cards(hero_hand, board);
     if cards.is_empty() {
-        return "Unknown".to_string();
+        return None;
     }
-
-    let (desc, score) = calculate_strength(&cards);
-    format!("{} (Strength: {}/100)", desc, score)
+    Some(calculate_strength(&cards, game))
 }
 
 fn parse_cards(hero_hand: &str, board: &str) -> Vec<(usize, usize)> {
@@ -23,7 +36,7 @@ fn parse_cards(hero_hand: &str, board: &str) -> Vec<(usize, usize)> {
 
 fn parse_single_card(c: &str) -> Option<(usize, usize)> {
     if c.len() != 2 { return None; }
-    let r_char = c.chars().nth(0)?;
+    let r_char = c.chars().next()?;
     let s_char = c.chars().nth(1)?;
     
     let rank = match r_char {
@@ -40,7 +53,12 @@ fn parse_single_card(c: &str) -> Option<(usize, usize)> {
     Some((rank, suit))
 }
 
-fn calculate_strength(cards: &[(usize, usize)]) -> (String, u8) {
+fn calculate_strength(cards: &[(usize, usize)], game: Game) -> (String, u8) {
+    let (flush_band, full_house_band) = match game {
+        Game::Holdem => (75, 80),
+        Game::ShortDeck => (85, 75),
+    };
+
     // 1. Check Flush
     let mut suits = [0; 4];
     for &(_, s) in cards {
@@ -57,11 +75,11 @@ fn calculate_strength(cards: &[(usize, usize)]) -> (String, u8) {
         let high = flush_ranks[0];
         
         // Check Straight Flush
-        if is_straight(&flush_ranks) {
+        if check_straight(&flush_ranks, game).is_some() {
              return ("Straight Flush".to_string(), 95 + (high as u8 * 5 / 13));
         }
         
-        return (format!("Flush ({})", suit_name(s)), 75 + (high as u8 * 5 / 13));
+        return (format!("Flush ({})", suit_name(s)), flush_band + (high as u8 * 5 / 13));
     }
 
     // 2. Check Straight
@@ -69,7 +87,7 @@ fn calculate_strength(cards: &[(usize, usize)]) -> (String, u8) {
     ranks.sort_by(|a, b| b.cmp(a));
     ranks.dedup();
     
-    if let Some(high) = check_straight(&ranks) {
+    if let Some(high) = check_straight(&ranks, game) {
         return ("Straight".to_string(), 70 + (high as u8 * 5 / 13));
     }
 
@@ -99,7 +117,7 @@ fn calculate_strength(cards: &[(usize, usize)]) -> (String, u8) {
     
     if !trips.is_empty() && (!pairs.is_empty() || trips.len() > 1) {
         let t = trips[0];
-        return ("Full House".to_string(), 80 + (t as u8 * 10 / 13));
+        return ("Full House".to_string(), full_house_band + (t as u8 * 10 / 13));
     }
     
     if let Some(&t) = trips.first() {
@@ -121,11 +139,7 @@ fn calculate_strength(cards: &[(usize, usize)]) -> (String, u8) {
     (format!("High Card ({})", rank_name(*max_rank)), (*max_rank as u8 * 20 / 13))
 }
 
-fn is_straight(ranks: &[usize]) -> bool {
-    check_straight(ranks).is_some()
-}
-
-fn check_straight(ranks: &[usize]) -> Option<usize> {
+fn check_straight(ranks: &[usize], game: Game) -> Option<usize> {
     // Ranks are sorted descending and unique
     if ranks.len() < 5 { return None; }
     
@@ -142,8 +156,9 @@ fn check_straight(ranks: &[usize]) -> Option<usize> {
     }
     
     // Wheel
-    if ranks.contains(&12) && ranks.contains(&0) && ranks.contains(&1) && ranks.contains(&2) && ranks.contains(&3) {
-        return Some(3); // 5 high straight
+    let low = game.lowest_rank();
+    if ranks.contains(&12) && (low..low + 4).all(|r| ranks.contains(&r)) {
+        return Some(low + 3); // 5 high straight, 9 high in short deck
     }
     
     None
@@ -163,3 +178,274 @@ fn rank_name(r: usize) -> &'static str {
         _ => "?",
     }
 }
+
+type Card = (usize, usize);
+
+// Comparable value of the best five-card hand among `cards` (5 to 7 cards):
+// the category's strength in `game` in the top bits, the category itself
+// (0 = high card .. 8 = straight flush) below, then up to five tie-break
+// ranks, so a larger value always wins.
+pub fn hand_rank(cards: &[Card], game: Game) -> u32 {
+    let value = category_rank(cards, game);
+    let category = value >> 20;
+    let order = match (game, category) {
+        (Game::ShortDeck, 5) => 6,
+        (Game::ShortDeck, 6) => 5,
+        _ => category,
+    };
+    (order << 24) | value
+}
+
+fn category_rank(cards: &[Card], game: Game) -> u32 {
+    let mut counts = [0u8; 13];
+    let mut suit_masks = [0u16; 4];
+    let mut rank_mask = 0u16;
+    for &(r, s) in cards {
+        counts[r] += 1;
+        suit_masks[s] |= 1 << r;
+        rank_mask |= 1 << r;
+    }
+
+    if let Some(&mask) = suit_masks.iter().find(|m| m.count_ones() >= 5) {
+        if let Some(high) = straight_high(mask, game) {
+            return encode(8, &[high]);
+        }
+        let top: Vec<usize> = (0..13).rev().filter(|r| mask & (1 << r) != 0).take(5).collect();
+        return encode(5, &top);
+    }
+
+    // Ranks grouped by count, highest rank first within each group.
+    let of_count = |n: u8| -> Vec<usize> { (0..13).rev().filter(|&r| counts[r] == n).collect() };
+    let quads = of_count(4);
+    let trips = of_count(3);
+    let pairs = of_count(2);
+    let kickers = |exclude: &[usize], n: usize| -> Vec<usize> {
+        (0..13).rev().filter(|r| counts[*r] > 0 && !exclude.contains(r)).take(n).collect()
+    };
+
+    if let Some(&q) = quads.first() {
+        return encode(7, &[&[q][..], &kickers(&[q], 1)].concat());
+    }
+    if let Some(&t) = trips.first() {
+        // The pair of a full house may come from a second set of trips.
+        let pair = trips.get(1).copied().into_iter().chain(pairs.iter().copied()).max();
+        if let Some(p) = pair {
+            return encode(6, &[t, p]);
+        }
+    }
+    if let Some(high) = straight_high(rank_mask, game) {
+        return encode(4, &[high]);
+    }
+    if let Some(&t) = trips.first() {
+        return encode(3, &[&[t][..], &kickers(&[t], 2)].concat());
+    }
+    if pairs.len() >= 2 {
+        let (p1, p2) = (pairs[0], pairs[1]);
+        return encode(2, &[&[p1, p2][..], &kickers(&[p1, p2], 1)].concat());
+    }
+    if let Some(&p) = pairs.first() {
+        return encode(1, &[&[p][..], &kickers(&[p], 3)].concat());
+    }
+    encode(0, &kickers(&[], 5))
+}
+
+const CATEGORY_NAMES: [&str; 9] = [
+    "high card",
+    "pair",
+    "two pair",
+    "three of a kind",
+    "straight",
+    "flush",
+    "full house",
+    "four of a kind",
+    "straight flush",
+];
+
+// Category of hero's best five cards with the board ("pair", "flush", ...).
+pub fn hand_category(hero_hand: &str, board: &str, game: Game) -> Option<&'static str> {
+    let cards = parse_cards(hero_hand, board);
+    if cards.len() < 5 {
+        return None;
+    }
+    CATEGORY_NAMES.get((hand_rank(&cards, game) >> 20 & 0xf) as usize).copied()
+}
+
+fn encode(category: u32, ranks: &[usize]) -> u32 {
+    let mut value = category;
+    for i in 0..5 {
+        value = (value << 4) | ranks.get(i).map(|&r| r as u32 + 1).unwrap_or(0);
+    }
+    value
+}
+
+// Highest card of a straight in a 13-bit rank mask (A-5 counts as 5 high,
+// A-6-7-8-9 as 9 high in short deck).
+fn straight_high(mask: u16, game: Game) -> Option<usize> {
+    for high in (4..13).rev() {
+        let run = 0b11111u16 << (high - 4);
+        if mask & run == run {
+            return Some(high);
+        }
+    }
+    let low = game.lowest_rank();
+    let wheel = (1 << 12) | (0b1111 << low);
+    if mask & wheel == wheel { Some(low + 3) } else { None }
+}
+
+// Every concrete combo of a hand class ("AA", "AKs", "AKo").
+pub fn class_combos(class: &str) -> Vec<[Card; 2]> {
+    let chars: Vec<char> = class.chars().collect();
+    let rank = |c: char| parse_single_card(&format!("{}h", c)).map(|(r, _)| r);
+    let (Some(r1), Some(r2)) = (chars.first().and_then(|&c| rank(c)), chars.get(1).and_then(|&c| rank(c))) else {
+        return Vec::new();
+    };
+    let mut combos = Vec::new();
+    for s1 in 0..4 {
+        for s2 in 0..4 {
+            let keep = match chars.get(2) {
+                _ if r1 == r2 => s1 < s2,
+                Some('s') => s1 == s2,
+                Some('o') => s1 != s2,
+                _ => true,
+            };
+            if keep {
+                combos.push([(r1, s1), (r2, s2)]);
+            }
+        }
+    }
+    combos
+}
+
+// Combos of a hand class that do not use a board card.
+pub fn live_combos(class: &str, board: &str) -> usize {
+    let dead = parse_cards("", board);
+    class_combos(class).iter().filter(|combo| !combo.iter().any(|c| dead.contains(c))).count()
+}
+
+pub struct Equity {
+    // All as fractions of 1, weighted by the range weights.
+    pub win: f64,
+    pub tie: f64,
+    pub equity: f64,
+    // Opponent combos left after removing the cards in play.
+    pub combos: usize,
+}
+
+// Hero's all-in equity against a weighted range (hand class -> weight),
+// enumerating every remaining runout of `game`'s deck exactly. `board` holds
+// 3 to 5 cards.
+pub fn equity_vs_range(hero_hand: &str, board: &str, range: &HashMap<String, f64>, game: Game) -> Option<Equity> {
+    let hero = parse_cards(hero_hand, "");
+    let board = parse_cards("", board);
+    if hero.len() != 2 || !(3..=5).contains(&board.len()) {
+        return None;
+    }
+
+    let mut dead: Vec<Card> = hero.iter().chain(board.iter()).copied().collect();
+    let (mut win, mut tie, mut total_weight, mut combos) = (0.0, 0.0, 0.0, 0);
+
+    for (class, &weight) in range {
+        if weight <= 0.0 {
+            continue;
+        }
+        for villain in class_combos(class) {
+            if villain.iter().any(|&(r, _)| r < game.lowest_rank()) {
+                continue;
+            }
+            if villain.iter().any(|c| dead.contains(c)) {
+                continue;
+            }
+            dead.extend_from_slice(&villain);
+            let deck: Vec<Card> = (game.lowest_rank()..13)
+                .flat_map(|r| (0..4).map(move |s| (r, s)))
+                .filter(|c| !dead.contains(c))
+                .collect();
+            dead.truncate(dead.len() - 2);
+
+            let (w, t, n) = runout_results(&hero, &villain, &board, &deck, game);
+            win += weight * w / n;
+            tie += weight * t / n;
+            total_weight += weight;
+            combos += 1;
+        }
+    }
+
+    if total_weight == 0.0 {
+        return None;
+    }
+    let (win, tie) = (win / total_weight, tie / total_weight);
+    Some(Equity { win, tie, equity: win + tie / 2.0, combos })
+}
+
+// (wins, ties, runouts) for hero vs one villain combo over all ways to
+// complete the board from `deck`.
+fn runout_results(hero: &[Card], villain: &[Card; 2], board: &[Card], deck: &[Card], game: Game) -> (f64, f64, f64) {
+    let mut hero_cards: Vec<Card> = hero.iter().chain(board).copied().collect();
+    let mut villain_cards: Vec<Card> = villain.iter().chain(board).copied().collect();
+    let (mut wins, mut ties, mut runouts) = (0.0, 0.0, 0.0);
+
+    let mut score = |extra: &[Card]| {
+        hero_cards.extend_from_slice(extra);
+        villain_cards.extend_from_slice(extra);
+        let (h, v) = (hand_rank(&hero_cards, game), hand_rank(&villain_cards, game));
+        hero_cards.truncate(hero_cards.len() - extra.len());
+        villain_cards.truncate(villain_cards.len() - extra.len());
+        if h > v {
+            wins += 1.0;
+        } else if h == v {
+            ties += 1.0;
+        }
+        runouts += 1.0;
+    };
+
+    match board.len() {
+        5 => score(&[]),
+        4 => deck.iter().for_each(|&c| score(&[c])),
+        _ => {
+            for (i, &a) in deck.iter().enumerate() {
+                for &b in &deck[i + 1..] {
+                    score(&[a, b]);
+                }
+            }
+        }
+    }
+    (wins, ties, runouts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rank(cards: &str, game: Game) -> u32 {
+        hand_rank(&parse_cards("", cards), game)
+    }
+
+    fn category(cards: &str, game: Game) -> u32 {
+        rank(cards, game) >> 20 & 0xf
+    }
+
+    #[test]
+    fn short_deck_flush_beats_full_house() {
+        let flush = "AhKhQh9h7h";
+        let full_house = "6c6d6s7c7d";
+        assert!(rank(flush, Game::ShortDeck) > rank(full_house, Game::ShortDeck));
+        assert!(rank(flush, Game::Holdem) < rank(full_house, Game::Holdem));
+    }
+
+    #[test]
+    fn short_deck_ace_six_to_nine_is_a_nine_high_straight() {
+        let low = "As6h7d8c9h";
+        assert_eq!(category(low, Game::ShortDeck), 4);
+        assert!(rank(low, Game::ShortDeck) < rank("6h7d8c9hTs", Game::ShortDeck));
+        assert!(rank(low, Game::ShortDeck) > rank("9s9h9dAcKh", Game::ShortDeck));
+        assert_eq!(category(low, Game::Holdem), 0);
+    }
+
+    #[test]
+    fn holdem_wheel_is_a_five_high_straight() {
+        let wheel = "As2h3d4c5h";
+        assert_eq!(category(wheel, Game::Holdem), 4);
+        assert!(rank(wheel, Game::Holdem) < rank("2h3d4c5h6s", Game::Holdem));
+        assert!(rank(wheel, Game::Holdem) > rank("5s5h5dAcKh", Game::Holdem));
+    }
+}
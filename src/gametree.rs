@@ -0,0 +1,268 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+// TexasSolver's .km game trees (resources/gametree/*.km) are mind-map files:
+// `{"root": node}`, where every node has display text under `data`, its
+// children under `children`, their edge labels in `children_actions`
+// ("check", "bet_4", "raise_8", "call", "fold", "dealcard") and the game
+// state under `meta`:
+//   node_type  "Action" | "Chance" | "Showdown" | "Terminal"
+//   round      "preflop" | "flop" | "turn" | "river"
+//   player     the player to act, 1 = OOP, 0 = IP
+//   pot        chips in the pot
+//   payoffs    at showdowns (and all-in deals): chips each player wins when
+//              player 0 wins, player 1 wins, or they tie
+//   payoff     at folds: chips each player wins
+// Everything else (`data`, colours, `resource`) is for the mind-map viewer
+// and ignored.
+
+pub const ROUNDS: [&str; 4] = ["preflop", "flop", "turn", "river"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NodeType {
+    Action,
+    Chance,
+    Showdown,
+    Terminal,
+}
+
+impl NodeType {
+    pub const ALL: [NodeType; 4] = [NodeType::Action, NodeType::Chance, NodeType::Showdown, NodeType::Terminal];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeType::Action => "Action",
+            NodeType::Chance => "Chance",
+            NodeType::Showdown => "Showdown",
+            NodeType::Terminal => "Terminal",
+        }
+    }
+}
+
+// Chips won by [player 0, player 1].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Payoff {
+    Showdown { ip_wins: [f64; 2], oop_wins: [f64; 2], tie: [f64; 2] },
+    Fold([f64; 2]),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameNode {
+    pub node_type: NodeType,
+    // Index into ROUNDS.
+    pub round: usize,
+    pub player: u8,
+    pub pot: f64,
+    pub payoff: Option<Payoff>,
+    // (edge label, child), in file order.
+    pub children: Vec<(String, GameNode)>,
+}
+
+impl GameNode {
+    pub fn round_name(&self) -> &'static str {
+        ROUNDS[self.round]
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+pub fn load(path: &Path) -> Result<GameNode, Box<dyn Error>> {
+    let text = fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    let value: Value = serde_json::from_str(&text).map_err(|e| format!("{} is not JSON: {}", path.display(), e))?;
+    let root = value.get("root").ok_or_else(|| format!("{} has no \"root\" node", path.display()))?;
+    parse_node(root, "root").map_err(|e| format!("{}: {}", path.display(), e).into())
+}
+
+// `at` is the line of edge labels leading here, for error messages.
+fn parse_node(v: &Value, at: &str) -> Result<GameNode, String> {
+    let meta = v.get("meta").ok_or_else(|| format!("{}: no meta", at))?;
+    let node_type = match meta["node_type"].as_str() {
+        Some("Action") => NodeType::Action,
+        Some("Chance") => NodeType::Chance,
+        Some("Showdown") => NodeType::Showdown,
+        Some("Terminal") => NodeType::Terminal,
+        other => return Err(format!("{}: unknown meta.node_type {:?}", at, other.unwrap_or("(missing)"))),
+    };
+    let round = meta["round"]
+        .as_str()
+        .and_then(|r| ROUNDS.iter().position(|x| *x == r))
+        .ok_or_else(|| format!("{}: unknown meta.round {}", at, meta["round"]))?;
+    let pot = meta["pot"].as_f64().ok_or_else(|| format!("{}: meta.pot is not a number", at))?;
+    let player = meta["player"].as_u64().filter(|p| *p <= 1).unwrap_or(0) as u8;
+
+    let payoff = if let Some(p) = meta.get("payoffs") {
+        let pair = |key: &str| chip_pair(&p[key]).ok_or_else(|| format!("{}: meta.payoffs.{} is not a pair of numbers", at, key));
+        Some(Payoff::Showdown { ip_wins: pair("0")?, oop_wins: pair("1")?, tie: pair("tie")? })
+    } else if let Some(p) = meta.get("payoff") {
+        Some(Payoff::Fold(chip_pair(p).ok_or_else(|| format!("{}: meta.payoff is not a pair of numbers", at))?))
+    } else {
+        None
+    };
+
+    let empty = Vec::new();
+    let children = v["children"].as_array().unwrap_or(&empty);
+    let labels = v["children_actions"].as_array().unwrap_or(&empty);
+    if children.len() != labels.len() && !(node_type == NodeType::Chance && labels.len() == 1) {
+        return Err(format!("{}: {} children but {} children_actions", at, children.len(), labels.len()));
+    }
+    let children = children
+        .iter()
+        .enumerate()
+        .map(|(i, child)| {
+            // Chance nodes label their single edge once, however many cards.
+            let label = labels.get(i).or(labels.first()).and_then(Value::as_str).unwrap_or("?").to_string();
+            let child = parse_node(child, &format!("{}/{}", at, label))?;
+            Ok((label, child))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(GameNode { node_type, round, player, pot, payoff, children })
+}
+
+fn chip_pair(v: &Value) -> Option<[f64; 2]> {
+    let arr = v.as_array()?;
+    match arr.as_slice() {
+        [a, b] => Some([a.as_f64()?, b.as_f64()?]),
+        _ => None,
+    }
+}
+
+pub struct TreeSummary {
+    // (round index, type) -> nodes.
+    pub counts: BTreeMap<(usize, NodeType), usize>,
+    pub nodes: usize,
+    // Leaves: each is one line of play to the end of the hand.
+    pub lines: usize,
+    // Most actions along one line (deals do not count).
+    pub max_depth: usize,
+    // Final pot -> lines ending with it, smallest pot first.
+    pub final_pots: Vec<(f64, usize)>,
+    // The line ending with the largest pot, and the pot after each step.
+    pub largest: Vec<(String, f64)>,
+}
+
+pub fn summarize(root: &GameNode) -> TreeSummary {
+    let mut summary = TreeSummary {
+        counts: BTreeMap::new(),
+        nodes: 0,
+        lines: 0,
+        max_depth: 0,
+        final_pots: Vec::new(),
+        largest: Vec::new(),
+    };
+    let mut line = Vec::new();
+    walk(root, &mut line, 0, &mut summary);
+    summary.final_pots.sort_by(|a, b| a.0.total_cmp(&b.0));
+    summary
+}
+
+fn walk(node: &GameNode, line: &mut Vec<(String, f64)>, depth: usize, summary: &mut TreeSummary) {
+    *summary.counts.entry((node.round, node.node_type)).or_default() += 1;
+    summary.nodes += 1;
+    if node.is_leaf() {
+        summary.lines += 1;
+        summary.max_depth = summary.max_depth.max(depth);
+        match summary.final_pots.iter_mut().find(|(pot, _)| *pot == node.pot) {
+            Some((_, n)) => *n += 1,
+            None => summary.final_pots.push((node.pot, 1)),
+        }
+        if summary.largest.last().is_none_or(|(_, pot)| node.pot > *pot) {
+            summary.largest = line.clone();
+        }
+        return;
+    }
+    let deal = node.node_type == NodeType::Chance;
+    for (label, child) in &node.children {
+        line.push((action_label(label, child), child.pot));
+        walk(child, line, if deal { depth } else { depth + 1 }, summary);
+        line.pop();
+    }
+}
+
+// "bet_4" -> "bet 4", "dealcard" -> "<round> card".
+pub fn action_label(label: &str, child: &GameNode) -> String {
+    if label == "dealcard" {
+        return format!("{} card", child.round_name());
+    }
+    label.replace('_', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> GameNode {
+        let value: Value = serde_json::from_str(text).unwrap();
+        parse_node(&value["root"], "root").unwrap()
+    }
+
+    fn count(summary: &TreeSummary, round: &str, node_type: NodeType) -> usize {
+        let round = ROUNDS.iter().position(|r| *r == round).unwrap();
+        summary.counts.get(&(round, node_type)).copied().unwrap_or(0)
+    }
+
+    #[test]
+    fn summarizes_a_river_tree() {
+        let root = parse(include_str!("../resources/gametree/simple_part_tree_depthinf.km"));
+        assert_eq!(root.children.iter().map(|(l, _)| l.as_str()).collect::<Vec<_>>(), vec!["check", "bet_2"]);
+        let summary = summarize(&root);
+        assert_eq!(summary.nodes, 9);
+        assert_eq!(count(&summary, "river", NodeType::Action), 4);
+        assert_eq!(count(&summary, "river", NodeType::Showdown), 3);
+        assert_eq!(count(&summary, "river", NodeType::Terminal), 2);
+        assert_eq!(summary.lines, 5);
+        // check, bet, call
+        assert_eq!(summary.max_depth, 3);
+        assert_eq!(summary.final_pots, vec![(4.0, 1), (6.0, 2), (8.0, 2)]);
+        let largest: Vec<&str> = summary.largest.iter().map(|(l, _)| l.as_str()).collect();
+        assert_eq!(largest, vec!["check", "bet 2", "call"]);
+    }
+
+    #[test]
+    fn deals_do_not_count_towards_depth() {
+        let summary = summarize(&parse(include_str!("../resources/gametree/part_tree_turn_depthinf.km")));
+        assert_eq!(summary.nodes, 90);
+        assert_eq!(count(&summary, "turn", NodeType::Action), 6);
+        assert_eq!(count(&summary, "turn", NodeType::Terminal), 4);
+        assert_eq!(count(&summary, "river", NodeType::Chance), 5);
+        assert_eq!(count(&summary, "river", NodeType::Action), 30);
+        assert_eq!(count(&summary, "river", NodeType::Showdown), 25);
+        assert_eq!(count(&summary, "river", NodeType::Terminal), 20);
+        assert_eq!(summary.lines, 49);
+        assert_eq!(summary.max_depth, 8);
+    }
+
+    #[test]
+    fn errors_name_the_node() {
+        let showdown = r#"{"meta": {"round": "river", "player": 0, "pot": 8, "node_type": "Showdown", "payoffs": {"0": [4, -4], "1": [-4], "tie": [0, 0]}}}"#;
+        let text = format!(
+            r#"{{"root": {{"meta": {{"round": "river", "player": 1, "pot": 4, "node_type": "Action"}},
+                "children_actions": ["check", "bet_2"],
+                "children": [
+                    {{"meta": {{"round": "river", "player": 0, "pot": 4, "node_type": "Terminal", "payoff": [0, 0]}}}},
+                    {{"meta": {{"round": "river", "player": 0, "pot": 6, "node_type": "Action"}},
+                      "children_actions": ["call"], "children": [{}]}}
+                ]}}}}"#,
+            showdown
+        );
+        let value: Value = serde_json::from_str(&text).unwrap();
+        let err = parse_node(&value["root"], "root").unwrap_err();
+        assert!(err.starts_with("root/bet_2/call: meta.payoffs.1"), "{}", err);
+
+        let bad_type = serde_json::json!({ "meta": { "round": "flop", "pot": 4, "node_type": "Deal" } });
+        assert!(parse_node(&bad_type, "root/check").unwrap_err().starts_with("root/check: unknown meta.node_type"));
+
+        let mismatch = serde_json::json!({
+            "meta": { "round": "flop", "pot": 4, "node_type": "Action" },
+            "children_actions": ["check", "bet_2"],
+            "children": [{ "meta": { "round": "flop", "pot": 4, "node_type": "Terminal" } }],
+        });
+        assert_eq!(parse_node(&mismatch, "root").unwrap_err(), "root: 1 children but 2 children_actions");
+    }
+}
@@ -0,0 +1,268 @@
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use crate::config::Profile;
+use crate::discovery::get_exe_dir;
+use crate::json_out::emit::{batch_document, StreetReport};
+use crate::solver::SolveMeta;
+
+// Session history: every query is appended as one JSON line to
+// resources/outputs/history.jsonl (next to the executable). A record is the
+// `--format json` document (see JSON_OUTPUT.md) plus:
+//
+//   "run_id":    "1792340000-3fa2"   unique, sortable by time
+//   "timestamp": 1792340000          seconds since the Unix epoch (UTC)
+//   "tree_file": "runs/1792340000-3fa2.json" | null
+//
+// The dumped tree of each run is kept under resources/outputs/runs/ so a run
+// can be diffed or browsed again without re-solving. Only the trees of the
+// latest `keep_trees` runs are kept (config key `history_trees`); older ones
+// are deleted and their records say "Tree: not saved" from then on.
+
+const HISTORY_REL_PATH: &str = "resources/outputs/history.jsonl";
+const RUNS_REL_DIR: &str = "resources/outputs/runs";
+
+pub fn history_path() -> PathBuf {
+    get_exe_dir().join(HISTORY_REL_PATH)
+}
+
+pub fn runs_dir() -> PathBuf {
+    get_exe_dir().join(RUNS_REL_DIR)
+}
+
+fn new_run_id(now: &std::time::Duration) -> String {
+    format!("{}-{:04x}", now.as_secs(), (now.subsec_nanos() >> 16) & 0xffff)
+}
+
+// Append one record and save its tree, unless `keep_trees` is 0. Returns the
+// run id.
+#[allow(clippy::too_many_arguments)]
+pub fn record(
+    profile: &Profile,
+    hero_hand: &str,
+    flop: &[String],
+    turn: Option<&str>,
+    river: Option<&str>,
+    streets: &[StreetReport],
+    meta: &SolveMeta,
+    tree: &Value,
+    keep_trees: usize,
+) -> Result<String, Box<dyn Error>> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+    let run_id = new_run_id(&now);
+
+    let path = history_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // A missing tree copy shouldn't lose the history entry itself.
+    let tree_file = (keep_trees > 0)
+        .then(|| {
+            fs::create_dir_all(runs_dir())
+                .and_then(|_| fs::write(runs_dir().join(format!("{}.json", run_id)), tree.to_string()))
+                .ok()
+                .map(|_| format!("runs/{}.json", run_id))
+        })
+        .flatten();
+    prune_trees(keep_trees);
+
+    let mut doc = batch_document(profile, hero_hand, flop, turn, river, streets, meta);
+    doc["run_id"] = json!(run_id);
+    doc["timestamp"] = json!(now.as_secs());
+    doc["tree_file"] = json!(tree_file);
+
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", doc)?;
+    Ok(run_id)
+}
+
+// Delete all but the newest `keep` trees in runs/. Run ids start with the
+// time, so file names sort oldest first. Failures are ignored; the next run
+// tries again.
+fn prune_trees(keep: usize) {
+    let Ok(entries) = fs::read_dir(runs_dir()) else {
+        return;
+    };
+    let mut trees: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|x| x == "json"))
+        .collect();
+    trees.sort();
+    let excess = trees.len().saturating_sub(keep);
+    for path in &trees[..excess] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+// All readable records, oldest first. Lines that fail to parse are skipped
+// and counted so a hand-edited file doesn't hide the rest of the history.
+pub fn load_all() -> Result<(Vec<Value>, usize), Box<dyn Error>> {
+    let path = history_path();
+    if !path.exists() {
+        return Ok((Vec::new(), 0));
+    }
+    Ok(parse_records(&fs::read_to_string(&path)?))
+}
+
+fn parse_records(data: &str) -> (Vec<Value>, usize) {
+    let mut records = Vec::new();
+    let mut skipped = 0;
+    for line in data.lines().filter(|l| !l.trim().is_empty()) {
+        match serde_json::from_str::<Value>(line) {
+            Ok(v) if v.get("run_id").is_some() => records.push(v),
+            _ => skipped += 1,
+        }
+    }
+    (records, skipped)
+}
+
+// Find a record by run id (or unique prefix of one), or by its position in
+// `history list` where 1 (or "#1") is the most recent run.
+pub fn find<'a>(records: &'a [Value], key: &str) -> Result<&'a Value, Box<dyn Error>> {
+    if let Ok(n) = key.strip_prefix('#').unwrap_or(key).parse::<usize>()
+        && n >= 1
+        && n <= records.len()
+        && !key.contains('-')
+    {
+        return Ok(&records[records.len() - n]);
+    }
+    let matches: Vec<&Value> = records
+        .iter()
+        .filter(|r| r["run_id"].as_str().is_some_and(|id| id.starts_with(key)))
+        .collect();
+    match matches.as_slice() {
+        [one] => Ok(one),
+        [] => Err(format!("no history entry matches '{}'", key).into()),
+        many => Err(format!("'{}' matches {} entries; use more of the run id", key, many.len()).into()),
+    }
+}
+
+pub fn tree_path(record: &Value) -> Option<PathBuf> {
+    record["tree_file"]
+        .as_str()
+        .map(|f| get_exe_dir().join("resources/outputs").join(f))
+}
+
+// The board of a record as "Qs,Jh,2h[,turn[,river]]".
+pub fn board_string(record: &Value) -> String {
+    let mut cards: Vec<String> = record["inputs"]["flop"]
+        .as_array()
+        .map(|a| a.iter().filter_map(|c| c.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+    for street in ["turn", "river"] {
+        if let Some(c) = record["inputs"][street].as_str() {
+            cards.push(c.to_string());
+        }
+    }
+    cards.join(",")
+}
+
+// Case-insensitive match against run id, hero, board, profile and date.
+pub fn matches(record: &Value, term: &str) -> bool {
+    let term = term.to_ascii_lowercase();
+    let fields = [
+        record["run_id"].as_str().unwrap_or("").to_string(),
+        record["inputs"]["hero"].as_str().unwrap_or("").to_string(),
+        board_string(record),
+        record["job"]["profile"].as_str().unwrap_or("").to_string(),
+        format_timestamp(record["timestamp"].as_u64().unwrap_or(0)),
+    ];
+    fields.iter().any(|f| f.to_ascii_lowercase().contains(&term))
+}
+
+// "2026-10-18 15:30:12 UTC" without pulling in a date crate.
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let (y, m, d) = civil_from_days(days);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        y,
+        m,
+        d,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+// Days since 1970-01-01 -> (year, month, day), proleptic Gregorian.
+// Howard Hinnant's algorithm.
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    (y, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(id: &str) -> Value {
+        json!({ "run_id": id, "timestamp": 1792340000 })
+    }
+
+    #[test]
+    fn corrupt_lines_are_skipped_and_counted() {
+        let data = [
+            r#"{"run_id": "1792340000-3fa2", "timestamp": 1792340000}"#,
+            r#"{"run_id": "1792340100-0001", "timest"#,
+            "",
+            r#"{"timestamp": 1792340200}"#,
+            "not json",
+            r#"{"run_id": "1792340300-beef", "timestamp": 1792340300}"#,
+        ]
+        .join("\n");
+        let (records, skipped) = parse_records(&data);
+        let ids: Vec<&str> = records.iter().filter_map(|r| r["run_id"].as_str()).collect();
+        assert_eq!(ids, vec!["1792340000-3fa2", "1792340300-beef"]);
+        assert_eq!(skipped, 3);
+        assert_eq!(parse_records("\n\n").1, 0);
+    }
+
+    #[test]
+    fn finds_by_position_and_id_prefix() {
+        let records = vec![run("1792340000-3fa2"), run("1792340000-77c1"), run("1792345000-0a0b")];
+        let id = |key: &str| find(&records, key).map(|r| r["run_id"].as_str().unwrap().to_string());
+
+        // Positions count back from the most recent run.
+        assert_eq!(id("1").unwrap(), "1792345000-0a0b");
+        assert_eq!(id("#1").unwrap(), "1792345000-0a0b");
+        assert_eq!(id("#3").unwrap(), "1792340000-3fa2");
+        assert!(id("#4").is_err());
+
+        assert_eq!(id("1792345").unwrap(), "1792345000-0a0b");
+        assert_eq!(id("1792340000-7").unwrap(), "1792340000-77c1");
+        assert_eq!(id("1792345000-0a0b").unwrap(), "1792345000-0a0b");
+
+        let err = id("1792340000-").unwrap_err().to_string();
+        assert!(err.contains("matches 2 entries"), "{}", err);
+        let err = id("1792349").unwrap_err().to_string();
+        assert!(err.contains("no history entry"), "{}", err);
+    }
+
+    #[test]
+    fn formats_utc_timestamps() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_timestamp(1792340000), "2026-10-18 16:13:20 UTC");
+        assert_eq!(format_timestamp(951782400), "2000-02-29 00:00:00 UTC");
+        assert_eq!(format_timestamp(1709251199), "2024-02-29 23:59:59 UTC");
+        assert_eq!(format_timestamp(1709251200), "2024-03-01 00:00:00 UTC");
+        // 2100 is not a leap year.
+        assert_eq!(format_timestamp(4107456000), "2100-02-28 00:00:00 UTC");
+        assert_eq!(format_timestamp(4107542400), "2100-03-01 00:00:00 UTC");
+    }
+}
@@ -0,0 +1,185 @@
+use std::fmt;
+
+// An action label from the dump, parsed: "CHECK", "CALL", "FOLD",
+// "BET 25.000000", "RAISE 85.000000", "ALLIN" (or "ALLIN 200.000000").
+// Amounts are in chips, as the label gives them: the chips this action puts
+// in on top of what they already have in on the street ("RAISE 12" from a
+// player who bet 2 is a raise to 14).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Check,
+    Call,
+    Fold,
+    Bet(f64),
+    Raise(f64),
+    AllIn(Option<f64>),
+}
+
+impl Action {
+    // None for anything else: deal cards, and "BET"/"RAISE" without their
+    // amount, which is all records from before response lines were kept say.
+    pub fn parse(label: &str) -> Option<Action> {
+        let mut words = label.split_whitespace();
+        let kind = words.next()?.to_ascii_uppercase();
+        let amount = match words.next() {
+            Some(w) => Some(w.parse::<f64>().ok()?),
+            None => None,
+        };
+        if words.next().is_some() {
+            return None;
+        }
+        match (kind.as_str(), amount) {
+            ("CHECK", _) => Some(Action::Check),
+            ("CALL", _) => Some(Action::Call),
+            ("FOLD", _) => Some(Action::Fold),
+            ("BET", Some(a)) => Some(Action::Bet(a)),
+            ("RAISE", Some(a)) => Some(Action::Raise(a)),
+            ("ALLIN" | "ALL-IN", a) => Some(Action::AllIn(a)),
+            _ => None,
+        }
+    }
+
+    // The label's first word, as the dump spells it.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Action::Check => "CHECK",
+            Action::Call => "CALL",
+            Action::Fold => "FOLD",
+            Action::Bet(_) => "BET",
+            Action::Raise(_) => "RAISE",
+            Action::AllIn(_) => "ALLIN",
+        }
+    }
+
+    pub fn chips(&self) -> Option<f64> {
+        match self {
+            Action::Bet(a) | Action::Raise(a) | Action::AllIn(Some(a)) => Some(*a),
+            _ => None,
+        }
+    }
+
+    // The chips this action puts in as a % of `pot`, the pot before it (see
+    // `pot_after`).
+    pub fn pct_of(&self, pot: f64) -> Option<f64> {
+        self.chips().filter(|_| pot > 0.0).map(|c| c / pot * 100.0)
+    }
+
+    pub fn is_aggressive(&self) -> bool {
+        matches!(self, Action::Bet(_) | Action::Raise(_) | Action::AllIn(_))
+    }
+
+    // Sort key among a node's children: checks, calls and folds first, then
+    // bets and raises by amount, an all-in without one last.
+    pub fn order(&self) -> f64 {
+        match self {
+            Action::AllIn(None) => f64::INFINITY,
+            a => a.chips().unwrap_or(0.0),
+        }
+    }
+
+    // Same kind and amount, however the label spelled the number.
+    pub fn matches(&self, other: &Action) -> bool {
+        self.kind() == other.kind()
+            && match (self.chips(), other.chips()) {
+                (Some(a), Some(b)) => (a - b).abs() < 0.01,
+                (None, None) => true,
+                _ => false,
+            }
+    }
+}
+
+// "BET 25", "RAISE 16.5", "ALLIN"
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.chips() {
+            Some(a) => write!(f, "{} {}", self.kind(), chips(a)),
+            None => write!(f, "{}", self.kind()),
+        }
+    }
+}
+
+// A chip amount as the UI shows it: "25", "16.5".
+pub fn chips(x: f64) -> String {
+    if x.fract() == 0.0 { format!("{}", x) } else { format!("{:.1}", x) }
+}
+
+pub fn is_aggressive(label: &str) -> bool {
+    Action::parse(label).is_some_and(|a| a.is_aggressive())
+}
+
+// The pot after `line` from a street that started with `pot`: what each bet
+// and raise along it put in.
+pub fn pot_after(pot: f64, line: &[String]) -> f64 {
+    pot + line.iter().filter_map(|a| Action::parse(a)?.chips()).sum::<f64>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dump_labels() {
+        let cases = [
+            ("CHECK", Some(Action::Check)),
+            ("CALL", Some(Action::Call)),
+            ("FOLD", Some(Action::Fold)),
+            ("BET 16.500000", Some(Action::Bet(16.5))),
+            ("RAISE 40.000000", Some(Action::Raise(40.0))),
+            ("ALLIN", Some(Action::AllIn(None))),
+            ("ALLIN 200.000000", Some(Action::AllIn(Some(200.0)))),
+            ("bet 25", Some(Action::Bet(25.0))),
+            ("check", Some(Action::Check)),
+            ("all-in", Some(Action::AllIn(None))),
+            ("BET", None),
+            ("RAISE lots", None),
+            ("BET 25 50", None),
+            ("DONK 25", None),
+            ("Kd", None),
+            ("", None),
+        ];
+        for (label, want) in cases {
+            assert_eq!(Action::parse(label), want, "{:?}", label);
+        }
+    }
+
+    #[test]
+    fn matches_ignores_how_the_amount_is_spelled() {
+        let bet = Action::parse("BET 16.500000").unwrap();
+        assert!(bet.matches(&Action::parse("BET 16.5").unwrap()));
+        assert!(!bet.matches(&Action::parse("BET 17").unwrap()));
+        assert!(!bet.matches(&Action::Raise(16.5)));
+        assert!(Action::AllIn(None).matches(&Action::AllIn(None)));
+        assert!(!Action::AllIn(None).matches(&Action::AllIn(Some(200.0))));
+        assert!(Action::Check.matches(&Action::Check));
+        assert!(!Action::Check.matches(&Action::Call));
+    }
+
+    #[test]
+    fn orders_passive_actions_first_and_bets_by_size() {
+        let mut labels = vec!["ALLIN", "RAISE 40.000000", "BET 16.500000", "CHECK", "BET 100.000000"];
+        labels.sort_by(|a, b| Action::parse(a).unwrap().order().total_cmp(&Action::parse(b).unwrap().order()));
+        assert_eq!(labels, vec!["CHECK", "BET 16.500000", "RAISE 40.000000", "BET 100.000000", "ALLIN"]);
+        assert_eq!(Action::Fold.order(), Action::Call.order());
+    }
+
+    #[test]
+    fn aggressive_actions_put_chips_in() {
+        for label in ["BET 16.500000", "RAISE 40.000000", "ALLIN"] {
+            assert!(is_aggressive(label), "{}", label);
+        }
+        for label in ["CHECK", "CALL", "FOLD", "Kd", "BET"] {
+            assert!(!is_aggressive(label), "{}", label);
+        }
+    }
+
+    #[test]
+    fn displays_and_sums_amounts() {
+        assert_eq!(Action::Bet(25.0).to_string(), "BET 25");
+        assert_eq!(Action::Raise(16.5).to_string(), "RAISE 16.5");
+        assert_eq!(Action::AllIn(None).to_string(), "ALLIN");
+        assert_eq!(Action::Bet(25.0).pct_of(50.0), Some(50.0));
+        assert_eq!(Action::Check.pct_of(50.0), None);
+        let line: Vec<String> = ["BET 10.000000", "RAISE 30.000000", "CALL"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(pot_after(50.0, &line), 90.0);
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::boards::RANK_ORDER;
+use crate::config::Profile;
+use crate::json_out::parse::{node_at_path, node_strategies, RangeStrategy};
+use crate::solver::{get_generic_hand, range_weights};
+
+// CSV export of a whole range's strategy at one node of a dumped tree:
+//
+//   combo,hand_class,range_weight,CHECK,BET 25.000000
+//   AhKh,AKs,1,0.412,0.588
+//
+// `range_weight` is the preflop weight of the combo's class in the acting
+// player's range (blank when the node doesn't say who acts).
+
+// The acting player at an action node, as the solver numbers them:
+// 0 = IP, 1 = OOP.
+fn acting_range<'a>(node: &Value, profile: &'a Profile) -> Option<&'a str> {
+    match node.get("player").and_then(|p| p.as_u64())? {
+        0 => Some(&profile.range_ip),
+        1 => Some(&profile.range_oop),
+        _ => None,
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains(',') || s.contains('"') || s.contains('\n') {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+// Sort key putting classes in the usual grid order (AA, AKs, AKo, ... 22).
+fn class_sort_key(class: &str) -> (usize, usize, u8) {
+    let mut chars = class.chars();
+    let r1 = chars.next().and_then(|c| RANK_ORDER.find(c)).unwrap_or(99);
+    let r2 = chars.next().and_then(|c| RANK_ORDER.find(c)).unwrap_or(99);
+    let kind = match chars.next() {
+        Some('s') => 1,
+        Some('o') => 2,
+        _ => 0,
+    };
+    (r1, r2, kind)
+}
+
+pub fn node_csv(node: &Value, profile: &Profile) -> Result<(String, usize), Box<dyn Error>> {
+    let RangeStrategy { actions, mut combos } =
+        node_strategies(node).ok_or("node has no strategy (is it an action node inside the dumped rounds?)")?;
+    let weights: Option<HashMap<String, f64>> = acting_range(node, profile).map(range_weights);
+
+    combos.sort_by(|(a, _), (b, _)| {
+        class_sort_key(&get_generic_hand(a))
+            .cmp(&class_sort_key(&get_generic_hand(b)))
+            .then_with(|| a.cmp(b))
+    });
+
+    let mut out = String::new();
+    let header: Vec<String> = ["combo", "hand_class", "range_weight"]
+        .iter()
+        .map(|s| s.to_string())
+        .chain(actions.iter().map(|a| csv_field(a)))
+        .collect();
+    out.push_str(&header.join(","));
+    out.push('\n');
+
+    for (combo, probs) in &combos {
+        let class = get_generic_hand(combo);
+        let weight = weights
+            .as_ref()
+            .map(|w| w.get(&class).copied().unwrap_or(0.0).to_string())
+            .unwrap_or_default();
+        let mut row = vec![combo.clone(), class, weight];
+        row.extend(probs.iter().map(|p| format!("{:.6}", p)));
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+
+    Ok((out, combos.len()))
+}
+
+// Write the node at `path` to `out`. With `per_card`, the node must be a
+// chance node and one file per dealt card is written instead, named
+// `<out stem>_<card>.csv`. Returns (file, combo count) for each file.
+pub fn export_node(
+    root: &Value,
+    path: &str,
+    out: &Path,
+    per_card: bool,
+    profile: &Profile,
+) -> Result<Vec<(PathBuf, usize)>, Box<dyn Error>> {
+    let node = node_at_path(root, path)?;
+    let mut written = Vec::new();
+
+    if per_card {
+        let cards = node
+            .get("dealcards")
+            .and_then(|d| d.as_object())
+            .ok_or("--per-card needs a chance node (one with dealcards); extend the path to it")?;
+        let stem = out.file_stem().and_then(|s| s.to_str()).unwrap_or("strategy");
+        let dir = out.parent().unwrap_or(Path::new(""));
+        for (card, child) in cards {
+            let file = dir.join(format!("{}_{}.csv", stem, card));
+            let (csv, n) = node_csv(child, profile).map_err(|e| format!("card {}: {}", card, e))?;
+            fs::write(&file, csv)?;
+            written.push((file, n));
+        }
+    } else {
+        if node.get("dealcards").is_some() {
+            return Err("node is a chance node; pick a card in the path or pass --per-card".into());
+        }
+        let (csv, n) = node_csv(node, profile)?;
+        fs::write(out, csv)?;
+        written.push((out.to_path_buf(), n));
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn profile() -> Profile {
+        Profile { range_oop: "AA,AKs:0.5,QQ,22".to_string(), range_ip: "AA,KK".to_string(), ..Profile::default() }
+    }
+
+    fn action_node(player: u64, combos: Value) -> Value {
+        json!({
+            "node_type": "action_node",
+            "player": player,
+            "actions": ["CHECK", "BET 25.000000"],
+            "strategy": { "actions": ["CHECK", "BET 25.000000"], "strategy": combos },
+        })
+    }
+
+    #[test]
+    fn rows_follow_the_grid_order() {
+        let node = action_node(
+            1,
+            json!({
+                "2c2d": [1.0, 0.0],
+                "KdAc": [0.25, 0.75],
+                "QhQs": [0.5, 0.5],
+                "AhKh": [0.0, 1.0],
+                "AsAd": [0.1, 0.9],
+                "AcAd": [0.2, 0.8],
+            }),
+        );
+        let (csv, n) = node_csv(&node, &profile()).unwrap();
+        assert_eq!(n, 6);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "combo,hand_class,range_weight,CHECK,BET 25.000000",
+                "AcAd,AA,1,0.200000,0.800000",
+                "AsAd,AA,1,0.100000,0.900000",
+                "AhKh,AKs,0.5,0.000000,1.000000",
+                "KdAc,AKo,0,0.250000,0.750000",
+                "QhQs,QQ,1,0.500000,0.500000",
+                "2c2d,22,1,1.000000,0.000000",
+            ]
+        );
+    }
+
+    #[test]
+    fn weights_are_blank_without_a_player() {
+        let mut node = action_node(1, json!({ "AsAd": [0.5, 0.5] }));
+        node.as_object_mut().unwrap().remove("player");
+        let (csv, _) = node_csv(&node, &profile()).unwrap();
+        assert_eq!(csv.lines().nth(1), Some("AsAd,AA,,0.500000,0.500000"));
+        assert!(node_csv(&json!({ "node_type": "chance_node" }), &profile()).is_err());
+    }
+
+    #[test]
+    fn per_card_writes_one_file_per_deal() {
+        let tree = json!({
+            "childrens": {
+                "CHECK": {
+                    "childrens": {
+                        "CHECK": {
+                            "node_type": "chance_node",
+                            "dealcards": {
+                                "7d": action_node(1, json!({ "AsAd": [1.0, 0.0], "QhQs": [0.0, 1.0] })),
+                                "2s": action_node(1, json!({ "AsAd": [0.0, 1.0] })),
+                            },
+                        },
+                    },
+                },
+            },
+        });
+        let dir = std::env::temp_dir().join(format!("texas_solver_tui-csv-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let out = dir.join("turn.csv");
+
+        assert!(export_node(&tree, "CHECK/CHECK", &out, false, &profile()).is_err());
+        let mut written = export_node(&tree, "check/check", &out, true, &profile()).unwrap();
+        written.sort();
+        assert_eq!(written, vec![(dir.join("turn_2s.csv"), 1), (dir.join("turn_7d.csv"), 2)]);
+        let turn_7d = fs::read_to_string(dir.join("turn_7d.csv")).unwrap();
+        assert_eq!(turn_7d.lines().nth(1), Some("AsAd,AA,1,1.000000,0.000000"));
+        assert!(!out.exists());
+
+        assert!(export_node(&tree, "CHECK", &out, true, &profile()).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,164 @@
+use serde_json::{json, Value};
+
+use crate::boards::Game;
+use crate::config::Profile;
+use crate::evaluator::evaluate_hand_parts;
+use crate::json_out::action::Action;
+use crate::json_out::parse::{HeroStrategy, Response};
+use crate::solver::{Convergence, SolveMeta};
+
+// Builds the `--format json` document for a batch run. The layout is the
+// contract described in JSON_OUTPUT.md: bump SCHEMA_VERSION whenever a field
+// is renamed, removed or changes meaning (adding fields is fine).
+pub const SCHEMA_VERSION: u32 = 2;
+
+// One street of a run, as shown in one `print_strategy_section`.
+pub struct StreetReport<'a> {
+    pub street: &'a str,
+    // All board cards up to and including this street.
+    pub board: Vec<String>,
+    pub oop: Option<&'a HeroStrategy>,
+    pub ip: Option<&'a HeroStrategy>,
+    pub responses: &'a [Response],
+}
+
+pub fn strategy_json(strategy: Option<&HeroStrategy>) -> Value {
+    match strategy {
+        Some(h) => {
+            let actions: Vec<Value> = h
+                .actions
+                .iter()
+                .zip(h.probs.iter())
+                .enumerate()
+                .map(|(i, (action, p))| {
+                    let mut a = json!({ "action": action, "probability": p });
+                    if let Some(evs) = &h.evs {
+                        a["ev"] = json!(evs[i]);
+                    }
+                    a
+                })
+                .collect();
+            json!({ "actions": actions })
+        }
+        None => Value::Null,
+    }
+}
+
+// Inverse of strategy_json, for reading history records back.
+pub fn strategy_from_json(v: &Value) -> Option<HeroStrategy> {
+    let arr = v.get("actions")?.as_array()?;
+    let mut actions = Vec::with_capacity(arr.len());
+    let mut probs = Vec::with_capacity(arr.len());
+    let mut evs = Vec::with_capacity(arr.len());
+    for a in arr {
+        actions.push(a.get("action")?.as_str()?.to_string());
+        probs.push(a.get("probability")?.as_f64()?);
+        evs.extend(a.get("ev").and_then(Value::as_f64));
+    }
+    let evs = (evs.len() == probs.len()).then_some(evs);
+    Some(HeroStrategy { actions, probs, evs })
+}
+
+fn street_json(hero_hand: &str, s: &StreetReport, game: Game) -> Value {
+    let evaluation = match evaluate_hand_parts(hero_hand, &s.board.join(","), game) {
+        Some((description, strength)) => json!({ "description": description, "strength": strength }),
+        None => Value::Null,
+    };
+    json!({
+        "street": s.street,
+        "board": s.board,
+        "hand_evaluation": evaluation,
+        "oop": strategy_json(s.oop),
+        "ip": strategy_json(s.ip),
+        // The first size of each, as before `responses` existed.
+        "oop_vs_bet": strategy_json(first_response(s.responses, &["CHECK", "BET"])),
+        "ip_vs_bet": strategy_json(first_response(s.responses, &["BET"])),
+        "responses": s.responses.iter().map(|r| json!({
+            "line": r.path(),
+            "player": r.player,
+            "strategy": strategy_json(Some(&r.strategy)),
+        })).collect::<Vec<_>>(),
+    })
+}
+
+// The first response whose line matches `shape` action by action.
+fn first_response<'a>(responses: &'a [Response], shape: &[&str]) -> Option<&'a HeroStrategy> {
+    responses
+        .iter()
+        .find(|r| r.line.len() == shape.len() && r.line.iter().zip(shape).all(|(a, kind)| Action::parse(a).is_some_and(|a| a.kind() == *kind)))
+        .map(|r| &r.strategy)
+}
+
+// Inverse of the `responses` part of street_json. Records from before it
+// existed only have `oop_vs_bet` (and maybe `ip_vs_bet`), whose lines were
+// not kept.
+pub fn responses_from_json(street: &Value) -> Vec<Response> {
+    if let Some(arr) = street["responses"].as_array() {
+        return arr
+            .iter()
+            .filter_map(|r| {
+                let line = r["line"].as_str()?.split('/').map(String::from).collect();
+                let player = if r["player"].as_str()? == "OOP" { "OOP" } else { "IP" };
+                Some(Response { line, player, strategy: strategy_from_json(&r["strategy"])? })
+            })
+            .collect();
+    }
+    let old = [("oop_vs_bet", vec!["CHECK", "BET"], "OOP"), ("ip_vs_bet", vec!["BET"], "IP")];
+    old.into_iter()
+        .filter_map(|(key, line, player)| {
+            let strategy = strategy_from_json(&street[key])?;
+            Some(Response { line: line.into_iter().map(String::from).collect(), player, strategy })
+        })
+        .collect()
+}
+
+pub fn streets_json(hero_hand: &str, streets: &[StreetReport], game: Game) -> Value {
+    json!(streets.iter().map(|s| street_json(hero_hand, s, game)).collect::<Vec<_>>())
+}
+
+pub fn solver_json(meta: &SolveMeta) -> Value {
+    json!({
+        "backend": meta.backend.as_str(),
+        "path": meta.solver_path.to_string_lossy(),
+        "resource_dir": meta.resource_dir.to_string_lossy(),
+        "mode": meta.mode,
+        "elapsed_seconds": meta.elapsed.as_secs_f64(),
+        "iterations": meta.convergence.iterations,
+        "exploitability_pct": meta.convergence.exploitability,
+    })
+}
+
+// Inverse of the convergence part of solver_json; records from before it
+// was kept read as "not reported".
+pub fn convergence_from_json(solver: &Value) -> Convergence {
+    Convergence {
+        iterations: solver["iterations"].as_u64().map(|n| n as u32),
+        exploitability: solver["exploitability_pct"].as_f64(),
+    }
+}
+
+pub fn batch_document(
+    profile: &Profile,
+    hero_hand: &str,
+    flop: &[String],
+    turn: Option<&str>,
+    river: Option<&str>,
+    streets: &[StreetReport],
+    meta: &SolveMeta,
+) -> Value {
+    let mut job = profile.to_json();
+    job["profile"] = json!(profile.name);
+
+    json!({
+        "schema_version": SCHEMA_VERSION,
+        "inputs": {
+            "hero": hero_hand,
+            "flop": flop,
+            "turn": turn,
+            "river": river,
+        },
+        "job": job,
+        "streets": streets_json(hero_hand, streets, profile.game),
+        "solver": solver_json(meta),
+    })
+}
@@ -1 +1,4 @@
 pub mod parse;
+pub mod action;
+pub mod emit;
+pub mod csv;
@@ -1,11 +1,17 @@
+use std::collections::HashMap;
 use std::error::Error;
 use std::fs;
 
 use serde_json::Value;
 
+use crate::json_out::action::Action;
+use crate::solver::get_generic_hand;
+
 pub struct HeroStrategy {
     pub actions: Vec<String>,
     pub probs: Vec<f64>,
+    // Per action, in chips, when the dump has EVs (see `combo_evs`).
+    pub evs: Option<Vec<f64>>,
 }
 
 pub fn load_tree(json_path: &str) -> Result<Value, Box<dyn Error>> {
@@ -44,66 +50,183 @@ pub fn hero_strategy_from_node(node: &Value, hero_hand: &str) -> Option<HeroStra
             let n = probs.len();
             actions = (0..n).map(|i| format!("action #{}", i)).collect();
         }
-        Some(HeroStrategy { actions, probs })
+        // Same key lookup as the strategy: either card order.
+        let evs = combo_evs(node, hero_hand)
+            .or_else(|| hero_hand.get(2..4).zip(hero_hand.get(0..2)).and_then(|(a, b)| combo_evs(node, &format!("{}{}", a, b))))
+            .filter(|evs| evs.len() >= probs.len())
+            .map(|mut evs| {
+                evs.truncate(probs.len());
+                evs
+            });
+        Some(HeroStrategy { actions, probs, evs })
     } else {
         None
     }
 }
 
-pub fn extract_street_strategies(
-    start_node: &Value,
-    hero_hand: &str
-) -> (Option<HeroStrategy>, Option<HeroStrategy>, Option<HeroStrategy>) {
-    // 1. OOP Open Strategy (Root of the street)
-    let oop_open = hero_strategy_from_node(start_node, hero_hand);
-    
-    // 2. IP Strategy (After OOP Checks)
+// Hero's strategy at the decisions of one street.
+#[derive(Default)]
+pub struct StreetStrategies {
+    // OOP first to act, and IP after OOP checks.
+    pub oop: Option<HeroStrategy>,
+    pub ip: Option<HeroStrategy>,
+    // Every decision facing a bet or raise (see response_nodes) where hero's
+    // hand is in the acting range.
+    pub responses: Vec<Response>,
+}
+
+pub struct Response {
+    // Actions from the first node of the street, e.g.
+    // ["CHECK", "BET 25.000000", "RAISE 85.000000"].
+    pub line: Vec<String>,
+    // Who faces the last action: "OOP" or "IP".
+    pub player: &'static str,
+    pub strategy: HeroStrategy,
+}
+
+impl Response {
+    pub fn path(&self) -> String {
+        self.line.join("/")
+    }
+}
+
+pub fn extract_street_strategies(start_node: &Value, hero_hand: &str) -> StreetStrategies {
     let check_node = start_node.get("childrens").and_then(|c| c.get("CHECK"));
-    let ip_vs_check = check_node.and_then(|n| hero_strategy_from_node(n, hero_hand));
-    
-    // 3. OOP Response to Bet (After OOP Checks -> IP Bets)
-    // We look for any child of the CHECK node that contains "BET"
-    let oop_vs_bet = check_node.and_then(|n| {
-         n.get("childrens")?.as_object()?.iter()
-            .find(|(k, _)| k.contains("BET"))
-            .map(|(_, v)| hero_strategy_from_node(v, hero_hand))
-    }).flatten();
-    
-    (oop_open, ip_vs_check, oop_vs_bet)
-}
-
-pub fn hero_strategy_flop_both(root: &Value, hero_hand: &str) -> (Option<HeroStrategy>, Option<HeroStrategy>, Option<HeroStrategy>) {
+    let responses = response_nodes(start_node)
+        .into_iter()
+        .filter_map(|(line, node)| {
+            let strategy = hero_strategy_from_node(node, hero_hand)?;
+            Some(Response { player: line_player(&line), line, strategy })
+        })
+        .collect();
+    StreetStrategies {
+        oop: hero_strategy_from_node(start_node, hero_hand),
+        ip: check_node.and_then(|n| hero_strategy_from_node(n, hero_hand)),
+        responses,
+    }
+}
+
+// Every node of a street where the player to act faces a bet or raise, with
+// the line leading to it from the street's first node: OOP facing each IP
+// bet after checking, IP facing each OOP lead, and below each of those the
+// raises, re-raises and so on. Each size is its own node. Lines come in
+// tree order, smallest size first, each followed by the raises of it.
+pub fn response_nodes(start_node: &Value) -> Vec<(Vec<String>, &Value)> {
+    let mut out = Vec::new();
+    collect_responses(start_node, &mut Vec::new(), &mut out);
+    out
+}
+
+fn collect_responses<'a>(node: &'a Value, line: &mut Vec<String>, out: &mut Vec<(Vec<String>, &'a Value)>) {
+    for child in action_children(node) {
+        let aggressive = child.action.is_some_and(|a| a.is_aggressive());
+        // Past OOP's opening check only bets and raises lead to a decision;
+        // calls and checks end the street.
+        let opening_check = line.is_empty() && child.action == Some(Action::Check);
+        if !aggressive && !opening_check {
+            continue;
+        }
+        line.push(child.label.to_string());
+        if aggressive {
+            out.push((line.clone(), child.node));
+        }
+        collect_responses(child.node, line, out);
+        line.pop();
+    }
+}
+
+// Who acts after `line`: OOP opens every street.
+pub fn line_player(line: &[String]) -> &'static str {
+    if line.len().is_multiple_of(2) { "OOP" } else { "IP" }
+}
+
+// A child of an action node with its label parsed (None for labels that
+// are not actions).
+pub struct ActionChild<'a> {
+    pub label: &'a str,
+    pub action: Option<Action>,
+    pub node: &'a Value,
+}
+
+// The action children of a node, smallest amount first (checks, calls and
+// folds before any bet, a bare "ALLIN" last), whatever order the dump's
+// object keys come in.
+pub fn action_children(node: &Value) -> Vec<ActionChild<'_>> {
+    let Some(children) = node.get("childrens").and_then(|c| c.as_object()) else {
+        return Vec::new();
+    };
+    let mut out: Vec<ActionChild> = children
+        .iter()
+        .map(|(label, node)| ActionChild { label, action: Action::parse(label), node })
+        .collect();
+    let order = |c: &ActionChild| c.action.map_or(0.0, |a| a.order());
+    out.sort_by(|a, b| order(a).total_cmp(&order(b)));
+    out
+}
+
+// The child for an action, however the label spells the number ("BET 25"
+// finds "BET 25.000000").
+pub fn child_by_action<'a>(node: &'a Value, action: &Action) -> Option<ActionChild<'a>> {
+    action_children(node).into_iter().find(|c| c.action.is_some_and(|a| a.matches(action)))
+}
+
+// "BET 25.000000" -> "BET 25", "RAISE 16.500000" -> "RAISE 16.5". Labels
+// that are not actions come back as they are.
+pub fn short_action(action: &str) -> String {
+    Action::parse(action).map_or_else(|| action.to_string(), |a| a.to_string())
+}
+
+// "OOP facing CHECK, BET 25, RAISE 85"
+pub fn response_label(response: &Response) -> String {
+    let line: Vec<String> = response.line.iter().map(|a| short_action(a)).collect();
+    format!("{} facing {}", response.player, line.join(", "))
+}
+
+// The node `line` leads to from `start`, through action children only.
+pub fn node_at_line<'a>(start: &'a Value, line: &[String]) -> Option<&'a Value> {
+    line.iter().try_fold(start, |node, action| node.get("childrens")?.get(action))
+}
+
+pub fn hero_strategy_flop_both(root: &Value, hero_hand: &str) -> StreetStrategies {
     extract_street_strategies(root, hero_hand)
 }
 
 pub fn hero_strategy_flop(root: &Value, hero_hand: &str) -> Option<HeroStrategy> {
-    let (oop, ip, _) = hero_strategy_flop_both(root, hero_hand);
-    ip.or(oop)
+    let s = hero_strategy_flop_both(root, hero_hand);
+    s.ip.or(s.oop)
 }
 
 pub fn hero_strategy_turn_both(
     root: &Value,
     hero_hand: &str,
     turn_card: &str,
-) -> Option<(Option<HeroStrategy>, Option<HeroStrategy>, Option<HeroStrategy>)> {
-    // Path to Turn: Root(OOP) -> Check -> IP -> Check -> Deal Turn Card
-    let childrens = root.get("childrens").and_then(|c| c.as_object())?;
-    let c1 = childrens.get("CHECK")?; // OOP Checked
-    let ch1 = c1.get("childrens").and_then(|c| c.as_object())?;
-    let chance = ch1.get("CHECK")?; // IP Checked
-    let dealcards = chance.get("dealcards").and_then(|c| c.as_object())?;
-    let turn_node = dealcards.get(turn_card)?; // Turn Card Dealt
+) -> Option<StreetStrategies> {
+    let turn_node = street_node(root, Some(turn_card), None)?;
 
     Some(extract_street_strategies(turn_node, hero_hand))
 }
 
+// The first node of a street along the check-check line:
+//   Turn:  Root(OOP) -> Check -> IP -> Check -> Deal Turn Card
+//   River: ... Turn Node -> OOP Check -> IP Check -> Deal River Card
+// With no turn card this is the root itself.
+pub fn street_node<'a>(root: &'a Value, turn_card: Option<&str>, river_card: Option<&str>) -> Option<&'a Value> {
+    let mut node = root;
+    for card in [turn_card, river_card].into_iter().flatten() {
+        let oop_checked = node.get("childrens")?.get("CHECK")?;
+        let ip_checked = oop_checked.get("childrens")?.get("CHECK")?;
+        node = ip_checked.get("dealcards")?.get(card)?;
+    }
+    Some(node)
+}
+
 pub fn hero_strategy_turn_check(
     root: &Value,
     hero_hand: &str,
     turn_card: &str,
 ) -> Option<HeroStrategy> {
-    let (oop, ip, _) = hero_strategy_turn_both(root, hero_hand, turn_card)?;
-    ip.or(oop)
+    let s = hero_strategy_turn_both(root, hero_hand, turn_card)?;
+    s.ip.or(s.oop)
 }
 
 pub fn hero_strategy_river_both(
@@ -111,24 +234,8 @@ pub fn hero_strategy_river_both(
     hero_hand: &str,
     turn_card: &str,
     river_card: &str,
-) -> Option<(Option<HeroStrategy>, Option<HeroStrategy>, Option<HeroStrategy>)> {
-    // Path to River: ... Turn Node -> OOP Check -> IP Check -> Deal River Card
-    
-    // 1. Navigate to Turn Node
-    let childrens = root.get("childrens").and_then(|c| c.as_object())?;
-    let c1 = childrens.get("CHECK")?;
-    let ch1 = c1.get("childrens").and_then(|c| c.as_object())?;
-    let chance_turn = ch1.get("CHECK")?;
-    let dealcards_turn = chance_turn.get("dealcards").and_then(|c| c.as_object())?;
-    let turn_node = dealcards_turn.get(turn_card)?;
-
-    // 2. Navigate through Turn Check-Check to River
-    let ch_t1 = turn_node.get("childrens").and_then(|c| c.as_object())?;
-    let c_t1 = ch_t1.get("CHECK")?; // OOP Checked Turn
-    let ch_t2 = c_t1.get("childrens").and_then(|c| c.as_object())?;
-    let c_t2 = ch_t2.get("CHECK")?; // IP Checked Turn
-    let dealcards_river = c_t2.get("dealcards").and_then(|c| c.as_object())?;
-    let river_node = dealcards_river.get(river_card)?; // River Card Dealt
+) -> Option<StreetStrategies> {
+    let river_node = street_node(root, Some(turn_card), Some(river_card))?;
 
     Some(extract_street_strategies(river_node, hero_hand))
 }
@@ -139,29 +246,26 @@ pub fn hero_strategy_river_check(
     turn_card: &str,
     river_card: &str,
 ) -> Option<HeroStrategy> {
-    let (oop, ip, _) = hero_strategy_river_both(root, hero_hand, turn_card, river_card)?;
-    ip.or(oop)
+    let s = hero_strategy_river_both(root, hero_hand, turn_card, river_card)?;
+    s.ip.or(s.oop)
 }
 
 fn find_node_with_hero_strategy<'a>(value: &'a Value, hero_hand: &str) -> Option<&'a Value> {
     match value {
         Value::Object(map) => {
             // Check if this node has a strategy for the hero hand
-            if let Some(strategy_obj) = map.get("strategy") {
-                if let Some(inner) = strategy_obj.get("strategy") {
-                    if inner.get(hero_hand).is_some() {
-                        return Some(value);
-                    }
-                }
+            if let Some(strategy_obj) = map.get("strategy")
+                && let Some(inner) = strategy_obj.get("strategy")
+                && inner.get(hero_hand).is_some()
+            {
+                return Some(value);
             }
 
             // Otherwise recurse into childrens
-            if let Some(childrens) = map.get("childrens") {
-                if let Value::Object(children_map) = childrens {
-                    for child in children_map.values() {
-                        if let Some(found) = find_node_with_hero_strategy(child, hero_hand) {
-                            return Some(found);
-                        }
+            if let Some(Value::Object(children_map)) = map.get("childrens") {
+                for child in children_map.values() {
+                    if let Some(found) = find_node_with_hero_strategy(child, hero_hand) {
+                        return Some(found);
                     }
                 }
             }
@@ -232,3 +336,209 @@ fn extract_strategy_vector(node: &Value, hero_hand: &str) -> Option<Vec<f64>> {
 
     Some(result)
 }
+
+// Walk a node path such as "CHECK/BET 25.000000/CALL/9d" down from `root`.
+// Action nodes are entered through `childrens`, chance nodes through
+// `dealcards`. Segments match a child key exactly, then case-insensitively,
+// then by action and amount ("bet 25.0"), then as a unique case-insensitive
+// prefix (so "bet" works when there is a single bet size). An empty path (or
+// "/") is the root.
+pub fn node_at_path<'a>(root: &'a Value, path: &str) -> Result<&'a Value, String> {
+    let mut node = root;
+    let mut walked: Vec<String> = Vec::new();
+
+    for segment in path.split('/').map(str::trim).filter(|s| !s.is_empty()) {
+        let children = node
+            .get("childrens")
+            .or_else(|| node.get("dealcards"))
+            .and_then(|c| c.as_object())
+            .ok_or_else(|| format!("node '{}' has no children", walked.join("/")))?;
+
+        let lower = segment.to_ascii_lowercase();
+        let key = if children.contains_key(segment) {
+            segment.to_string()
+        } else if let Some(k) = children.keys().find(|k| k.to_ascii_lowercase() == lower) {
+            k.clone()
+        } else if let Some(action) = Action::parse(segment)
+            && action.chips().is_some()
+            && let Some(child) = child_by_action(node, &action)
+        {
+            child.label.to_string()
+        } else {
+            let matches: Vec<&String> = children
+                .keys()
+                .filter(|k| k.to_ascii_lowercase().starts_with(&lower))
+                .collect();
+            match matches.as_slice() {
+                [k] => (*k).clone(),
+                [] => {
+                    return Err(format!(
+                        "no child '{}' under '{}' (children: {})",
+                        segment,
+                        walked.join("/"),
+                        children.keys().cloned().collect::<Vec<_>>().join(", ")
+                    ))
+                }
+                many => {
+                    return Err(format!(
+                        "'{}' is ambiguous under '{}' (matches: {})",
+                        segment,
+                        walked.join("/"),
+                        many.iter().map(|k| k.as_str()).collect::<Vec<_>>().join(", ")
+                    ))
+                }
+            }
+        };
+
+        node = &children[&key];
+        walked.push(key);
+    }
+
+    Ok(node)
+}
+
+// Every combo's strategy at an action node, in dump order.
+pub struct RangeStrategy {
+    pub actions: Vec<String>,
+    // (combo, probability per action)
+    pub combos: Vec<(String, Vec<f64>)>,
+}
+
+pub fn node_strategies(node: &Value) -> Option<RangeStrategy> {
+    let strategy = node.get("strategy")?;
+    let actions = strategy
+        .get("actions")
+        .and_then(|a| a.as_array())
+        .map(|a| a.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_else(|| extract_actions(node));
+    let combos = strategy
+        .get("strategy")?
+        .as_object()?
+        .iter()
+        .map(|(combo, probs)| {
+            let probs = probs
+                .as_array()
+                .map(|a| a.iter().filter_map(|v| v.as_f64()).collect())
+                .unwrap_or_default();
+            (combo.clone(), probs)
+        })
+        .collect();
+    Some(RangeStrategy { actions, combos })
+}
+
+// Share of each action over the whole range at a node, each combo weighted
+// by its class's weight in the acting player's range (equal weights when the
+// range is unknown).
+pub fn range_frequencies(strategy: &RangeStrategy, weights: Option<&HashMap<String, f64>>) -> Vec<f64> {
+    let mut totals = vec![0.0; strategy.actions.len()];
+    let mut total_weight = 0.0;
+    for (combo, probs) in &strategy.combos {
+        let weight = weights.map_or(1.0, |w| w.get(&get_generic_hand(combo)).copied().unwrap_or(0.0));
+        for (total, p) in totals.iter_mut().zip(probs) {
+            *total += weight * p;
+        }
+        total_weight += weight;
+    }
+    if total_weight > 0.0 {
+        totals.iter_mut().for_each(|t| *t /= total_weight);
+    }
+    totals
+}
+
+// Per-action EVs of one combo at an action node, in chips, when the dump has
+// them: an `evs` object next to `strategy` with the same layout
+// ({"actions": [...], "evs": {"AhKd": [...]}}). Stock dumps carry none.
+pub fn combo_evs(node: &Value, combo: &str) -> Option<Vec<f64>> {
+    let evs = node.get("evs")?.get("evs")?.get(combo)?.as_array()?;
+    Some(evs.iter().filter_map(|v| v.as_f64()).collect())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn node(player: u64, children: Value) -> Value {
+        let actions: Vec<String> = children.as_object().map(|c| c.keys().cloned().collect()).unwrap_or_default();
+        let probs = vec![1.0 / actions.len().max(1) as f64; actions.len()];
+        json!({
+            "node_type": "action_node",
+            "player": player,
+            "actions": actions,
+            "strategy": { "actions": actions, "strategy": { "AhKd": probs } },
+            "childrens": children,
+        })
+    }
+
+    // One street: OOP checks or leads 25 into 50. After a check IP bets 16.5
+    // or 37.5 and OOP can check-raise the small one; IP can raise the lead.
+    pub(crate) fn street_fixture() -> Value {
+        let end = || json!({ "node_type": "action_node", "player": 1, "actions": [] });
+        node(
+            1,
+            json!({
+                "CHECK": node(0, json!({
+                    "CHECK": end(),
+                    "BET 16.500000": node(1, json!({
+                        "FOLD": end(),
+                        "CALL": end(),
+                        "RAISE 50.000000": node(0, json!({ "FOLD": end(), "CALL": end() })),
+                    })),
+                    "BET 37.500000": node(1, json!({ "FOLD": end(), "CALL": end() })),
+                })),
+                "BET 25.000000": node(0, json!({
+                    "FOLD": end(),
+                    "CALL": end(),
+                    "RAISE 75.000000": node(1, json!({ "FOLD": end(), "CALL": end() })),
+                })),
+            }),
+        )
+    }
+
+    #[test]
+    fn each_size_faced_is_its_own_response() {
+        let tree = street_fixture();
+        let lines: Vec<String> = response_nodes(&tree).iter().map(|(line, _)| line.join("/")).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "CHECK/BET 16.500000",
+                "CHECK/BET 16.500000/RAISE 50.000000",
+                "CHECK/BET 37.500000",
+                "BET 25.000000",
+                "BET 25.000000/RAISE 75.000000",
+            ]
+        );
+    }
+
+    #[test]
+    fn line_player_agrees_with_the_dump() {
+        let tree = street_fixture();
+        let players: Vec<&str> = response_nodes(&tree).iter().map(|(line, _)| line_player(line)).collect();
+        assert_eq!(players, vec!["OOP", "IP", "OOP", "IP", "OOP"]);
+        for (line, node) in response_nodes(&tree) {
+            let dumped = if node["player"] == 1 { "OOP" } else { "IP" };
+            assert_eq!(line_player(&line), dumped, "{:?}", line);
+        }
+        assert_eq!(line_player(&[]), "OOP");
+    }
+
+    #[test]
+    fn finds_children_by_parsed_action() {
+        let tree = street_fixture();
+        let after_check = &tree["childrens"]["CHECK"];
+        assert_eq!(child_by_action(after_check, &Action::Bet(16.5)).unwrap().label, "BET 16.500000");
+        assert_eq!(child_by_action(after_check, &Action::Bet(37.5)).unwrap().label, "BET 37.500000");
+        assert_eq!(child_by_action(after_check, &Action::Check).unwrap().label, "CHECK");
+        assert!(child_by_action(after_check, &Action::Bet(25.0)).is_none());
+        assert!(child_by_action(after_check, &Action::Raise(16.5)).is_none());
+
+        let lead = child_by_action(&tree, &Action::parse("BET 25").unwrap()).unwrap();
+        assert_eq!(lead.node["player"], 0);
+        // Passive actions first, whatever order the keys come in.
+        let labels: Vec<&str> = action_children(lead.node).iter().map(|c| c.label).collect();
+        assert_eq!(labels.len(), 3);
+        assert_eq!(labels[2], "RAISE 75.000000");
+    }
+}
@@ -4,23 +4,75 @@ pub mod ui;
 pub mod json_out;
 pub mod solver;
 pub mod evaluator;
+pub mod config;
+pub mod cli;
+pub mod discovery;
+pub mod library;
+pub mod history;
+pub mod report;
+pub mod boards;
+pub mod batch;
+pub mod texture;
+pub mod trainer;
+pub mod diff;
+pub mod gametree;
+pub mod tree;
+pub mod sizing;
+
+use config::Config;
 
 pub fn run() -> Result<(), Box<dyn std::error::Error>> {
-	let mut args = env::args().skip(1);
+	let cli_args = cli::parse_args(env::args().skip(1))?;
+	let config = Config::load(cli_args.config_path.as_deref())?;
+
+	let mut args = cli_args.positional.iter();
+	let first = args.next();
+
+	match first.map(String::as_str) {
+		Some("profiles") => return ui::flow::list_profiles(&config),
+		Some("doctor") => return ui::doctor::run(&config),
+		Some("history") => return ui::history::run(&config, &cli_args.positional[1..]),
+		Some("report") => return ui::report::run(&cli_args.positional[1..]),
+		Some("batch-flops") => {
+			let profile = config.profile(cli_args.profile.as_deref())?;
+			return ui::batch::run(&config, profile, &cli_args.positional[1..], cli_args.subset);
+		}
+		Some("flop-report") => return ui::batch::texture_report(&cli_args.positional[1..]),
+		Some("flops") => {
+			let profile = config.profile(cli_args.profile.as_deref())?;
+			return ui::batch::export_list(&cli_args.positional[1..], cli_args.subset, profile.game);
+		}
+		Some("train") => return ui::trainer::run(&cli_args.positional[1..], cli_args.user.as_deref()),
+		Some("diff") => return ui::diff::run(&cli_args.positional[1..], cli_args.format),
+		Some("gametree") => return ui::gametree::run(&config, &cli_args.positional[1..], cli_args.format),
+		Some("tree") => {
+			let profile = config.profile(cli_args.profile.as_deref())?;
+			return ui::tree::run(profile, &cli_args.positional[1..], cli_args.format);
+		}
+		Some("export-csv") => {
+			let profile = config.profile(cli_args.profile.as_deref())?;
+			return ui::export::run(profile, &cli_args.positional[1..], cli_args.per_card);
+		}
+		_ => {}
+	}
 
-	let hero = args.next();
+	let hero = first;
 	let flop = args.next();
 	let turn = args.next();
 	let river = args.next();
 
 	if hero.is_none() || flop.is_none() {
-		return ui::flow::run();
+		return ui::flow::run(&config, cli_args.profile.as_deref());
 	}
 
+	let profile = config.profile(cli_args.profile.as_deref())?;
 	ui::flow::run_batch(
-		hero.as_deref().unwrap(),
-		flop.as_deref().unwrap(),
-		turn.as_deref(),
-		river.as_deref(),
+		&config,
+		profile,
+		hero.unwrap(),
+		flop.unwrap(),
+		turn.map(String::as_str),
+		river.map(String::as_str),
+		cli_args.format,
 	)
 }
@@ -0,0 +1,226 @@
+use std::error::Error;
+use std::ffi::{CStr, CString, c_char, c_int, c_void};
+use std::path::Path;
+
+use libloading::Library;
+use serde_json::Value;
+
+use crate::config::NodeLock;
+
+// In-process solver backend: instead of spawning console_solver and reading a
+// JSON file back, load a shared library that exposes the C ABI below and get
+// the dump as a string. The library also reports progress while solving.
+//
+// C ABI (version 1). All strings are NUL-terminated UTF-8.
+//
+//   typedef void (*ts_progress_fn)(void *user, uint32_t iteration,
+//                                  double exploitability_pct);
+//
+//   uint32_t    ts_abi_version(void);
+//       Must return 1.
+//   void       *ts_create(const char *resource_dir, const char *mode);
+//       `mode` is "holdem" or "shortdeck". Returns NULL on failure.
+//   int         ts_build_tree(void *solver, const char *commands);
+//       `commands` holds the same `set_*` lines console_solver reads from its
+//       input file (pot, stack, board, ranges, bet sizes, solver options), one
+//       per line, without build_tree/start_solve/dump_result. 0 on success.
+//   int         ts_solve(void *solver, ts_progress_fn progress, void *user);
+//       Runs the solve, calling `progress` (if not NULL) every print
+//       interval. 0 on success.
+//   char       *ts_dump(void *solver, uint32_t dump_rounds);
+//       The strategy tree in console_solver's dump_result JSON format, or
+//       NULL on failure. Release with ts_free_string.
+//   void        ts_free_string(char *s);
+//   void        ts_destroy(void *solver);
+//   const char *ts_last_error(void *solver);      (optional)
+//       Message for the last failure on this handle, or NULL. Owned by the
+//       library; valid until the next call on the handle.
+//   int         ts_set_dump_evs(void *solver, int enabled);   (optional)
+//       Called before ts_dump when the config asks for EVs: every action
+//       node of the dump then also carries
+//         "evs": {"actions": [...], "evs": {"AhKd": [ev per action], ...}}
+//       in chips, laid out like "strategy". 0 on success.
+//   int         ts_lock_node(void *solver, const char *path,
+//                            const char *strategy);        (optional)
+//       Called after ts_build_tree, once per lock in the profile. Fixes the
+//       strategy of every combo at `path` ("CHECK/BET 25", segments as in
+//       node_at_path) to `strategy`, a JSON object of action -> frequency
+//       such as {"BET": 1.0}. 0 on success.
+
+pub const ABI_VERSION: u32 = 1;
+
+type ProgressFn = unsafe extern "C" fn(*mut c_void, u32, f64);
+type AbiVersionFn = unsafe extern "C" fn() -> u32;
+type CreateFn = unsafe extern "C" fn(*const c_char, *const c_char) -> *mut c_void;
+type BuildTreeFn = unsafe extern "C" fn(*mut c_void, *const c_char) -> c_int;
+type SolveFn = unsafe extern "C" fn(*mut c_void, Option<ProgressFn>, *mut c_void) -> c_int;
+type DumpFn = unsafe extern "C" fn(*mut c_void, u32) -> *mut c_char;
+type FreeStringFn = unsafe extern "C" fn(*mut c_char);
+type DestroyFn = unsafe extern "C" fn(*mut c_void);
+type LastErrorFn = unsafe extern "C" fn(*mut c_void) -> *const c_char;
+type SetDumpEvsFn = unsafe extern "C" fn(*mut c_void, c_int) -> c_int;
+type LockNodeFn = unsafe extern "C" fn(*mut c_void, *const c_char, *const c_char) -> c_int;
+
+pub struct SolverLibrary {
+    create: CreateFn,
+    build_tree: BuildTreeFn,
+    solve: SolveFn,
+    dump: DumpFn,
+    free_string: FreeStringFn,
+    destroy: DestroyFn,
+    last_error: Option<LastErrorFn>,
+    set_dump_evs: Option<SetDumpEvsFn>,
+    lock_node: Option<LockNodeFn>,
+    // Keeps the function pointers above valid; must outlive every call.
+    _lib: Library,
+}
+
+// Owns one solver instance; destroyed when dropped.
+struct Handle<'a> {
+    lib: &'a SolverLibrary,
+    ptr: *mut c_void,
+}
+
+impl Drop for Handle<'_> {
+    fn drop(&mut self) {
+        unsafe { (self.lib.destroy)(self.ptr) }
+    }
+}
+
+impl Handle<'_> {
+    fn error(&self, call: &str, code: Option<c_int>) -> Box<dyn Error> {
+        let detail = self
+            .lib
+            .last_error
+            .map(|f| unsafe { f(self.ptr) })
+            .filter(|p| !p.is_null())
+            .map(|p| unsafe { CStr::from_ptr(p) }.to_string_lossy().into_owned());
+        let code = code.map(|c| format!(" (code {})", c)).unwrap_or_default();
+        match detail {
+            Some(msg) => format!("{} failed{}: {}", call, code, msg).into(),
+            None => format!("{} failed{}", call, code).into(),
+        }
+    }
+}
+
+impl SolverLibrary {
+    pub fn open(path: &Path) -> Result<SolverLibrary, Box<dyn Error>> {
+        // Safety: loading runs the library's initialisers; we trust the
+        // library the user configured, as we trust console_solver.
+        let lib = unsafe { Library::new(path) }
+            .map_err(|e| format!("cannot load solver library {}: {}", path.display(), e))?;
+
+        unsafe {
+            let version = *lib.get::<AbiVersionFn>(b"ts_abi_version\0")?;
+            let found = version();
+            if found != ABI_VERSION {
+                return Err(format!(
+                    "{} implements solver ABI v{}, expected v{}",
+                    path.display(),
+                    found,
+                    ABI_VERSION
+                )
+                .into());
+            }
+
+            Ok(SolverLibrary {
+                create: *lib.get::<CreateFn>(b"ts_create\0")?,
+                build_tree: *lib.get::<BuildTreeFn>(b"ts_build_tree\0")?,
+                solve: *lib.get::<SolveFn>(b"ts_solve\0")?,
+                dump: *lib.get::<DumpFn>(b"ts_dump\0")?,
+                free_string: *lib.get::<FreeStringFn>(b"ts_free_string\0")?,
+                destroy: *lib.get::<DestroyFn>(b"ts_destroy\0")?,
+                last_error: lib.get::<LastErrorFn>(b"ts_last_error\0").ok().map(|s| *s),
+                set_dump_evs: lib.get::<SetDumpEvsFn>(b"ts_set_dump_evs\0").ok().map(|s| *s),
+                lock_node: lib.get::<LockNodeFn>(b"ts_lock_node\0").ok().map(|s| *s),
+                _lib: lib,
+            })
+        }
+    }
+
+    pub fn supports_evs(&self) -> bool {
+        self.set_dump_evs.is_some()
+    }
+
+    pub fn supports_locks(&self) -> bool {
+        self.lock_node.is_some()
+    }
+
+    // Build, solve and dump in one go. `progress` receives (iteration,
+    // exploitability in % of pot) as the library reports them. `dump_evs` is
+    // ignored by libraries without ts_set_dump_evs; `locks` are an error for
+    // libraries without ts_lock_node.
+    #[allow(clippy::too_many_arguments)]
+    pub fn solve(
+        &self,
+        resource_dir: &Path,
+        mode: &str,
+        commands: &str,
+        locks: &[NodeLock],
+        dump_rounds: u32,
+        dump_evs: bool,
+        progress: &mut dyn FnMut(u32, f64),
+    ) -> Result<Value, Box<dyn Error>> {
+        let resource_dir = CString::new(resource_dir.to_string_lossy().as_bytes())?;
+        let mode = CString::new(mode)?;
+        let commands = CString::new(commands)?;
+
+        let ptr = unsafe { (self.create)(resource_dir.as_ptr(), mode.as_ptr()) };
+        if ptr.is_null() {
+            return Err("ts_create returned NULL".into());
+        }
+        let handle = Handle { lib: self, ptr };
+
+        let rc = unsafe { (self.build_tree)(handle.ptr, commands.as_ptr()) };
+        if rc != 0 {
+            return Err(handle.error("ts_build_tree", Some(rc)));
+        }
+
+        for lock in locks {
+            let lock_node = self.lock_node.ok_or("the solver library has no ts_lock_node; it cannot lock nodes")?;
+            let path = CString::new(lock.path.as_str())?;
+            let strategy = CString::new(lock.strategy_json().to_string())?;
+            let rc = unsafe { lock_node(handle.ptr, path.as_ptr(), strategy.as_ptr()) };
+            if rc != 0 {
+                let call = format!("ts_lock_node at '{}'", lock.path);
+                return Err(handle.error(&call, Some(rc)));
+            }
+        }
+
+        // The callback gets a pointer to our `&mut dyn FnMut`, which lives on
+        // this stack frame for the whole ts_solve call.
+        let mut progress = progress;
+        let user = &mut progress as *mut &mut dyn FnMut(u32, f64) as *mut c_void;
+        let rc = unsafe { (self.solve)(handle.ptr, Some(progress_trampoline), user) };
+        if rc != 0 {
+            return Err(handle.error("ts_solve", Some(rc)));
+        }
+
+        if dump_evs && let Some(set_dump_evs) = self.set_dump_evs {
+            let rc = unsafe { set_dump_evs(handle.ptr, 1) };
+            if rc != 0 {
+                return Err(handle.error("ts_set_dump_evs", Some(rc)));
+            }
+        }
+
+        let raw = unsafe { (self.dump)(handle.ptr, dump_rounds) };
+        if raw.is_null() {
+            return Err(handle.error("ts_dump", None));
+        }
+        let parsed = {
+            let text = unsafe { CStr::from_ptr(raw) }.to_string_lossy();
+            serde_json::from_str::<Value>(&text)
+        };
+        unsafe { (self.free_string)(raw) };
+
+        Ok(parsed.map_err(|e| format!("ts_dump returned invalid JSON: {}", e))?)
+    }
+}
+
+unsafe extern "C" fn progress_trampoline(user: *mut c_void, iteration: u32, exploitability: f64) {
+    if user.is_null() {
+        return;
+    }
+    let callback = unsafe { &mut *(user as *mut &mut dyn FnMut(u32, f64)) };
+    callback(iteration, exploitability);
+}
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::boards::RANK_ORDER;
+use crate::evaluator::Equity;
+use crate::json_out::action::Action;
+use crate::json_out::parse::{response_label, HeroStrategy};
+use crate::report::spot::{exploitability_note, grid_class, NodeSpot, Spot, StreetSpot, EQUITY_NOTE};
+
+// Self-contained HTML: one file, inline CSS, no scripts or external assets,
+// so it can be mailed or dropped in a shared folder as is.
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; max-width: 960px; margin: 2em auto; color: #222; }
+h1 { margin-bottom: 0; }
+.meta { color: #666; margin-top: 0.3em; }
+table { border-collapse: collapse; margin: 0.5em 0 1.2em; }
+th, td { border: 1px solid #ddd; padding: 3px 8px; }
+td.num { text-align: right; }
+table.grid td { width: 2.6em; height: 1.6em; text-align: center; font-size: 0.8em; padding: 0; }
+table.grid td.out { background: #f4f4f4; color: #bbb; }
+.bar { display: inline-block; height: 0.8em; background: #999; vertical-align: middle; }
+.bet { background: #d9534f; } .check { background: #5cb85c; } .fold { background: #5b8fd9; } .call { background: #e0b030; }
+.cards { font-weight: bold; }
+.h, .d { color: #c9302c; } .s, .c { color: #1f6f8b; }
+.grids { display: flex; gap: 2em; flex-wrap: wrap; }
+"#;
+
+pub fn render(spot: &Spot) -> String {
+    let mut out = String::new();
+    let p = &spot.profile;
+
+    let _ = writeln!(out, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">");
+    let _ = writeln!(
+        out,
+        "<title>Study report: {} on {}</title>\n<style>{}</style>\n</head>\n<body>",
+        escape(&spot.hero),
+        escape(&spot.board.join(" ")),
+        STYLE
+    );
+    let _ = writeln!(out, "<h1>Study report: {} on {}</h1>", cards(&[spot.hero.as_str()]), cards(&spot.board));
+    let _ = writeln!(
+        out,
+        "<p class=\"meta\">Run <code>{}</code> · {} · profile <code>{}</code> · {} backend, solved in {:.1}s{}</p>",
+        escape(&spot.run_id),
+        escape(&spot.date),
+        escape(&p.name),
+        escape(&spot.backend),
+        spot.elapsed_seconds,
+        exploitability_note(spot)
+    );
+    if !p.description.is_empty() {
+        let _ = writeln!(out, "<blockquote>{}</blockquote>", escape(&p.description));
+    }
+
+    let _ = writeln!(out, "<h2>Setup</h2>\n<table>");
+    let _ = writeln!(out, "<tr><th>Pot</th><th>Effective stack</th><th>SPR</th><th>Iterations</th><th>Target accuracy</th></tr>");
+    let _ = writeln!(
+        out,
+        "<tr><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{:.1}</td><td class=\"num\">{}</td><td class=\"num\">{}%</td></tr>\n</table>",
+        p.pot,
+        p.effective_stack,
+        p.effective_stack / p.pot,
+        p.max_iteration,
+        p.accuracy
+    );
+
+    let _ = writeln!(out, "<div class=\"grids\">");
+    for (title, range) in [("OOP range", &spot.range_oop), ("IP range", &spot.range_ip)] {
+        let _ = writeln!(out, "<div><h3>{}</h3>", title);
+        grid(&mut out, range, |v| (v > 0.0).then(|| (weight_label(v), shade(80, 120, 200, v))));
+        let _ = writeln!(out, "</div>");
+    }
+    let _ = writeln!(out, "</div>");
+
+    for street in &spot.streets {
+        street_section(&mut out, &spot.hero, street);
+    }
+    let _ = writeln!(out, "</body>\n</html>");
+    out
+}
+
+fn street_section(out: &mut String, hero: &str, street: &StreetSpot) {
+    let mut title = street.street.clone();
+    if let Some(first) = title.get_mut(0..1) {
+        first.make_ascii_uppercase();
+    }
+    let _ = writeln!(out, "<h2>{} ({})</h2>", escape(&title), cards(&street.board));
+
+    let hand = street
+        .hand
+        .as_ref()
+        .map(|(d, s)| format!(" — {} (strength {}/100)", escape(d), s))
+        .unwrap_or_default();
+    let _ = writeln!(out, "<p><b>Hero:</b> {}{}</p>", cards(&[hero]), hand);
+
+    let _ = writeln!(out, "<h3>Equity</h3>\n<p>{}</p>\n<table>", EQUITY_NOTE);
+    let _ = writeln!(out, "<tr><th>Hero vs</th><th>Equity</th><th>Win</th><th>Tie</th><th>Combos</th></tr>");
+    equity_row(out, "OOP range (hero IP)", street.equity_vs_oop.as_ref());
+    equity_row(out, "IP range (hero OOP)", street.equity_vs_ip.as_ref());
+    let _ = writeln!(out, "</table>");
+
+    let _ = writeln!(out, "<h3>Hero strategy</h3>");
+    hero_table(out, "OOP first to act", street.oop.as_ref());
+    hero_table(out, "IP after OOP checks", street.ip.as_ref());
+    for response in &street.responses {
+        hero_table(out, &response_label(response), Some(&response.strategy));
+    }
+
+    let _ = writeln!(out, "<h3>Whole-range strategy</h3>");
+    if street.nodes.is_empty() {
+        let _ = writeln!(out, "<p><i>Not in the dumped tree.</i></p>");
+    }
+    for node in &street.nodes {
+        node_section(out, node);
+    }
+}
+
+fn equity_row(out: &mut String, label: &str, equity: Option<&Equity>) {
+    match equity {
+        Some(e) => {
+            let _ = writeln!(
+                out,
+                "<tr><td>{}</td><td class=\"num\">{:.1}%</td><td class=\"num\">{:.1}%</td><td class=\"num\">{:.1}%</td><td class=\"num\">{}</td></tr>",
+                escape(label),
+                e.equity * 100.0,
+                e.win * 100.0,
+                e.tie * 100.0,
+                e.combos
+            );
+        }
+        None => {
+            let _ = writeln!(out, "<tr><td>{}</td><td>–</td><td>–</td><td>–</td><td class=\"num\">0</td></tr>", escape(label));
+        }
+    }
+}
+
+fn hero_table(out: &mut String, title: &str, strategy: Option<&HeroStrategy>) {
+    let _ = writeln!(out, "<h4>{}</h4>", escape(title));
+    match strategy {
+        Some(h) => frequency_table(out, &h.actions, &h.probs),
+        None => {
+            let _ = writeln!(out, "<p><i>No strategy found for this range.</i></p>");
+        }
+    }
+}
+
+fn node_section(out: &mut String, node: &NodeSpot) {
+    let path = if node.path.is_empty() { "(root)" } else { node.path.as_str() };
+    let _ = writeln!(out, "<h4>{} — <code>{}</code></h4>", escape(&node.label), escape(path));
+    frequency_table(out, &node.actions, &node.frequencies);
+    let _ = writeln!(out, "<p>Bet/raise frequency by hand:</p>");
+    grid(out, &node.aggression, |v| Some((format!("{:.0}", v * 100.0), shade(217, 83, 79, v))));
+}
+
+fn frequency_table(out: &mut String, actions: &[String], probs: &[f64]) {
+    let _ = writeln!(out, "<table>");
+    for (action, prob) in actions.iter().zip(probs) {
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td class=\"num\">{:.1}%</td><td><span class=\"bar {}\" style=\"width: {:.0}px\"></span></td></tr>",
+            escape(action),
+            prob * 100.0,
+            action_class(action),
+            prob * 200.0
+        );
+    }
+    let _ = writeln!(out, "</table>");
+}
+
+fn action_class(action: &str) -> &'static str {
+    match Action::parse(action) {
+        Some(a) if a.is_aggressive() => "bet",
+        Some(Action::Check) => "check",
+        Some(Action::Fold) => "fold",
+        Some(Action::Call) => "call",
+        _ => "",
+    }
+}
+
+// 13x13 grid; `cell` gives (label, background) for a class's value, or None
+// to grey the cell out. Classes without a value are greyed out too.
+fn grid(out: &mut String, values: &HashMap<String, f64>, cell: impl Fn(f64) -> Option<(String, String)>) {
+    let _ = writeln!(out, "<table class=\"grid\">");
+    for row in 0..RANK_ORDER.len() {
+        let _ = write!(out, "<tr>");
+        for col in 0..RANK_ORDER.len() {
+            let class = grid_class(row, col);
+            match values.get(&class).and_then(|v| cell(*v)) {
+                Some((label, background)) => {
+                    let _ = write!(
+                        out,
+                        "<td style=\"background: {}\" title=\"{}\">{}<br>{}</td>",
+                        background, class, class, label
+                    );
+                }
+                None => {
+                    let _ = write!(out, "<td class=\"out\">{}</td>", class);
+                }
+            }
+        }
+        let _ = writeln!(out, "</tr>");
+    }
+    let _ = writeln!(out, "</table>");
+}
+
+// `rgb` blended with white by `amount` (0..1).
+fn shade(r: u8, g: u8, b: u8, amount: f64) -> String {
+    let a = amount.clamp(0.0, 1.0);
+    let mix = |c: u8| (255.0 - (255.0 - c as f64) * a).round() as u8;
+    format!("rgb({}, {}, {})", mix(r), mix(g), mix(b))
+}
+
+fn weight_label(v: f64) -> String {
+    if v.fract() == 0.0 { format!("{}", v) } else { format!("{:.2}", v) }
+}
+
+// "Qs", "AhKd" -> cards coloured by suit.
+fn cards<S: AsRef<str>>(list: &[S]) -> String {
+    let spans: Vec<String> = list
+        .iter()
+        .map(|text| {
+            text.as_ref()
+                .as_bytes()
+                .chunks(2)
+                .map(|c| {
+                    let card = String::from_utf8_lossy(c);
+                    let suit = card.get(1..2).unwrap_or("");
+                    format!("<span class=\"{}\">{}</span>", escape(suit), escape(&card))
+                })
+                .collect::<String>()
+        })
+        .collect();
+    format!("<span class=\"cards\">{}</span>", spans.join(" "))
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
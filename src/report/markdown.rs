@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::boards::RANK_ORDER;
+use crate::evaluator::Equity;
+use crate::json_out::parse::{response_label, HeroStrategy};
+use crate::report::spot::{exploitability_note, grid_class, NodeSpot, Spot, StreetSpot, EQUITY_NOTE};
+
+// Self-contained Markdown (GitHub flavour: tables, no HTML).
+
+pub fn render(spot: &Spot) -> String {
+    let mut out = String::new();
+    let p = &spot.profile;
+
+    let _ = writeln!(out, "# Study report: {} on {}\n", spot.hero, spot.board.join(" "));
+    let _ = writeln!(
+        out,
+        "*Run `{}` · {} · profile `{}` · {} backend, solved in {:.1}s{}*\n",
+        spot.run_id,
+        spot.date,
+        p.name,
+        spot.backend,
+        spot.elapsed_seconds,
+        exploitability_note(spot)
+    );
+    if !p.description.is_empty() {
+        let _ = writeln!(out, "> {}\n", p.description);
+    }
+
+    let _ = writeln!(out, "## Setup\n");
+    let _ = writeln!(out, "| Pot | Effective stack | SPR | Iterations | Target accuracy |");
+    let _ = writeln!(out, "|---:|---:|---:|---:|---:|");
+    let _ = writeln!(
+        out,
+        "| {} | {} | {:.1} | {} | {}% |\n",
+        p.pot,
+        p.effective_stack,
+        p.effective_stack / p.pot,
+        p.max_iteration,
+        p.accuracy
+    );
+
+    let _ = writeln!(out, "### OOP range (weights)\n");
+    grid(&mut out, &spot.range_oop, weight_cell);
+    let _ = writeln!(out, "### IP range (weights)\n");
+    grid(&mut out, &spot.range_ip, weight_cell);
+
+    for street in &spot.streets {
+        street_section(&mut out, &spot.hero, street);
+    }
+    out
+}
+
+fn street_section(out: &mut String, hero: &str, street: &StreetSpot) {
+    let _ = writeln!(out, "## {} ({})\n", capitalize(&street.street), street.board.join(" "));
+
+    match &street.hand {
+        Some((desc, strength)) => {
+            let _ = writeln!(out, "**Hero:** {} — {} (strength {}/100)\n", hero, desc, strength);
+        }
+        None => {
+            let _ = writeln!(out, "**Hero:** {}\n", hero);
+        }
+    }
+
+    let _ = writeln!(out, "### Equity\n");
+    let _ = writeln!(out, "{}\n", EQUITY_NOTE);
+    let _ = writeln!(out, "| Hero vs | Equity | Win | Tie | Combos |");
+    let _ = writeln!(out, "|---|---:|---:|---:|---:|");
+    equity_row(out, "OOP range (hero IP)", street.equity_vs_oop.as_ref());
+    equity_row(out, "IP range (hero OOP)", street.equity_vs_ip.as_ref());
+    out.push('\n');
+
+    let _ = writeln!(out, "### Hero strategy\n");
+    hero_table(out, "OOP first to act", street.oop.as_ref());
+    hero_table(out, "IP after OOP checks", street.ip.as_ref());
+    for response in &street.responses {
+        hero_table(out, &response_label(response), Some(&response.strategy));
+    }
+
+    let _ = writeln!(out, "### Whole-range strategy\n");
+    if street.nodes.is_empty() {
+        let _ = writeln!(out, "*Not in the dumped tree.*\n");
+    }
+    for node in &street.nodes {
+        node_section(out, node);
+    }
+}
+
+fn equity_row(out: &mut String, label: &str, equity: Option<&Equity>) {
+    match equity {
+        Some(e) => {
+            let _ = writeln!(
+                out,
+                "| {} | {:.1}% | {:.1}% | {:.1}% | {} |",
+                label,
+                e.equity * 100.0,
+                e.win * 100.0,
+                e.tie * 100.0,
+                e.combos
+            );
+        }
+        None => {
+            let _ = writeln!(out, "| {} | – | – | – | 0 |", label);
+        }
+    }
+}
+
+fn hero_table(out: &mut String, title: &str, strategy: Option<&HeroStrategy>) {
+    let _ = writeln!(out, "**{}**\n", title);
+    let Some(h) = strategy else {
+        let _ = writeln!(out, "*No strategy found for this range.*\n");
+        return;
+    };
+    let _ = writeln!(out, "| Action | Frequency |");
+    let _ = writeln!(out, "|---|---:|");
+    for (action, prob) in h.actions.iter().zip(&h.probs) {
+        let _ = writeln!(out, "| {} | {:.1}% |", action, prob * 100.0);
+    }
+    out.push('\n');
+}
+
+fn node_section(out: &mut String, node: &NodeSpot) {
+    let path = if node.path.is_empty() { "(root)" } else { node.path.as_str() };
+    let _ = writeln!(out, "#### {} — `{}`\n", node.label, path);
+    let _ = writeln!(out, "| {} |", node.actions.join(" | "));
+    let _ = writeln!(out, "|{}", "---:|".repeat(node.actions.len()));
+    let cells: Vec<String> = node.frequencies.iter().map(|f| format!("{:.1}%", f * 100.0)).collect();
+    let _ = writeln!(out, "| {} |\n", cells.join(" | "));
+    let _ = writeln!(out, "Bet/raise frequency by hand (%):\n");
+    grid(out, &node.aggression, |v| format!("{:.0}", v * 100.0));
+}
+
+// 13x13 table of `values`, one cell per hand class; classes without a value
+// are left blank.
+fn grid(out: &mut String, values: &HashMap<String, f64>, cell: impl Fn(f64) -> String) {
+    let ranks: Vec<char> = RANK_ORDER.chars().collect();
+    let _ = writeln!(out, "| | {} |", ranks.iter().map(|r| r.to_string()).collect::<Vec<_>>().join(" | "));
+    let _ = writeln!(out, "|---|{}", ":---:|".repeat(13));
+    for (row, r) in ranks.iter().enumerate() {
+        let cells: Vec<String> = (0..13)
+            .map(|col| values.get(&grid_class(row, col)).map(|v| cell(*v)).unwrap_or_default())
+            .collect();
+        let _ = writeln!(out, "| **{}** | {} |", r, cells.join(" | "));
+    }
+    out.push('\n');
+}
+
+// Range weights: blank when out of the range, "1" or "0.25" otherwise.
+fn weight_cell(v: f64) -> String {
+    if v <= 0.0 {
+        String::new()
+    } else if v.fract() == 0.0 {
+        format!("{}", v)
+    } else {
+        format!("{:.2}", v)
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,3 @@
+pub mod spot;
+pub mod markdown;
+pub mod html;
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use serde_json::Value;
+
+use crate::boards::RANK_ORDER;
+use crate::config::Profile;
+use crate::evaluator::{equity_vs_range, evaluate_hand_parts, Equity};
+use crate::history::format_timestamp;
+use crate::json_out::action::is_aggressive;
+use crate::json_out::emit::{responses_from_json, strategy_from_json};
+use crate::json_out::parse::{line_player, node_strategies, range_frequencies, response_nodes, short_action, street_node, HeroStrategy, Response};
+use crate::solver::{activate_hand_in_range, get_generic_hand, range_weights};
+
+// Everything a study report shows, gathered once so the Markdown and HTML
+// renderers only differ in layout. Built from a history record (the same
+// strategies `print_strategy_section` showed) and the run's saved tree.
+pub struct Spot {
+    pub run_id: String,
+    pub date: String,
+    pub profile: Profile,
+    pub hero: String,
+    pub board: Vec<String>,
+    pub backend: String,
+    pub elapsed_seconds: f64,
+    // % of pot, when the solver reported it.
+    pub exploitability: Option<f64>,
+    // Hand class -> weight, as solved: the profile ranges with hero's class
+    // switched on (see `activate_hand_in_range`).
+    pub range_oop: HashMap<String, f64>,
+    pub range_ip: HashMap<String, f64>,
+    pub streets: Vec<StreetSpot>,
+}
+
+pub struct StreetSpot {
+    pub street: String,
+    pub board: Vec<String>,
+    // (description, strength 0-100) as in the terminal header.
+    pub hand: Option<(String, u8)>,
+    pub oop: Option<HeroStrategy>,
+    pub ip: Option<HeroStrategy>,
+    // Facing each bet and raise, as in the terminal.
+    pub responses: Vec<Response>,
+    // Against the profile's ranges as given, without hero's class switched
+    // on in them.
+    pub equity_vs_oop: Option<Equity>,
+    pub equity_vs_ip: Option<Equity>,
+    pub nodes: Vec<NodeSpot>,
+}
+
+// The whole range at one of the decision points of a street.
+pub struct NodeSpot {
+    pub label: String,
+    // Node path from the tree root, usable with `export-csv`.
+    pub path: String,
+    pub actions: Vec<String>,
+    // Range-weighted share of each action.
+    pub frequencies: Vec<f64>,
+    // Hand class -> bet/raise frequency, averaged over the class's combos.
+    pub aggression: HashMap<String, f64>,
+}
+
+// Shown under the equity table of each street.
+pub const EQUITY_NOTE: &str = "Against each range as the profile gives it: hero's class is not added to it, unlike in the solve.";
+
+// The class in a 13x13 grid cell: pairs on the diagonal, suited hands above
+// it, offsuit hands below.
+pub fn grid_class(row: usize, col: usize) -> String {
+    let ranks: Vec<char> = RANK_ORDER.chars().collect();
+    match row.cmp(&col) {
+        std::cmp::Ordering::Equal => format!("{}{}", ranks[row], ranks[col]),
+        std::cmp::Ordering::Less => format!("{}{}s", ranks[row], ranks[col]),
+        std::cmp::Ordering::Greater => format!("{}{}o", ranks[col], ranks[row]),
+    }
+}
+
+// ", exploitability 0.42% pot" for the report header.
+pub fn exploitability_note(spot: &Spot) -> String {
+    spot.exploitability.map(|x| format!(", exploitability {:.2}% pot", x)).unwrap_or_default()
+}
+
+pub fn build(record: &Value, tree: &Value) -> Result<Spot, Box<dyn Error>> {
+    let name = record["job"]["profile"].as_str().unwrap_or("history");
+    let profile = Profile::from_json(name, &record["job"])?;
+    let hero = record["inputs"]["hero"].as_str().ok_or("record has no hero hand")?.to_string();
+    let generic = get_generic_hand(&hero);
+    let range_oop = range_weights(&activate_hand_in_range(&profile.range_oop, &generic));
+    let range_ip = range_weights(&activate_hand_in_range(&profile.range_ip, &generic));
+    let villain_oop = range_weights(&profile.range_oop);
+    let villain_ip = range_weights(&profile.range_ip);
+
+    let mut streets = Vec::new();
+    for street in record["streets"].as_array().map(Vec::as_slice).unwrap_or_default() {
+        let board: Vec<String> = street["board"]
+            .as_array()
+            .map(|a| a.iter().filter_map(|c| c.as_str().map(String::from)).collect())
+            .unwrap_or_default();
+        if board.len() < 3 {
+            continue;
+        }
+        let board_str = board.join(",");
+        let start = street_node(tree, board.get(3).map(String::as_str), board.get(4).map(String::as_str));
+        let prefix = board[3..]
+            .iter()
+            .map(|card| format!("CHECK/CHECK/{}/", card))
+            .collect::<String>();
+
+        streets.push(StreetSpot {
+            street: street["street"].as_str().unwrap_or("?").to_string(),
+            hand: evaluate_hand_parts(&hero, &board_str, profile.game),
+            oop: strategy_from_json(&street["oop"]),
+            ip: strategy_from_json(&street["ip"]),
+            responses: responses_from_json(street),
+            equity_vs_oop: equity_vs_range(&hero, &board_str, &villain_oop, profile.game),
+            equity_vs_ip: equity_vs_range(&hero, &board_str, &villain_ip, profile.game),
+            nodes: start.map(|n| street_nodes(n, &prefix, &range_oop, &range_ip)).unwrap_or_default(),
+            board,
+        });
+    }
+
+    let board = streets.last().map(|s| s.board.clone()).unwrap_or_default();
+    Ok(Spot {
+        run_id: record["run_id"].as_str().unwrap_or("?").to_string(),
+        date: format_timestamp(record["timestamp"].as_u64().unwrap_or(0)),
+        profile,
+        hero,
+        board,
+        backend: record["solver"]["backend"].as_str().unwrap_or("?").to_string(),
+        elapsed_seconds: record["solver"]["elapsed_seconds"].as_f64().unwrap_or(0.0),
+        exploitability: record["solver"]["exploitability_pct"].as_f64(),
+        range_oop,
+        range_ip,
+        streets,
+    })
+}
+
+// The same nodes `extract_street_strategies` reads hero's strategy from.
+fn street_nodes(
+    start: &Value,
+    prefix: &str,
+    range_oop: &HashMap<String, f64>,
+    range_ip: &HashMap<String, f64>,
+) -> Vec<NodeSpot> {
+    let mut found: Vec<(String, String, &Value)> = vec![("OOP first to act".to_string(), prefix.to_string(), start)];
+    if let Some(check) = start.get("childrens").and_then(|c| c.get("CHECK")) {
+        found.push(("IP after OOP checks".to_string(), format!("{}CHECK", prefix), check));
+    }
+    for (line, node) in response_nodes(start) {
+        let shown: Vec<String> = line.iter().map(|a| short_action(a)).collect();
+        found.push((format!("{} facing {}", line_player(&line), shown.join(", ")), format!("{}{}", prefix, line.join("/")), node));
+    }
+
+    found
+        .into_iter()
+        .filter_map(|(label, path, node)| {
+            let weights = match node.get("player").and_then(|p| p.as_u64()) {
+                Some(0) => Some(range_ip),
+                Some(1) => Some(range_oop),
+                _ => None,
+            };
+            summarize_node(label, path.trim_end_matches('/').to_string(), node, weights)
+        })
+        .collect()
+}
+
+fn summarize_node(
+    label: String,
+    path: String,
+    node: &Value,
+    weights: Option<&HashMap<String, f64>>,
+) -> Option<NodeSpot> {
+    let strategy = node_strategies(node)?;
+    // class -> (sum of aggressive frequency, combos)
+    let mut by_class: HashMap<String, (f64, usize)> = HashMap::new();
+
+    for (combo, probs) in &strategy.combos {
+        let aggressive: f64 = strategy
+            .actions
+            .iter()
+            .zip(probs)
+            .filter(|(a, _)| is_aggressive(a))
+            .map(|(_, p)| p)
+            .sum();
+        let entry = by_class.entry(get_generic_hand(combo)).or_insert((0.0, 0));
+        entry.0 += aggressive;
+        entry.1 += 1;
+    }
+
+    Some(NodeSpot {
+        label,
+        path,
+        frequencies: range_frequencies(&strategy, weights),
+        actions: strategy.actions,
+        aggression: by_class.into_iter().map(|(c, (sum, n))| (c, sum / n as f64)).collect(),
+    })
+}
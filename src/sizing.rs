@@ -0,0 +1,200 @@
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::json_out::action::chips;
+
+// Bet size expressions, as written in a profile's `bet_sizes` lists:
+//   50, "33%"   % of the pot (a bare number is a percentage, as before)
+//   "2.5x"      raise to 2.5 times the bet faced (raise lists only)
+//   "25c"       chips: a bet of 25, or a raise to 25
+//   "3bb"       big blinds, turned into chips with the profile's big_blind
+//   "geo2"      the same % of pot on N streets in a row puts the stacks in,
+//               counting this street (bet lists only)
+//   "allin"     same as setting `allin: true` for that street
+//
+// The solver takes one % of pot per size and street, so everything else is
+// resolved to that once, against the profile's starting pot and effective
+// stack. On the turn and river that is the pot after earlier streets check
+// through. A raise is resolved against the first size the opponent bets
+// (or donks) on that street.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SizeExpr {
+    PotPct(f64),
+    Times(f64),
+    Chips(f64),
+    BigBlinds(f64),
+    Geo(u32),
+    AllIn,
+}
+
+// What the solver sees: a % of pot, or the street's all-in flag.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolved {
+    Pct(f64),
+    AllIn,
+}
+
+// The starting pot and stack expressions are resolved against.
+#[derive(Debug, Clone, Copy)]
+pub struct Sizing {
+    pub pot: f64,
+    pub stack: f64,
+    pub big_blind: f64,
+}
+
+impl SizeExpr {
+    pub fn parse(v: &Value) -> Result<SizeExpr, String> {
+        if let Some(pct) = v.as_f64() {
+            return positive(pct, v).map(SizeExpr::PotPct);
+        }
+        let text = v.as_str().ok_or_else(|| format!("expected a size like 50, \"33%\", \"2.5x\", \"25c\", \"3bb\", \"geo2\" or \"allin\", got {}", v))?;
+        let t = text.trim().to_ascii_lowercase();
+        let number = |s: &str| s.trim().parse::<f64>().ok().filter(|n| *n > 0.0).ok_or_else(|| format!("'{}' is not a size", text));
+        if t == "allin" || t == "all-in" {
+            Ok(SizeExpr::AllIn)
+        } else if let Some(n) = t.strip_prefix("geo") {
+            match n.trim().parse::<u32>() {
+                Ok(n) if n >= 1 => Ok(SizeExpr::Geo(n)),
+                _ => Err(format!("'{}' needs a number of streets, e.g. geo2", text)),
+            }
+        } else if let Some(n) = t.strip_suffix('%') {
+            number(n).map(SizeExpr::PotPct)
+        } else if let Some(n) = t.strip_suffix("bb") {
+            number(n).map(SizeExpr::BigBlinds)
+        } else if let Some(n) = t.strip_suffix('c') {
+            number(n).map(SizeExpr::Chips)
+        } else if let Some(n) = t.strip_suffix('x') {
+            match number(n)? {
+                x if x > 1.0 => Ok(SizeExpr::Times(x)),
+                _ => Err(format!("'{}' must raise to more than 1x the bet", text)),
+            }
+        } else {
+            number(&t).map(SizeExpr::PotPct)
+        }
+    }
+}
+
+fn positive(n: f64, v: &Value) -> Result<f64, String> {
+    if n > 0.0 { Ok(n) } else { Err(format!("must be greater than 0, got {}", v)) }
+}
+
+impl fmt::Display for SizeExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SizeExpr::PotPct(p) => write!(f, "{}%", p),
+            SizeExpr::Times(x) => write!(f, "{}x", x),
+            SizeExpr::Chips(c) => write!(f, "{}c", c),
+            SizeExpr::BigBlinds(b) => write!(f, "{}bb", b),
+            SizeExpr::Geo(n) => write!(f, "geo{}", n),
+            SizeExpr::AllIn => write!(f, "allin"),
+        }
+    }
+}
+
+impl Sizing {
+    // A bet or donk on `street` (0 = flop).
+    pub fn bet(&self, expr: SizeExpr, street: usize) -> Result<Resolved, String> {
+        let pct = match expr {
+            SizeExpr::AllIn => return Ok(Resolved::AllIn),
+            SizeExpr::PotPct(p) => p,
+            SizeExpr::Times(_) => return Err(format!("'{}' is a raise size; bets take % of pot, chips or geoN", expr)),
+            SizeExpr::Chips(_) | SizeExpr::BigBlinds(_) => self.chips(expr)? / self.pot * 100.0,
+            SizeExpr::Geo(n) => {
+                let left = 3 - street;
+                if n as usize > left {
+                    let left = if left == 1 { "only the river is".to_string() } else { format!("only {} streets are", left) };
+                    return Err(format!("'{}' needs {} streets but {} left", expr, n, left));
+                }
+                // pot * (1 + 2f)^n = pot + 2 * stack
+                ((1.0 + 2.0 * self.stack / self.pot).powf(1.0 / n as f64) - 1.0) / 2.0 * 100.0
+            }
+        };
+        Ok(Resolved::Pct(round(pct)))
+    }
+
+    // A raise facing a bet of `faced` % of pot (None when the opponent has
+    // no bet on that street).
+    pub fn raise(&self, expr: SizeExpr, faced: Option<f64>) -> Result<Resolved, String> {
+        let raise_to = match expr {
+            SizeExpr::AllIn => return Ok(Resolved::AllIn),
+            SizeExpr::PotPct(p) => return Ok(Resolved::Pct(p)),
+            SizeExpr::Geo(_) => return Err(format!("'{}' is a bet size; raises take % of pot, Nx or chips", expr)),
+            SizeExpr::Times(_) | SizeExpr::Chips(_) | SizeExpr::BigBlinds(_) => expr,
+        };
+        let faced = faced.ok_or_else(|| format!("'{}' needs a bet to raise, but the opponent has no bet size on this street", expr))?;
+        let bet = faced / 100.0 * self.pot;
+        let to = match raise_to {
+            SizeExpr::Times(x) => x * bet,
+            _ => self.chips(raise_to)?,
+        };
+        if to <= bet {
+            return Err(format!("'{}' raises to {} but the bet it faces is already {}", expr, chips(to), chips(bet)));
+        }
+        // The solver's raise is call + pct * (pot after the call).
+        Ok(Resolved::Pct(round((to - bet) / (self.pot + 2.0 * bet) * 100.0)))
+    }
+
+    fn chips(&self, expr: SizeExpr) -> Result<f64, String> {
+        let amount = match expr {
+            SizeExpr::BigBlinds(b) => b * self.big_blind,
+            SizeExpr::Chips(c) => c,
+            _ => unreachable!("only chip sizes are converted"),
+        };
+        if amount > self.stack {
+            return Err(format!("'{}' is {} chips, more than the effective stack of {}; use allin", expr, chips(amount), chips(self.stack)));
+        }
+        Ok(amount)
+    }
+}
+
+// To a tenth of a percent, which is plenty for the solver.
+fn round(pct: f64) -> f64 {
+    (pct * 10.0).round() / 10.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SIZING: Sizing = Sizing { pot: 10.0, stack: 95.0, big_blind: 2.0 };
+
+    fn parse(v: Value) -> SizeExpr {
+        SizeExpr::parse(&v).unwrap()
+    }
+
+    #[test]
+    fn geo_puts_the_stacks_in_over_n_streets() {
+        // 10 * (1 + 2f)^2 = 10 + 2 * 95, so 1 + 2f = sqrt(20)
+        assert_eq!(SIZING.bet(parse(json!("geo2")), 1), Ok(Resolved::Pct(173.6)));
+        assert!(SIZING.bet(parse(json!("geo2")), 2).is_err());
+    }
+
+    #[test]
+    fn times_raises_against_the_bet_faced() {
+        // A 33% bet is 3.3; raising to 8.25 adds 4.95 to a pot of 16.6 after the call.
+        assert_eq!(SIZING.raise(parse(json!("2.5x")), Some(33.0)), Ok(Resolved::Pct(29.8)));
+        assert!(SIZING.raise(parse(json!("2.5x")), None).is_err());
+    }
+
+    #[test]
+    fn chips_and_big_blinds_become_pot_percentages() {
+        assert_eq!(SIZING.bet(parse(json!("25c")), 0), Ok(Resolved::Pct(250.0)));
+        assert_eq!(SIZING.bet(parse(json!("3bb")), 0), Ok(Resolved::Pct(60.0)));
+    }
+
+    #[test]
+    fn chips_above_the_stack_are_an_error() {
+        let short = Sizing { stack: 20.0, ..SIZING };
+        let err = short.bet(parse(json!("25c")), 0).unwrap_err();
+        assert!(err.contains("more than the effective stack"), "{}", err);
+    }
+
+    #[test]
+    fn times_is_not_a_bet_size() {
+        let err = SIZING.bet(parse(json!("2.5x")), 0).unwrap_err();
+        assert!(err.contains("raise size"), "{}", err);
+    }
+}
@@ -1,15 +1,22 @@
+use std::collections::HashMap;
 use std::error::Error;
 use std::fs;
-use std::io::Write;
-use std::path::{Path, PathBuf};
-use std::process::Command;
-use std::env;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::boards::RANK_ORDER;
+use crate::config::{Backend, PlayerBetSizes, Profile, SolverSettings};
+use crate::discovery::{find_library, find_resource_dir, find_solver, get_exe_dir};
+use crate::json_out::parse::load_tree;
+use crate::library::SolverLibrary;
 
 // We will resolve these relative to the executable path at runtime
 const JOB_FILE_REL_PATH: &str = "resources/text/job_config_debug.txt";
 const OUTPUT_JSON_REL_PATH: &str = "strategy_debug.json";
-const RESOURCE_DIR_REL: &str = "resources";
-const CONSOLE_SOLVER_REL_PATH: &str = "TexasSolver-v0.2.0-MacOs/console_solver";
 
 // Public preflop ranges used in the job config. These come from the solver's
 // own presets (qb_ranges, 100bb 2.5x 500rake) so that our TUI matches a
@@ -19,67 +26,120 @@ pub const RANGE_IP: &str = "AA:1.0,A2s:1.0,A2o:0.0,A3s:1.0,A3o:0.016,A4s:1.0,A4o
 // OOP (out of position) = BB call vs BTN 2.5x open at 100bb.
 pub const RANGE_OOP: &str = "AA:0.0,A2s:1.0,A2o:0.0,A3s:0.822,A3o:0.0,A4s:0.282,A4o:0.48,A5s:0.0,A5o:0.93,A6s:0.766,A6o:0.432,A7s:0.412,A7o:0.976,A8s:0.616,A8o:0.928,A9s:0.818,A9o:0.876,ATs:0.13,ATo:0.918,AJs:0.0,AJo:0.526,AQs:0.0,AQo:0.03,AKs:0.0,AKo:0.0,22:1.0,32s:0.278,32o:0.0,42s:0.796,42o:0.0,52s:1.0,52o:0.0,62s:0.0,62o:0.0,72s:0.0,72o:0.0,82s:0.0,82o:0.0,92s:0.0,92o:0.0,T2s:0.0,T2o:0.0,J2s:0.782,J2o:0.0,Q2s:1.0,Q2o:0.0,K2s:1.0,K2o:0.0,33:1.0,43s:1.0,43o:0.0,53s:0.904,53o:0.0,63s:1.0,63o:0.0,73s:0.032,73o:0.0,83s:0.0,83o:0.0,93s:0.0,93o:0.0,T3s:0.23,T3o:0.0,J3s:1.0,J3o:0.0,Q3s:1.0,Q3o:0.0,K3s:1.0,K3o:0.0,44:1.0,54s:0.396,54o:0.0,64s:0.904,64o:0.0,74s:1.0,74o:0.0,84s:0.136,84o:0.0,94s:0.0,94o:0.0,T4s:0.252,T4o:0.0,J4s:0.996,J4o:0.0,Q4s:1.0,Q4o:0.0,K4s:1.0,K4o:0.0,55:0.972,65s:0.456,65o:0.0,75s:0.82,75o:0.0,85s:1.0,85o:0.0,95s:0.22,95o:0.0,T5s:0.622,T5o:0.0,J5s:0.802,J5o:0.0,Q5s:0.98,Q5o:0.0,K5s:0.898,K5o:0.0,66:0.832,76s:0.346,76o:0.224,86s:0.824,86o:0.0,96s:0.924,96o:0.0,T6s:0.758,T6o:0.0,J6s:0.84,J6o:0.0,Q6s:0.932,Q6o:0.0,K6s:0.736,K6o:0.0,77:0.704,87s:0.212,87o:0.382,97s:0.818,97o:0.0,T7s:0.726,T7o:0.0,J7s:0.55,J7o:0.0,Q7s:0.992,Q7o:0.0,K7s:0.856,K7o:0.0,88:0.486,98s:0.338,98o:0.372,T8s:0.248,T8o:0.42,J8s:0.606,J8o:0.038,Q8s:0.766,Q8o:0.0,K8s:0.64,K8o:0.442,99:0.084,T9s:0.0,T9o:0.876,J9s:0.0,J9o:0.89,Q9s:0.068,Q9o:1.0,K9s:0.306,K9o:0.91,TT:0.0,JTs:0.0,JTo:0.776,QTs:0.122,QTo:0.796,KTs:0.026,KTo:0.802,JJ:0.0,QJs:0.06,QJo:0.904,KJs:0.0,KJo:0.696,QQ:0.0,KQs:0.0,KQo:0.474,KK:0.0";
 
-fn get_exe_dir() -> PathBuf {
-    env::current_exe()
-        .ok()
-        .and_then(|p| p.parent().map(|p| p.to_path_buf()))
-        .unwrap_or_else(|| PathBuf::from("."))
-}
-
 fn get_absolute_path(rel_path: &str) -> PathBuf {
     get_exe_dir().join(rel_path)
 }
 
-fn build_job_content(board: &str, hero_hand: &str) -> String {
-    let generic = get_generic_hand(hero_hand);
-    
-    // Determine how many rounds to dump based on the board length.
-    // Board format is comma separated cards.
-    // 3 cards = Flop (dump 1 round)
-    // 4 cards = Turn (dump 2 rounds)
-    // 5 cards = River (dump 3 rounds)
+pub fn job_file_path() -> PathBuf {
+    get_absolute_path(JOB_FILE_REL_PATH)
+}
+
+pub fn output_json_path() -> PathBuf {
+    get_absolute_path(OUTPUT_JSON_REL_PATH)
+}
+
+// How many rounds to dump based on the board length.
+// Board format is comma separated cards.
+// 3 cards = Flop (dump 1 round)
+// 4 cards = Turn (dump 2 rounds)
+// 5 cards = River (dump 3 rounds)
+fn dump_rounds_for(board: &str) -> u32 {
     let card_count = board.split(',').filter(|s| !s.is_empty()).count();
-    let dump_rounds = match card_count {
+    match card_count {
         3 => 1,
         4 => 2,
         _ => 3,
-    };
+    }
+}
 
-    let output_path = get_absolute_path(OUTPUT_JSON_REL_PATH);
-    // We need to escape the path for the config file if it contains spaces, 
-    // but the solver might just take the string.
-    // For safety, we just pass the path string.
-    let output_path_str = output_path.to_string_lossy();
+// The `set_*` part of the job: everything the solver needs to build the tree
+// and solve it. Shared by the console_solver input file and the library
+// backend's ts_build_tree.
+fn tree_commands(board: &str, hero_hand: &str, profile: &Profile) -> String {
+    // No hero (e.g. multi-flop batches): solve the ranges as configured.
+    let generic = if hero_hand.is_empty() { String::new() } else { get_generic_hand(hero_hand) };
 
     let content = format!(
-        r#"set_pot 50
-set_effective_stack 200
+        r#"set_pot {pot}
+set_effective_stack {stack}
 set_board {board}
 set_range_ip {range_ip}
 set_range_oop {range_oop}
-set_bet_sizes ip,flop,bet,50
-set_bet_sizes ip,turn,bet,50
-set_allin_threshold 0.8
-set_thread_num 8
-set_accuracy 5.0
-set_max_iteration 10
-set_print_interval 10
-set_use_isomorphism 1
-build_tree
-start_solve
-set_dump_rounds {dump_rounds}
-dump_result {output_path}
+{bet_sizes}set_allin_threshold {allin_threshold}
+set_raise_limit {raise_limit}
+set_thread_num {thread_num}
+set_accuracy {accuracy}
+set_max_iteration {max_iteration}
+set_print_interval {print_interval}
+set_use_isomorphism {use_isomorphism}
 "#,
+        pot = format_number(profile.pot),
+        stack = format_number(profile.effective_stack),
         board = board,
-        range_ip = activate_hand_in_range(RANGE_IP, &generic),
-        range_oop = activate_hand_in_range(RANGE_OOP, &generic),
-        output_path = output_path_str,
-        dump_rounds = dump_rounds
+        range_ip = activate_hand_in_range(&profile.range_ip, &generic),
+        range_oop = activate_hand_in_range(&profile.range_oop, &generic),
+        bet_sizes = bet_size_lines(profile).iter().map(|l| format!("{}\n", l)).collect::<String>(),
+        allin_threshold = profile.allin_threshold,
+        raise_limit = profile.raise_limit,
+        thread_num = profile.thread_num,
+        accuracy = format_number(profile.accuracy),
+        max_iteration = profile.max_iteration,
+        print_interval = profile.print_interval,
+        use_isomorphism = if profile.use_isomorphism { 1 } else { 0 },
     );
     content.trim().to_string() + "\n"
 }
 
-fn get_generic_hand(hand: &str) -> String {
+fn build_job_content(board: &str, hero_hand: &str, profile: &Profile) -> String {
+    let output_path = get_absolute_path(OUTPUT_JSON_REL_PATH);
+    // We need to escape the path for the config file if it contains spaces, 
+    // but the solver might just take the string.
+    // For safety, we just pass the path string.
+    let output_path_str = output_path.to_string_lossy();
+
+    format!(
+        "{commands}build_tree\nstart_solve\nset_dump_rounds {dump_rounds}\ndump_result {output_path}\n",
+        commands = tree_commands(board, hero_hand, profile),
+        dump_rounds = dump_rounds_for(board),
+        output_path = output_path_str,
+    )
+}
+
+// One `set_bet_sizes player,street,kind,sizes...` line per configured kind.
+// OOP lines come first, matching the solver's sample inputs.
+fn bet_size_lines(profile: &Profile) -> Vec<String> {
+    let mut lines = Vec::new();
+    let players: [(&str, &PlayerBetSizes); 2] = [
+        ("oop", &profile.bet_sizes_oop),
+        ("ip", &profile.bet_sizes_ip),
+    ];
+    for (player, sizes) in players {
+        for (street, s) in sizes.streets() {
+            for (kind, list) in [("bet", &s.bet), ("raise", &s.raise), ("donk", &s.donk)] {
+                if list.is_empty() {
+                    continue;
+                }
+                let joined = list.iter().map(|v| format_number(*v)).collect::<Vec<_>>().join(",");
+                lines.push(format!("set_bet_sizes {},{},{},{}", player, street, kind, joined));
+            }
+            if s.allin {
+                lines.push(format!("set_bet_sizes {},{},allin", player, street));
+            }
+        }
+    }
+    lines
+}
+
+// 50.0 -> "50", 2.5 -> "2.5"
+fn format_number(v: f64) -> String {
+    if v.fract() == 0.0 {
+        format!("{}", v as i64)
+    } else {
+        format!("{}", v)
+    }
+}
+
+pub fn get_generic_hand(hand: &str) -> String {
     // hand is like "AhKh" or "AsKs"
     let chars: Vec<char> = hand.chars().collect();
     if chars.len() < 4 { return "AA".to_string(); } // fallback
@@ -90,10 +150,8 @@ fn get_generic_hand(hand: &str) -> String {
     let s2 = chars[3];
 
     // Sort ranks to match standard notation (AK, not KA)
-    // Ranks: A, K, Q, J, T, 9, 8, 7, 6, 5, 4, 3, 2
-    let rank_order = "AKQJT98765432";
-    let idx1 = rank_order.find(r1).unwrap_or(0);
-    let idx2 = rank_order.find(r2).unwrap_or(0);
+    let idx1 = RANK_ORDER.find(r1).unwrap_or(0);
+    let idx2 = RANK_ORDER.find(r2).unwrap_or(0);
 
     let (first, second) = if idx1 <= idx2 { (r1, r2) } else { (r2, r1) };
 
@@ -106,7 +164,7 @@ fn get_generic_hand(hand: &str) -> String {
     }
 }
 
-fn activate_hand_in_range(range: &str, target_generic: &str) -> String {
+pub fn activate_hand_in_range(range: &str, target_generic: &str) -> String {
     // Optimization:
     // 1. If the hand is the user's hand (target_generic), we force it to 1.0 (even if it was 0.0).
     // 2. If the hand has 0.0 weight AND is NOT the user's hand, we remove it entirely.
@@ -143,39 +201,70 @@ fn activate_hand_in_range(range: &str, target_generic: &str) -> String {
         .join(",")
 }
 
-fn write_job_file(board: &str, hero_hand: &str) -> Result<PathBuf, Box<dyn Error>> {
-    println!("DEBUG: Writing job file...");
+// Hand class -> weight for a range string ("AA:1.0,AKs:0.5,KQo"). A class
+// without an explicit weight counts as 1.0.
+pub fn range_weights(range: &str) -> HashMap<String, f64> {
+    range
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(|token| match token.split_once(':') {
+            Some((hand, w)) => (hand.to_string(), w.parse().unwrap_or(0.0)),
+            None => (token.to_string(), 1.0),
+        })
+        .collect()
+}
+
+fn write_job_file(board: &str, hero_hand: &str, profile: &Profile) -> Result<PathBuf, Box<dyn Error>> {
     let job_path = get_absolute_path(JOB_FILE_REL_PATH);
     if let Some(parent) = job_path.parent() {
         fs::create_dir_all(parent)?;
     }
     let mut f = fs::File::create(&job_path)?;
-    let content = build_job_content(board, hero_hand);
+    let content = build_job_content(board, hero_hand, profile);
     f.write_all(content.as_bytes())?;
-    println!("DEBUG: Job file written to {}", job_path.display());
     Ok(job_path)
 }
 
-pub fn run_sample_job(board: &str, hero_hand: &str) -> Result<(), Box<dyn Error>> {
+pub fn run_sample_job(
+    board: &str,
+    hero_hand: &str,
+    profile: &Profile,
+    settings: &SolverSettings,
+) -> Result<Convergence, Box<dyn Error>> {
     let output_path = get_absolute_path(OUTPUT_JSON_REL_PATH);
     if output_path.exists() {
         fs::remove_file(&output_path)?;
     }
     
-    let job_path = write_job_file(board, hero_hand)?;
-    let solver_path = get_absolute_path(CONSOLE_SOLVER_REL_PATH);
-    let resource_dir = get_absolute_path(RESOURCE_DIR_REL);
-    
-    // Run the external console solver binary on macOS.
-    println!("DEBUG: About to run command: {}", solver_path.display());
-    let status = Command::new(solver_path)
+    let solver_path = find_solver(settings)?.path;
+    let resource_dir = find_resource_dir(settings, Some(&solver_path)).path;
+    let job_path = write_job_file(board, hero_hand, profile)?;
+
+    // Run the external console solver binary.
+    let mut child = Command::new(solver_path)
         .arg("--input_file")
         .arg(job_path)
         .arg("-r")
         .arg(resource_dir)
         .arg("-m")
-        .arg("holdem")
-        .status()?;
+        .arg(profile.game.as_str())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    // Solver progress is diagnostics: keep stdout for our own output
+    // (e.g. the JSON document in `--format json`), but read it on the way
+    // for the convergence numbers. Reading until EOF also keeps the child
+    // from blocking on a full pipe.
+    let mut convergence = Convergence::default();
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines() {
+            let line = line?;
+            eprintln!("{}", line);
+            convergence.observe_line(&line);
+        }
+    }
+    let status = child.wait()?;
 
     if !status.success() {
         return Err(format!(
@@ -189,5 +278,128 @@ pub fn run_sample_job(board: &str, hero_hand: &str) -> Result<(), Box<dyn Error>
         return Err(format!("expected output JSON not found at {}", output_path.display()).into());
     }
 
-    Ok(())
+    Ok(convergence)
+}
+
+// How far the solve got, as the solver last reported it. Exploitability is
+// in % of the pot; None when the solver printed nothing we recognise (it is
+// not recomputed from the dump, which stops at `dump_rounds`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Convergence {
+    pub iterations: Option<u32>,
+    pub exploitability: Option<f64>,
+}
+
+impl Convergence {
+    // console_solver prints "Iter: 40" and, every print interval,
+    // "Total exploitability 0.512 precent" (sic).
+    fn observe_line(&mut self, line: &str) {
+        let line = line.trim();
+        if let Some(n) = line.strip_prefix("Iter:").and_then(|n| n.trim().parse().ok()) {
+            self.iterations = Some(n);
+        } else if let Some(rest) = line.strip_prefix("Total exploitability")
+            && let Some(x) = rest.split_whitespace().next().and_then(|x| x.parse().ok())
+        {
+            self.exploitability = Some(x);
+        }
+    }
+}
+
+// What was used to produce a solve, for reports and JSON output.
+#[derive(Debug, Clone)]
+pub struct SolveMeta {
+    pub backend: Backend,
+    // console_solver binary or solver library.
+    pub solver_path: PathBuf,
+    pub resource_dir: PathBuf,
+    pub mode: &'static str,
+    pub elapsed: Duration,
+    pub convergence: Convergence,
+}
+
+pub struct Solved {
+    pub tree: Value,
+    pub meta: SolveMeta,
+}
+
+// Solve a spot with whichever backend the config selects and return the
+// dumped strategy tree.
+pub fn solve(
+    board: &str,
+    hero_hand: &str,
+    profile: &Profile,
+    settings: &SolverSettings,
+) -> Result<Solved, Box<dyn Error>> {
+    let started = Instant::now();
+    match settings.backend {
+        Backend::Process => {
+            let solver_path = find_solver(settings)?.path;
+            let resource_dir = find_resource_dir(settings, Some(&solver_path)).path;
+            if !profile.locks.is_empty() {
+                return Err(format!(
+                    "profile '{}' locks nodes, but console_solver cannot lock them; use the library backend",
+                    profile.name
+                )
+                .into());
+            }
+            if settings.dump_evs {
+                eprintln!("Note: console_solver cannot dump EVs; showing frequencies only.");
+            }
+            let convergence = run_sample_job(board, hero_hand, profile, settings)?;
+            let tree = load_tree(&output_json_path().to_string_lossy())?;
+            Ok(Solved {
+                tree,
+                meta: SolveMeta {
+                    backend: Backend::Process,
+                    solver_path,
+                    resource_dir,
+                    mode: profile.game.as_str(),
+                    elapsed: started.elapsed(),
+                    convergence,
+                },
+            })
+        }
+        Backend::Library => {
+            let lib_path = find_library(settings)?;
+            let library = SolverLibrary::open(&lib_path)?;
+            let resource_dir = find_resource_dir(settings, None).path;
+            let commands = tree_commands(board, hero_hand, profile);
+            let mut convergence = Convergence::default();
+            let mut report = |iteration: u32, exploitability: f64| {
+                eprintln!("Iter {}: exploitability {:.3}% pot", iteration, exploitability);
+                convergence = Convergence { iterations: Some(iteration), exploitability: Some(exploitability) };
+            };
+            if !profile.locks.is_empty() && !library.supports_locks() {
+                return Err(format!(
+                    "profile '{}' locks nodes, but {} has no ts_lock_node",
+                    profile.name,
+                    lib_path.display()
+                )
+                .into());
+            }
+            if settings.dump_evs && !library.supports_evs() {
+                eprintln!("Note: {} has no ts_set_dump_evs; showing frequencies only.", lib_path.display());
+            }
+            let tree = library.solve(
+                &resource_dir,
+                profile.game.as_str(),
+                &commands,
+                &profile.locks,
+                dump_rounds_for(board),
+                settings.dump_evs,
+                &mut report,
+            )?;
+            Ok(Solved {
+                tree,
+                meta: SolveMeta {
+                    backend: Backend::Library,
+                    solver_path: lib_path,
+                    resource_dir,
+                    mode: profile.game.as_str(),
+                    elapsed: started.elapsed(),
+                    convergence,
+                },
+            })
+        }
+    }
 }
@@ -0,0 +1,318 @@
+use crate::batch::{FlopResult, NodeFrequencies};
+use crate::boards::{parse_flop, rank_count, suit_count, Flop, Game, RANKS};
+use crate::json_out::action::Action;
+
+// Flop textures and the batch aggregates grouped by them.
+//
+// Every flop falls in one group of each kind:
+//   texture     trips, paired, monotone, two-tone/rainbow x connected/dry
+//   high card   A, K, Q, ... 2
+//   pairing     unpaired, paired, trips
+//   suits       rainbow, two-tone, monotone
+//
+// "Connected" means the three ranks fit in a five-rank window (A also plays
+// low, below the deck's lowest rank: A-2-3-4-5, or A-6-7-8-9 in short deck),
+// so a straight is possible with two hole cards.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Grouping {
+    Texture,
+    HighCard,
+    Pairing,
+    Suits,
+}
+
+impl Grouping {
+    pub const ALL: [Grouping; 4] = [Grouping::Texture, Grouping::HighCard, Grouping::Pairing, Grouping::Suits];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Grouping::Texture => "texture",
+            Grouping::HighCard => "high_card",
+            Grouping::Pairing => "pairing",
+            Grouping::Suits => "suits",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Grouping::Texture => "Texture class",
+            Grouping::HighCard => "High card",
+            Grouping::Pairing => "Pairedness",
+            Grouping::Suits => "Suitedness",
+        }
+    }
+
+    // Every group of this kind, in display order.
+    pub fn groups(&self) -> Vec<String> {
+        match self {
+            Grouping::Texture => [
+                "trips",
+                "paired",
+                "monotone",
+                "two-tone connected",
+                "two-tone dry",
+                "rainbow connected",
+                "rainbow dry",
+            ]
+            .map(String::from)
+            .to_vec(),
+            Grouping::HighCard => RANKS.iter().rev().map(|&r| (r as char).to_string()).collect(),
+            Grouping::Pairing => ["unpaired", "paired", "trips"].map(String::from).to_vec(),
+            Grouping::Suits => ["rainbow", "two-tone", "monotone"].map(String::from).to_vec(),
+        }
+    }
+
+    pub fn group_of(&self, flop: &Flop, game: Game) -> String {
+        match self {
+            Grouping::Texture => texture_class(flop, game).to_string(),
+            Grouping::HighCard => {
+                let high = flop.iter().map(|c| c / 4).max().unwrap_or(0);
+                (RANKS[high as usize] as char).to_string()
+            }
+            Grouping::Pairing => match rank_count(flop) {
+                1 => "trips",
+                2 => "paired",
+                _ => "unpaired",
+            }
+            .to_string(),
+            Grouping::Suits => suits_name(flop).to_string(),
+        }
+    }
+}
+
+fn suits_name(flop: &Flop) -> &'static str {
+    match suit_count(flop) {
+        1 => "monotone",
+        2 => "two-tone",
+        _ => "rainbow",
+    }
+}
+
+pub fn is_connected(flop: &Flop, game: Game) -> bool {
+    let mut ranks: Vec<i32> = flop.iter().map(|c| (c / 4) as i32).collect();
+    ranks.sort_unstable();
+    let fits = |r: &[i32]| r[2] - r[0] <= 4;
+    if fits(&ranks) {
+        return true;
+    }
+    // Ace low: A-2-3, A-3-5, ... (A-6-7, A-7-9, ... in short deck)
+    if ranks[2] == 12 {
+        let mut low = vec![game.lowest_rank() as i32 - 1, ranks[0], ranks[1]];
+        low.sort_unstable();
+        return fits(&low);
+    }
+    false
+}
+
+pub fn texture_class(flop: &Flop, game: Game) -> &'static str {
+    match (rank_count(flop), suit_count(flop)) {
+        (1, _) => "trips",
+        (2, _) => "paired",
+        (_, 1) => "monotone",
+        (_, 2) if is_connected(flop, game) => "two-tone connected",
+        (_, 2) => "two-tone dry",
+        _ if is_connected(flop, game) => "rainbow connected",
+        _ => "rainbow dry",
+    }
+}
+
+// Average bet size at a node in % of `pot`, weighted by how often each size
+// is used. None when the node never bets.
+pub fn average_bet_pct(node: &NodeFrequencies, pot: f64) -> Option<f64> {
+    let (chips, freq) = node
+        .actions
+        .iter()
+        .zip(&node.frequencies)
+        .filter_map(|(a, f)| Action::parse(a).filter(|a| a.is_aggressive())?.chips().map(|amount| (amount * f, *f)))
+        .fold((0.0, 0.0), |(c, t), (ac, f)| (c + ac, t + f));
+    (freq > 0.0).then(|| chips / freq / pot * 100.0)
+}
+
+// One row of an aggregate table. Frequencies and sizes are averages over the
+// group's flops weighted by flop weight (sizes additionally by how often the
+// flop bets).
+pub struct GroupRow {
+    pub grouping: Grouping,
+    pub group: String,
+    pub flops: usize,
+    pub weight: f64,
+    pub oop_bet: Option<f64>,
+    pub oop_size_pct: Option<f64>,
+    pub ip_bet: Option<f64>,
+    pub ip_size_pct: Option<f64>,
+}
+
+#[derive(Default)]
+struct Sums {
+    flops: usize,
+    weight: f64,
+    // (weighted sum, total weight)
+    bet: (f64, f64),
+    size: (f64, f64),
+}
+
+impl Sums {
+    fn add(&mut self, node: Option<&NodeFrequencies>, weight: f64, pot: f64) {
+        let Some(node) = node else { return };
+        let bet = node.aggressive();
+        self.bet.0 += bet * weight;
+        self.bet.1 += weight;
+        if let Some(size) = average_bet_pct(node, pot) {
+            self.size.0 += size * bet * weight;
+            self.size.1 += bet * weight;
+        }
+    }
+
+    fn bet(&self) -> Option<f64> {
+        (self.bet.1 > 0.0).then(|| self.bet.0 / self.bet.1)
+    }
+
+    fn size(&self) -> Option<f64> {
+        (self.size.1 > 0.0).then(|| self.size.0 / self.size.1)
+    }
+}
+
+// Rows for one grouping, in display order; groups without flops are left out.
+pub fn aggregate(results: &[FlopResult], grouping: Grouping, pot: f64, game: Game) -> Vec<GroupRow> {
+    let groups = grouping.groups();
+    let mut oop: Vec<Sums> = groups.iter().map(|_| Sums::default()).collect();
+    let mut ip: Vec<Sums> = groups.iter().map(|_| Sums::default()).collect();
+
+    for r in results {
+        let Ok(flop) = parse_flop(&r.flop) else { continue };
+        let Some(i) = groups.iter().position(|g| *g == grouping.group_of(&flop, game)) else { continue };
+        oop[i].flops += 1;
+        oop[i].weight += r.weight;
+        oop[i].add(r.oop.as_ref(), r.weight, pot);
+        ip[i].add(r.ip_vs_check.as_ref(), r.weight, pot);
+    }
+
+    groups
+        .into_iter()
+        .zip(oop.iter().zip(&ip))
+        .filter(|(_, (o, _))| o.flops > 0)
+        .map(|(group, (o, i))| GroupRow {
+            grouping,
+            group,
+            flops: o.flops,
+            weight: o.weight,
+            oop_bet: o.bet(),
+            oop_size_pct: o.size(),
+            ip_bet: i.bet(),
+            ip_size_pct: i.size(),
+        })
+        .collect()
+}
+
+pub fn rows_csv(rows: &[GroupRow]) -> String {
+    let mut out = String::from("grouping,group,flops,weight,oop_bet_freq,oop_avg_bet_pct_pot,ip_bet_freq,ip_avg_bet_pct_pot\n");
+    let cell = |v: Option<f64>| v.map(|f| format!("{:.4}", f)).unwrap_or_default();
+    for r in rows {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\n",
+            r.grouping.as_str(),
+            r.group,
+            r.flops,
+            r.weight,
+            cell(r.oop_bet),
+            cell(r.oop_size_pct),
+            cell(r.ip_bet),
+            cell(r.ip_size_pct)
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::FlopResult;
+
+    fn class(flop: &str, game: Game) -> &'static str {
+        texture_class(&parse_flop(flop).unwrap(), game)
+    }
+
+    fn node(actions: &[(&str, f64)]) -> Option<NodeFrequencies> {
+        Some(NodeFrequencies {
+            actions: actions.iter().map(|(a, _)| a.to_string()).collect(),
+            frequencies: actions.iter().map(|(_, f)| *f).collect(),
+        })
+    }
+
+    fn result(flop: &str, weight: f64, oop: Option<NodeFrequencies>, ip: Option<NodeFrequencies>) -> FlopResult {
+        FlopResult {
+            flop: flop.to_string(),
+            weight,
+            oop,
+            ip_vs_check: ip,
+            elapsed_seconds: 1.0,
+            exploitability: None,
+            tree_file: None,
+        }
+    }
+
+    fn close(x: Option<f64>, y: f64) -> bool {
+        x.is_some_and(|x| (x - y).abs() < 1e-9)
+    }
+
+    #[test]
+    fn classifies_holdem_flops() {
+        assert_eq!(class("7s7d7c", Game::Holdem), "trips");
+        assert_eq!(class("KhKd2c", Game::Holdem), "paired");
+        assert_eq!(class("Ah9h2h", Game::Holdem), "monotone");
+        assert_eq!(class("9h8h5d", Game::Holdem), "two-tone connected");
+        assert_eq!(class("KhTh2d", Game::Holdem), "two-tone dry");
+        assert_eq!(class("Ah2c3d", Game::Holdem), "rainbow connected");
+        assert_eq!(class("Ah5c4d", Game::Holdem), "rainbow connected");
+        assert_eq!(class("Ah6c9d", Game::Holdem), "rainbow dry");
+        assert_eq!(class("AhKcJd", Game::Holdem), "rainbow connected");
+    }
+
+    #[test]
+    fn short_deck_ace_plays_below_the_six() {
+        assert_eq!(class("Ah6c9d", Game::ShortDeck), "rainbow connected");
+        assert_eq!(class("Ah7h8d", Game::ShortDeck), "two-tone connected");
+        assert_eq!(class("Ah7c8d", Game::Holdem), "rainbow dry");
+        assert_eq!(class("AhTc6d", Game::ShortDeck), "rainbow dry");
+        assert_eq!(class("9hTc6d", Game::ShortDeck), "rainbow connected");
+    }
+
+    #[test]
+    fn aggregates_by_flop_weight() {
+        let results = vec![
+            // rainbow dry: OOP bets half, at half pot; IP never bets.
+            result("AhKd7c", 24.0, node(&[("CHECK", 0.5), ("BET 25.000000", 0.5)]), node(&[("CHECK", 1.0)])),
+            // two-tone dry: OOP always bets 2x pot; no IP node.
+            result("Qs9s2d", 12.0, node(&[("CHECK", 0.0), ("BET 100.000000", 1.0)]), None),
+            // rainbow connected: two OOP sizes.
+            result(
+                "8h7d6c",
+                24.0,
+                node(&[("CHECK", 0.8), ("BET 25.000000", 0.1), ("BET 50.000000", 0.1)]),
+                node(&[("CHECK", 0.4), ("BET 25.000000", 0.6)]),
+            ),
+        ];
+
+        let rows = aggregate(&results, Grouping::Texture, 50.0, Game::Holdem);
+        let groups: Vec<(&str, usize)> = rows.iter().map(|r| (r.group.as_str(), r.flops)).collect();
+        assert_eq!(groups, vec![("two-tone dry", 1), ("rainbow connected", 1), ("rainbow dry", 1)]);
+        assert!(rows[0].ip_bet.is_none() && rows[0].ip_size_pct.is_none());
+        assert!(close(rows[1].oop_bet, 0.2) && close(rows[1].oop_size_pct, 75.0));
+        assert!(close(rows[2].ip_bet, 0.0) && rows[2].ip_size_pct.is_none());
+
+        let rows = aggregate(&results, Grouping::Pairing, 50.0, Game::Holdem);
+        assert_eq!(rows.len(), 1);
+        let all = &rows[0];
+        assert_eq!((all.group.as_str(), all.flops, all.weight), ("unpaired", 3, 60.0));
+        assert!(close(all.oop_bet, (0.5 * 24.0 + 12.0 + 0.2 * 24.0) / 60.0), "{:?}", all.oop_bet);
+        // Sizes weighted by flop weight and by how often each flop bets.
+        let size = (50.0 * 0.5 * 24.0 + 200.0 * 12.0 + 75.0 * 0.2 * 24.0) / (0.5 * 24.0 + 12.0 + 0.2 * 24.0);
+        assert!(close(all.oop_size_pct, size), "{:?}", all.oop_size_pct);
+        assert!(close(all.ip_bet, 0.6 * 24.0 / 48.0));
+        assert!(close(all.ip_size_pct, 50.0));
+
+        let high: Vec<String> = aggregate(&results, Grouping::HighCard, 50.0, Game::Holdem).into_iter().map(|r| r.group).collect();
+        assert_eq!(high, vec!["A", "Q", "8"]);
+    }
+}
@@ -0,0 +1,497 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use crate::batch;
+use crate::boards::Game;
+use crate::config::Profile;
+use crate::history;
+use crate::json_out::parse::{combo_evs, node_strategies};
+use crate::solver::{activate_hand_in_range, get_generic_hand, range_weights};
+
+pub mod records;
+
+// Trainer: single decisions dealt at random from solved trees, answered by
+// the user and scored against the solver's mixed strategy.
+//
+// A drill is one board (the flop, or a turn/river reached by check-check
+// when the tree was dumped that deep), one action node of that street at
+// most MAX_LINE_DEPTH actions in, and one combo of the acting player's
+// range. Combos are dealt by preflop range weight, not by how often they
+// reach the node, so a hand that would rarely get there can still come up.
+//
+// Scoring, 0..1 per drill:
+//   EV loss      when the dump has per-action EVs (see `combo_evs`):
+//                1 - loss / (EV_LOSS_FLOOR * pot), floored at 0
+//   frequency    otherwise: how often the solver takes the chosen action
+//                relative to its most frequent one, so any action of a
+//                50/50 mix scores 1 and a 10% action next to a 90% one 0.11
+
+const MAX_LINE_DEPTH: usize = 3;
+// Losing this share of the starting pot (or more) scores 0.
+const EV_LOSS_FLOOR: f64 = 0.10;
+// Scores from here up count as correct.
+pub const CORRECT_SCORE: f64 = 0.9;
+
+// xorshift64*, seeded from the clock. Deals only need to look random.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn from_time() -> Rng {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or(0);
+        Rng::new(nanos)
+    }
+
+    pub fn new(seed: u64) -> Rng {
+        // xorshift never leaves 0.
+        Rng(seed ^ 0x9e37_79b9_7f4a_7c15 | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next() % n.max(1) as u64) as usize
+    }
+
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Index picked with probability proportional to its weight; None when
+    // nothing has weight.
+    pub fn weighted(&mut self, weights: &[f64]) -> Option<usize> {
+        let total: f64 = weights.iter().filter(|w| **w > 0.0).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut x = self.unit() * total;
+        for (i, w) in weights.iter().enumerate().filter(|(_, w)| **w > 0.0) {
+            if x < *w {
+                return Some(i);
+            }
+            x -= w;
+        }
+        weights.iter().rposition(|w| *w > 0.0)
+    }
+}
+
+// One solved tree on disk.
+struct TreeFile {
+    // Run id or flop, for the drill header.
+    label: String,
+    flop: Vec<String>,
+    path: PathBuf,
+}
+
+// The trees a session deals from: one history run, or every flop of a batch.
+pub struct Pool {
+    pub name: String,
+    pub profile: Profile,
+    // Hand class -> weight, as solved.
+    range_oop: HashMap<String, f64>,
+    range_ip: HashMap<String, f64>,
+    trees: Vec<TreeFile>,
+    // The last tree read, so drills from a single run don't re-read it.
+    loaded: Option<(usize, Value)>,
+}
+
+impl Pool {
+    pub fn from_history(record: &Value) -> Result<Pool, Box<dyn Error>> {
+        let run_id = record["run_id"].as_str().unwrap_or("?").to_string();
+        let path = history::tree_path(record)
+            .filter(|p| p.is_file())
+            .ok_or_else(|| format!("run {} has no saved tree to train on", run_id))?;
+        let name = record["job"]["profile"].as_str().unwrap_or("history");
+        let profile = Profile::from_json(name, &record["job"])?;
+        // The run was solved with hero's class switched on.
+        let generic = get_generic_hand(record["inputs"]["hero"].as_str().unwrap_or(""));
+        let flop = history::board_string(record).split(',').take(3).map(String::from).collect();
+        Ok(Pool {
+            name: format!("run {}", run_id),
+            range_oop: range_weights(&activate_hand_in_range(&profile.range_oop, &generic)),
+            range_ip: range_weights(&activate_hand_in_range(&profile.range_ip, &generic)),
+            profile,
+            trees: vec![TreeFile { label: run_id, flop, path }],
+            loaded: None,
+        })
+    }
+
+    pub fn from_batch(dir: &Path) -> Result<Pool, Box<dyn Error>> {
+        let profile = batch::load_profile(dir)?;
+        let trees: Vec<TreeFile> = batch::load_results(dir, &profile)?
+            .iter()
+            .filter_map(|r| {
+                let path = batch::tree_path(dir, r).filter(|p| p.is_file())?;
+                Some(TreeFile { label: r.flop.clone(), flop: r.flop.split(',').map(String::from).collect(), path })
+            })
+            .collect();
+        if trees.is_empty() {
+            return Err(format!("{} has no solved trees yet", dir.display()).into());
+        }
+        Ok(Pool {
+            name: dir.display().to_string(),
+            range_oop: range_weights(&profile.range_oop),
+            range_ip: range_weights(&profile.range_ip),
+            profile,
+            trees,
+            loaded: None,
+        })
+    }
+
+    pub fn tree_count(&self) -> usize {
+        self.trees.len()
+    }
+
+    fn load(&mut self, i: usize) -> Result<(), Box<dyn Error>> {
+        if self.loaded.as_ref().is_none_or(|(j, _)| *j != i) {
+            let path = &self.trees[i].path;
+            let text = fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+            self.loaded = Some((i, serde_json::from_str(&text)?));
+        }
+        Ok(())
+    }
+
+    pub fn deal(&mut self, rng: &mut Rng) -> Result<Drill, Box<dyn Error>> {
+        // A tree without usable decisions (e.g. a failed dump) just gets
+        // skipped; give up only if none of a few tries works.
+        for _ in 0..self.trees.len() * 4 {
+            let i = rng.below(self.trees.len());
+            self.load(i)?;
+            let Some((_, tree)) = &self.loaded else { continue };
+            let ranges = (&self.range_oop, &self.range_ip);
+            if let Some(drill) = deal_from_tree(tree, &self.trees[i], ranges, &self.profile, rng) {
+                return Ok(drill);
+            }
+        }
+        Err(format!("no decision with at least two actions found in {}", self.name).into())
+    }
+}
+
+// One decision to answer.
+pub struct Drill {
+    pub source: String,
+    pub street: &'static str,
+    pub board: Vec<String>,
+    // Actions taken earlier in this street, in order.
+    pub line: Vec<String>,
+    // Node path from the tree root, usable with `export-csv`.
+    pub path: String,
+    pub oop: bool,
+    pub combo: String,
+    pub actions: Vec<String>,
+    pub probs: Vec<f64>,
+    // Per action, in chips, when the dump has them.
+    pub evs: Option<Vec<f64>>,
+    pub pot: f64,
+    pub game: Game,
+}
+
+fn deal_from_tree(
+    tree: &Value,
+    file: &TreeFile,
+    (range_oop, range_ip): (&HashMap<String, f64>, &HashMap<String, f64>),
+    profile: &Profile,
+    rng: &mut Rng,
+) -> Option<Drill> {
+    // A random runout along check-check, then one of its streets.
+    let mut streets: Vec<(&Value, Vec<String>)> = vec![(tree, Vec::new())];
+    while streets.len() < 3 {
+        let (node, cards) = streets.last()?;
+        let Some(deals) = node
+            .get("childrens")
+            .and_then(|c| c.get("CHECK"))
+            .and_then(|n| n.get("childrens"))
+            .and_then(|c| c.get("CHECK"))
+            .and_then(|n| n.get("dealcards"))
+            .and_then(|d| d.as_object())
+            .filter(|d| !d.is_empty())
+        else {
+            break;
+        };
+        let (card, next) = deals.iter().nth(rng.below(deals.len()))?;
+        let mut cards = cards.clone();
+        cards.push(card.clone());
+        streets.push((next, cards));
+    }
+    let (start, runout) = streets.swap_remove(rng.below(streets.len()));
+
+    let mut nodes = Vec::new();
+    collect_decisions(start, &mut Vec::new(), &mut nodes);
+    if nodes.is_empty() {
+        return None;
+    }
+    let (node, line) = nodes.swap_remove(rng.below(nodes.len()));
+
+    let oop = node.get("player").and_then(|p| p.as_u64()) == Some(1);
+    let weights = if oop { range_oop } else { range_ip };
+    let strategy = node_strategies(node)?;
+    let combo_weights: Vec<f64> = strategy
+        .combos
+        .iter()
+        .map(|(combo, _)| weights.get(&get_generic_hand(combo)).copied().unwrap_or(0.0))
+        .collect();
+    // A node whose combos all have weight 0 comes from a range the profile no
+    // longer describes; deal uniformly rather than not at all.
+    let pick = rng.weighted(&combo_weights).unwrap_or_else(|| rng.below(strategy.combos.len()));
+    let (combo, probs) = strategy.combos.get(pick)?.clone();
+    if probs.len() != strategy.actions.len() {
+        return None;
+    }
+
+    let prefix: String = runout.iter().map(|card| format!("CHECK/CHECK/{}/", card)).collect();
+    let mut board = file.flop.clone();
+    board.extend(runout.iter().cloned());
+    Some(Drill {
+        source: file.label.clone(),
+        street: ["flop", "turn", "river"][runout.len().min(2)],
+        board,
+        path: format!("{}{}", prefix, line.join("/")).trim_end_matches('/').to_string(),
+        line,
+        oop,
+        evs: combo_evs(node, &combo).filter(|e| e.len() == probs.len()),
+        combo,
+        actions: strategy.actions,
+        probs,
+        pot: profile.pot,
+        game: profile.game,
+    })
+}
+
+// Action nodes with a real choice, down to MAX_LINE_DEPTH actions into the
+// street (chance nodes end the street).
+fn collect_decisions<'a>(node: &'a Value, line: &mut Vec<String>, out: &mut Vec<(&'a Value, Vec<String>)>) {
+    if node_strategies(node).is_some_and(|s| s.actions.len() > 1 && !s.combos.is_empty()) {
+        out.push((node, line.clone()));
+    }
+    if line.len() >= MAX_LINE_DEPTH {
+        return;
+    }
+    if let Some(children) = node.get("childrens").and_then(|c| c.as_object()) {
+        for (action, child) in children {
+            line.push(action.clone());
+            collect_decisions(child, line, out);
+            line.pop();
+        }
+    }
+}
+
+pub struct Grade {
+    pub score: f64,
+    // The solver's preferred action: highest EV, or highest frequency.
+    pub best: usize,
+    // Chips given up against the best action, when EVs are known.
+    pub ev_loss: Option<f64>,
+}
+
+impl Drill {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "source": self.source,
+            "street": self.street,
+            "board": self.board,
+            "line": self.line,
+            "path": self.path,
+            "oop": self.oop,
+            "combo": self.combo,
+            "actions": self.actions,
+            "probs": self.probs,
+            "evs": self.evs,
+            "pot": self.pot,
+            "game": self.game.as_str(),
+        })
+    }
+
+    // Inverse of to_json, for replaying recorded drills.
+    pub fn from_json(v: &Value) -> Option<Drill> {
+        let strings = |key: &str| -> Option<Vec<String>> {
+            Some(v[key].as_array()?.iter().filter_map(|s| s.as_str().map(String::from)).collect())
+        };
+        let numbers = |key: &str| -> Option<Vec<f64>> { Some(v[key].as_array()?.iter().filter_map(Value::as_f64).collect()) };
+        let drill = Drill {
+            source: v["source"].as_str()?.to_string(),
+            street: match v["street"].as_str()? {
+                "turn" => "turn",
+                "river" => "river",
+                _ => "flop",
+            },
+            board: strings("board")?,
+            line: strings("line").unwrap_or_default(),
+            path: v["path"].as_str().unwrap_or("").to_string(),
+            oop: v["oop"].as_bool().unwrap_or(false),
+            combo: v["combo"].as_str().filter(|c| c.len() == 4)?.to_string(),
+            actions: strings("actions")?,
+            probs: numbers("probs")?,
+            evs: numbers("evs"),
+            pot: v["pot"].as_f64().unwrap_or(0.0),
+            game: v["game"].as_str().and_then(Game::parse).unwrap_or_default(),
+        };
+        let sizes_match = drill.actions.len() == drill.probs.len() && drill.evs.as_ref().is_none_or(|e| e.len() == drill.probs.len());
+        (drill.actions.len() > 1 && sizes_match).then_some(drill)
+    }
+
+    pub fn grade(&self, choice: usize) -> Grade {
+        if let Some(evs) = &self.evs {
+            let best = argmax(evs);
+            let loss = (evs[best] - evs[choice]).max(0.0);
+            let score = if self.pot > 0.0 { (1.0 - loss / (EV_LOSS_FLOOR * self.pot)).max(0.0) } else { 1.0 };
+            return Grade { score, best, ev_loss: Some(loss) };
+        }
+        let best = argmax(&self.probs);
+        let score = if self.probs[best] > 0.0 { self.probs[choice] / self.probs[best] } else { 0.0 };
+        Grade { score, best, ev_loss: None }
+    }
+}
+
+fn argmax(values: &[f64]) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .fold(0, |best, (i, v)| if *v > values[best] { i } else { best })
+}
+
+// Running totals for one sitting.
+#[derive(Default)]
+pub struct Session {
+    pub drills: usize,
+    pub correct: usize,
+    pub total_score: f64,
+    pub total_ev_loss: f64,
+    pub ev_drills: usize,
+}
+
+impl Session {
+    pub fn add(&mut self, grade: &Grade) {
+        self.drills += 1;
+        self.total_score += grade.score;
+        if grade.score >= CORRECT_SCORE {
+            self.correct += 1;
+        }
+        if let Some(loss) = grade.ev_loss {
+            self.total_ev_loss += loss;
+            self.ev_drills += 1;
+        }
+    }
+
+    pub fn accuracy(&self) -> f64 {
+        if self.drills == 0 { 0.0 } else { self.total_score / self.drills as f64 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drill(probs: &[f64], evs: Option<&[f64]>) -> Drill {
+        Drill {
+            source: "test".to_string(),
+            street: "flop",
+            board: vec!["Qs".to_string(), "Jh".to_string(), "2c".to_string()],
+            line: Vec::new(),
+            path: String::new(),
+            oop: true,
+            combo: "AhKd".to_string(),
+            actions: (0..probs.len()).map(|i| format!("BET {}", i * 10)).collect(),
+            probs: probs.to_vec(),
+            evs: evs.map(<[f64]>::to_vec),
+            pot: 50.0,
+            game: Game::Holdem,
+        }
+    }
+
+    fn decision(player: u64, actions: &[&str], children: Value) -> Value {
+        let probs = vec![1.0 / actions.len() as f64; actions.len()];
+        json!({
+            "node_type": "action_node",
+            "player": player,
+            "actions": actions,
+            "strategy": { "actions": actions, "strategy": { "AhKd": probs } },
+            "childrens": children,
+        })
+    }
+
+    #[test]
+    fn best_action_scores_one() {
+        let grade = drill(&[0.2, 0.8], None).grade(1);
+        assert_eq!(grade.best, 1);
+        assert_eq!(grade.score, 1.0);
+        assert!(grade.ev_loss.is_none());
+    }
+
+    #[test]
+    fn near_even_mix_counts_as_correct() {
+        let grade = drill(&[0.48, 0.52], None).grade(0);
+        assert_eq!(grade.best, 1);
+        assert!(grade.score >= CORRECT_SCORE && grade.score < 1.0, "{}", grade.score);
+
+        let grade = drill(&[0.1, 0.9], None).grade(0);
+        assert!(grade.score < CORRECT_SCORE, "{}", grade.score);
+    }
+
+    #[test]
+    fn never_taken_action_scores_zero() {
+        let grade = drill(&[0.0, 0.3, 0.7], None).grade(0);
+        assert_eq!(grade.best, 2);
+        assert_eq!(grade.score, 0.0);
+    }
+
+    #[test]
+    fn evs_take_over_from_frequencies() {
+        // The floor is 10% of the 50 pot: 5 chips lost scores 0.
+        let d = drill(&[0.7, 0.2, 0.1], Some(&[10.0, 8.0, 12.0]));
+        let grade = d.grade(2);
+        assert_eq!((grade.best, grade.score, grade.ev_loss), (2, 1.0, Some(0.0)));
+
+        let grade = d.grade(0);
+        assert_eq!(grade.ev_loss, Some(2.0));
+        assert!((grade.score - 0.6).abs() < 1e-9, "{}", grade.score);
+
+        let grade = drill(&[0.5, 0.5], Some(&[3.0, -4.0])).grade(1);
+        assert_eq!(grade.ev_loss, Some(7.0));
+        assert_eq!(grade.score, 0.0);
+    }
+
+    #[test]
+    fn decisions_stop_at_the_street_and_the_depth_limit() {
+        let deal = json!({ "node_type": "chance_node", "dealcards": { "7d": decision(1, &["CHECK", "BET 25.000000"], json!({})) } });
+        let terminal = json!({ "node_type": "action_node", "player": 1, "actions": ["CALL"] });
+        let deep = decision(1, &["FOLD", "CALL"], json!({}));
+        let tree = decision(
+            1,
+            &["CHECK", "BET 25.000000"],
+            json!({
+                "CHECK": decision(0, &["CHECK", "BET 25.000000"], json!({ "CHECK": deal })),
+                "BET 25.000000": decision(0, &["FOLD", "CALL", "RAISE 75.000000"], json!({
+                    "CALL": terminal,
+                    "RAISE 75.000000": decision(1, &["FOLD", "CALL", "RAISE 125.000000"], json!({
+                        "RAISE 125.000000": decision(0, &["FOLD", "CALL", "RAISE 200.000000"], json!({
+                            "RAISE 200.000000": deep,
+                        })),
+                    })),
+                })),
+            }),
+        );
+
+        let mut out = Vec::new();
+        collect_decisions(&tree, &mut Vec::new(), &mut out);
+        let lines: Vec<String> = out.iter().map(|(_, line)| line.join("/")).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "",
+                "BET 25.000000",
+                "BET 25.000000/RAISE 75.000000",
+                "BET 25.000000/RAISE 75.000000/RAISE 125.000000",
+                "CHECK",
+            ]
+        );
+    }
+}
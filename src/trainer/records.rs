@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use crate::discovery::get_exe_dir;
+use crate::evaluator::hand_category;
+use crate::history::format_timestamp;
+use crate::solver::get_generic_hand;
+use crate::trainer::{Drill, Grade, Rng};
+
+// Trainer records: every answered drill is appended as one JSON line to
+// resources/outputs/trainer/<user>.jsonl (next to the executable):
+//
+//   "timestamp":  1792340000
+//   "pool":       "run 1792339000-3fa2" | "batch_monotone"
+//   "review":     true when dealt by `train review`
+//   "drill":      the whole decision (Drill::to_json), so it can be replayed
+//                 without its tree
+//   "hand_class": "AKo"
+//   "category":   "pair" (made hand with the board, see `hand_category`)
+//   "choice", "action", "score", "ev_loss"
+//
+// Review works on buckets of one spot (tree + node path) and one hand
+// category. A bucket's error rate is 1 - score averaged over its last
+// REVIEW_WINDOW answers, so a spot stops coming back once it has been
+// answered well a few times in a row.
+
+const TRAINER_REL_DIR: &str = "resources/outputs/trainer";
+const REVIEW_WINDOW: usize = 5;
+// Buckets answered at least this well recently are left alone.
+const REVIEW_MIN_ERROR: f64 = 0.1;
+
+// --user, else the login name, else "default". Only [A-Za-z0-9_-] is kept
+// since the name becomes a file name.
+pub fn user_name(cli: Option<&str>) -> String {
+    let raw = cli
+        .map(String::from)
+        .or_else(|| env::var("USER").ok())
+        .or_else(|| env::var("USERNAME").ok())
+        .unwrap_or_default();
+    let clean: String = raw.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-').collect();
+    if clean.is_empty() { "default".to_string() } else { clean }
+}
+
+pub fn records_path(user: &str) -> PathBuf {
+    get_exe_dir().join(TRAINER_REL_DIR).join(format!("{}.jsonl", user))
+}
+
+pub fn append(user: &str, pool: &str, drill: &Drill, choice: usize, grade: &Grade, review: bool) -> Result<Value, Box<dyn Error>> {
+    let path = records_path(user);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let record = json!({
+        "timestamp": SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        "pool": pool,
+        "review": review,
+        "drill": drill.to_json(),
+        "hand_class": get_generic_hand(&drill.combo),
+        "category": hand_category(&drill.combo, &drill.board.join(","), drill.game),
+        "choice": choice,
+        "action": drill.actions[choice],
+        "score": grade.score,
+        "ev_loss": grade.ev_loss,
+    });
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", record)?;
+    Ok(record)
+}
+
+// All readable records, oldest first, and how many lines were skipped.
+pub fn load(user: &str) -> Result<(Vec<Value>, usize), Box<dyn Error>> {
+    let path = records_path(user);
+    if !path.exists() {
+        return Ok((Vec::new(), 0));
+    }
+    let mut records = Vec::new();
+    let mut skipped = 0;
+    for line in fs::read_to_string(&path)?.lines().filter(|l| !l.trim().is_empty()) {
+        match serde_json::from_str::<Value>(line) {
+            Ok(v) if v["score"].is_f64() && Drill::from_json(&v["drill"]).is_some() => records.push(v),
+            _ => skipped += 1,
+        }
+    }
+    Ok((records, skipped))
+}
+
+pub fn error(record: &Value) -> f64 {
+    1.0 - record["score"].as_f64().unwrap_or(0.0).clamp(0.0, 1.0)
+}
+
+// "As,Kh,Qd CHECK/BET 16.500000": the tree (flop or run id) and the node.
+pub fn spot_key(record: &Value) -> String {
+    let drill = &record["drill"];
+    let path = drill["path"].as_str().filter(|p| !p.is_empty()).unwrap_or("(root)");
+    format!("{} {}", drill["source"].as_str().unwrap_or("?"), path)
+}
+
+pub fn category(record: &Value) -> String {
+    record["category"].as_str().unwrap_or("unknown").to_string()
+}
+
+// One spot and hand category, with the records that fall in it.
+pub struct Bucket {
+    pub spot: String,
+    pub category: String,
+    pub records: Vec<usize>,
+    pub recent_error: f64,
+}
+
+pub fn buckets(records: &[Value]) -> Vec<Bucket> {
+    let mut by_key: HashMap<(String, String), Vec<usize>> = HashMap::new();
+    for (i, r) in records.iter().enumerate() {
+        by_key.entry((spot_key(r), category(r))).or_default().push(i);
+    }
+    let mut out: Vec<Bucket> = by_key
+        .into_iter()
+        .map(|((spot, category), records_in)| {
+            let recent = &records_in[records_in.len().saturating_sub(REVIEW_WINDOW)..];
+            let recent_error = recent.iter().map(|&i| error(&records[i])).sum::<f64>() / recent.len() as f64;
+            Bucket { spot, category, records: records_in, recent_error }
+        })
+        .collect();
+    out.sort_by(|a, b| b.recent_error.total_cmp(&a.recent_error).then_with(|| a.spot.cmp(&b.spot)));
+    out
+}
+
+// A recorded drill to replay: a bucket picked by its recent error rate, then
+// one of its drills picked by how badly it went.
+pub fn pick_review(records: &[Value], rng: &mut Rng) -> Option<Drill> {
+    let due: Vec<Bucket> = buckets(records).into_iter().filter(|b| b.recent_error >= REVIEW_MIN_ERROR).collect();
+    let bucket = &due[rng.weighted(&due.iter().map(|b| b.recent_error).collect::<Vec<_>>())?];
+    let errors: Vec<f64> = bucket.records.iter().map(|&i| error(&records[i])).collect();
+    let pick = rng.weighted(&errors).unwrap_or(errors.len() - 1);
+    Drill::from_json(&records[bucket.records[pick]]["drill"])
+}
+
+pub fn due_count(records: &[Value]) -> usize {
+    buckets(records).iter().filter(|b| b.recent_error >= REVIEW_MIN_ERROR).count()
+}
+
+// (group, drills, average score), groups in first-seen order.
+pub fn accuracy_by(records: &[Value], key: impl Fn(&Value) -> String) -> Vec<(String, usize, f64)> {
+    let mut groups: Vec<(String, usize, f64)> = Vec::new();
+    for r in records {
+        let k = key(r);
+        let score = 1.0 - error(r);
+        match groups.iter_mut().find(|(g, _, _)| *g == k) {
+            Some(group) => {
+                group.1 += 1;
+                group.2 += score;
+            }
+            None => groups.push((k, 1, score)),
+        }
+    }
+    for group in &mut groups {
+        group.2 /= group.1 as f64;
+    }
+    groups
+}
+
+// "2026-10-18"
+pub fn day(record: &Value) -> String {
+    format_timestamp(record["timestamp"].as_u64().unwrap_or(0))[..10].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(source: &str, category: &str, score: f64) -> Value {
+        json!({
+            "timestamp": 1792340000,
+            "pool": "test",
+            "drill": {
+                "source": source,
+                "street": "flop",
+                "board": ["Qs", "Jh", "2c"],
+                "path": "CHECK",
+                "combo": "AhKd",
+                "actions": ["CHECK", "BET 25.000000"],
+                "probs": [0.5, 0.5],
+                "pot": 50,
+            },
+            "category": category,
+            "score": score,
+        })
+    }
+
+    #[test]
+    fn recent_error_only_counts_the_last_answers() {
+        let mut records: Vec<Value> = (0..3).map(|_| record("QsJh2c", "ace high", 0.0)).collect();
+        records.extend((0..REVIEW_WINDOW - 1).map(|_| record("QsJh2c", "ace high", 1.0)));
+        let b = buckets(&records);
+        assert_eq!(b.len(), 1);
+        assert_eq!(b[0].records.len(), 3 + REVIEW_WINDOW - 1);
+        // One miss left in the window.
+        assert!((b[0].recent_error - 1.0 / REVIEW_WINDOW as f64).abs() < 1e-9);
+
+        records.push(record("QsJh2c", "ace high", 1.0));
+        assert_eq!(buckets(&records)[0].recent_error, 0.0);
+    }
+
+    #[test]
+    fn buckets_split_by_spot_and_category_worst_first() {
+        let records = vec![
+            record("QsJh2c", "ace high", 0.8),
+            record("QsJh2c", "pair", 0.2),
+            record("7d7c2h", "ace high", 0.5),
+        ];
+        let b: Vec<(String, String)> = buckets(&records).into_iter().map(|b| (b.spot, b.category)).collect();
+        assert_eq!(
+            b,
+            vec![
+                ("QsJh2c CHECK".to_string(), "pair".to_string()),
+                ("7d7c2h CHECK".to_string(), "ace high".to_string()),
+                ("QsJh2c CHECK".to_string(), "ace high".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn well_answered_buckets_are_never_picked() {
+        let records = vec![
+            record("QsJh2c", "ace high", 1.0 - REVIEW_MIN_ERROR / 2.0),
+            record("7d7c2h", "pair", 0.4),
+            record("QsJh2c", "ace high", 1.0),
+        ];
+        let mut rng = Rng::new(7);
+        for _ in 0..200 {
+            assert_eq!(pick_review(&records, &mut rng).unwrap().source, "7d7c2h");
+        }
+
+        let good = vec![record("QsJh2c", "ace high", 1.0), record("7d7c2h", "pair", 0.95)];
+        assert!(pick_review(&good, &mut rng).is_none());
+        assert!(pick_review(&[], &mut rng).is_none());
+    }
+
+    #[test]
+    fn due_count_clears_after_good_answers() {
+        let mut records = vec![record("QsJh2c", "ace high", 0.0), record("7d7c2h", "pair", 0.3)];
+        assert_eq!(due_count(&records), 2);
+        for _ in 0..REVIEW_WINDOW - 1 {
+            records.push(record("QsJh2c", "ace high", 1.0));
+        }
+        assert_eq!(due_count(&records), 2);
+        records.push(record("QsJh2c", "ace high", 1.0));
+        assert_eq!(due_count(&records), 1);
+        records.extend((0..REVIEW_WINDOW).map(|_| record("7d7c2h", "pair", 0.95)));
+        assert_eq!(due_count(&records), 0);
+    }
+}
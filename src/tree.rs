@@ -0,0 +1,423 @@
+use std::fs;
+
+use crate::config::{Profile, StreetBetSizes};
+use crate::evaluator::live_combos;
+use crate::solver::range_weights;
+
+// The betting tree a profile makes the solver build, rebuilt on our side so
+// its size can be judged before a long solve.
+//
+// Each street starts with OOP to act. Without a bet to face a player may
+// check or bet (the `bet` sizes; OOP leading into IP, the previous street's
+// last aggressor, uses the `donk` sizes instead), facing one fold, call or
+// raise (`raise` sizes, while fewer than `raise_limit` raises were made).
+// Sizes are % of the pot; a raise is the call plus that share of the pot
+// after calling. A bet or raise that would leave less than
+// 1 - allin_threshold of the player's stack behind is an all-in, and sizes
+// that end up the same are one action. Check-check or a call ends the street:
+// a chance node deals the next card (a showdown after the river), and an
+// all-in call runs the board out without further actions.
+//
+// Chance nodes keep one representative child; counts multiply by the cards
+// they deal, ignoring suit isomorphism, so they are an upper bound.
+
+pub const STREETS: [&str; 3] = ["flop", "turn", "river"];
+// Regrets, cumulative strategy, current strategy and EVs, 4-byte floats, per
+// action and combo of an action node.
+const BYTES_PER_ENTRY: f64 = 16.0;
+// Time to update one entry once, per thread. A rough figure: estimates are
+// good to an order of magnitude.
+const SECONDS_PER_ENTRY: f64 = 8e-9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Player {
+    Oop,
+    Ip,
+}
+
+impl Player {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Player::Oop => "OOP",
+            Player::Ip => "IP",
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Player::Oop => 0,
+            Player::Ip => 1,
+        }
+    }
+
+    fn other(&self) -> Player {
+        match self {
+            Player::Oop => Player::Ip,
+            Player::Ip => Player::Oop,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ActionKind {
+    Check,
+    Bet,
+    Call,
+    Raise,
+    Fold,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeAction {
+    pub kind: ActionKind,
+    // Chips this action puts in.
+    pub chips: f64,
+    pub all_in: bool,
+    // The configured sizes (% of pot) that became this action: several when
+    // they collapse into one all-in. Empty for check, call, fold and the
+    // `allin` option.
+    pub sizes: Vec<f64>,
+}
+
+impl TreeAction {
+    // The solver's label: "CHECK", "BET 25.000000", ...
+    pub fn label(&self) -> String {
+        match self.kind {
+            ActionKind::Check => "CHECK".to_string(),
+            ActionKind::Call => "CALL".to_string(),
+            ActionKind::Fold => "FOLD".to_string(),
+            ActionKind::Bet => format!("BET {:.6}", self.chips),
+            ActionKind::Raise => format!("RAISE {:.6}", self.chips),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeKind {
+    Action(Player),
+    // Deals the next street's card, one of `cards`.
+    Chance { cards: u32 },
+    Showdown,
+    // The player who folded.
+    Fold(Player),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeNode {
+    pub kind: NodeKind,
+    // Index into STREETS.
+    pub street: usize,
+    pub pot: f64,
+    // Chips behind, [OOP, IP].
+    pub stacks: [f64; 2],
+    // What the player to act must put in to call.
+    pub to_call: f64,
+    // Action nodes: one per child. Chance nodes have one child and none.
+    pub actions: Vec<TreeAction>,
+    pub children: Vec<TreeNode>,
+}
+
+// Betting state while building.
+#[derive(Clone, Copy)]
+struct State {
+    street: usize,
+    pot: f64,
+    stacks: [f64; 2],
+    to_call: f64,
+    raises: u32,
+    // Who bet or raised last on this street, and on the one before.
+    aggressor: Option<Player>,
+    previous_aggressor: Option<Player>,
+    // Cards still in the deck.
+    deck: u32,
+}
+
+struct Builder<'a> {
+    profile: &'a Profile,
+}
+
+// The tree the solver builds for `profile` from a board of `board_cards`
+// cards (3 = from the flop).
+pub fn build(profile: &Profile, board_cards: usize) -> TreeNode {
+    let deck = 52 - (profile.game.lowest_rank() as u32 * 4) - board_cards as u32;
+    let stack = profile.effective_stack;
+    let state = State {
+        street: board_cards.clamp(3, 5) - 3,
+        pot: profile.pot,
+        stacks: [stack, stack],
+        to_call: 0.0,
+        raises: 0,
+        aggressor: None,
+        previous_aggressor: None,
+        deck,
+    };
+    Builder { profile }.action(Player::Oop, state)
+}
+
+impl Builder<'_> {
+    fn sizes(&self, player: Player, street: usize) -> &StreetBetSizes {
+        let sizes = match player {
+            Player::Oop => &self.profile.bet_sizes_oop,
+            Player::Ip => &self.profile.bet_sizes_ip,
+        };
+        sizes.streets()[street].1
+    }
+
+    fn action(&self, player: Player, s: State) -> TreeNode {
+        let me = player.index();
+        let opp = player.other().index();
+        let mut actions = Vec::new();
+        let mut children = Vec::new();
+        let sizes = self.sizes(player, s.street);
+
+        if s.to_call == 0.0 {
+            let check = TreeAction { kind: ActionKind::Check, chips: 0.0, all_in: false, sizes: Vec::new() };
+            let after = if player == Player::Oop { self.action(Player::Ip, s) } else { self.end_street(s) };
+            actions.push(check);
+            children.push(after);
+
+            let donk = player == Player::Oop && s.street > 0 && s.previous_aggressor == Some(Player::Ip);
+            let list = if donk { &sizes.donk } else { &sizes.bet };
+            let max = s.stacks[me].min(s.stacks[opp]);
+            for bet in self.options(ActionKind::Bet, list, sizes.allin, |pct| pct / 100.0 * s.pot, s.stacks[me], max) {
+                let mut next = s;
+                next.pot += bet.chips;
+                next.stacks[me] -= bet.chips;
+                next.to_call = bet.chips;
+                next.aggressor = Some(player);
+                actions.push(bet);
+                children.push(self.action(player.other(), next));
+            }
+        } else {
+            actions.push(TreeAction { kind: ActionKind::Fold, chips: 0.0, all_in: false, sizes: Vec::new() });
+            children.push(self.leaf(NodeKind::Fold(player), s));
+
+            let call = s.to_call.min(s.stacks[me]);
+            let mut called = s;
+            called.pot += call;
+            called.stacks[me] -= call;
+            called.to_call = 0.0;
+            actions.push(TreeAction { kind: ActionKind::Call, chips: call, all_in: called.stacks[me] == 0.0, sizes: Vec::new() });
+            children.push(self.end_street(called));
+
+            if s.raises < self.profile.raise_limit && s.stacks[opp] > 0.0 && s.stacks[me] > s.to_call {
+                let max = s.stacks[me].min(s.stacks[opp] + s.to_call);
+                let to_call = s.to_call;
+                let raise_size = |pct: f64| to_call + pct / 100.0 * (s.pot + to_call);
+                for raise in self.options(ActionKind::Raise, &sizes.raise, sizes.allin, raise_size, s.stacks[me], max) {
+                    let mut next = s;
+                    next.pot += raise.chips;
+                    next.stacks[me] -= raise.chips;
+                    next.to_call = raise.chips - to_call;
+                    next.raises += 1;
+                    next.aggressor = Some(player);
+                    actions.push(raise);
+                    children.push(self.action(player.other(), next));
+                }
+            }
+        }
+
+        TreeNode { kind: NodeKind::Action(player), street: s.street, pot: s.pot, stacks: s.stacks, to_call: s.to_call, actions, children }
+    }
+
+    // Bets or raises for `sizes` (% of pot, turned into chips by `chips`),
+    // plus all-in when `allin` is set. Anything reaching the all-in
+    // threshold of `stack` becomes `max`; equal amounts are merged.
+    fn options(
+        &self,
+        kind: ActionKind,
+        sizes: &[f64],
+        allin: bool,
+        chips: impl Fn(f64) -> f64,
+        stack: f64,
+        max: f64,
+    ) -> Vec<TreeAction> {
+        let mut out: Vec<TreeAction> = Vec::new();
+        let threshold = self.profile.allin_threshold * stack;
+        let requested = sizes.iter().map(|&pct| (chips(pct), Some(pct))).chain(allin.then_some((max, None)));
+        for (amount, pct) in requested {
+            let amount = if amount >= threshold { max } else { amount.min(max) };
+            if amount <= 0.0 {
+                continue;
+            }
+            let i = match out.iter().position(|a| (a.chips - amount).abs() < 1e-6) {
+                Some(i) => i,
+                None => {
+                    out.push(TreeAction { kind, chips: amount, all_in: amount >= max, sizes: Vec::new() });
+                    out.len() - 1
+                }
+            };
+            out[i].sizes.extend(pct);
+        }
+        out.sort_by(|a, b| a.chips.total_cmp(&b.chips));
+        out
+    }
+
+    fn end_street(&self, s: State) -> TreeNode {
+        if s.street + 1 >= STREETS.len() {
+            return self.leaf(NodeKind::Showdown, s);
+        }
+        let mut next = s;
+        next.street += 1;
+        next.deck -= 1;
+        next.to_call = 0.0;
+        next.raises = 0;
+        next.previous_aggressor = s.aggressor;
+        next.aggressor = None;
+        let child = if s.stacks.contains(&0.0) { self.end_street(next) } else { self.action(Player::Oop, next) };
+        TreeNode {
+            kind: NodeKind::Chance { cards: s.deck },
+            street: next.street,
+            pot: s.pot,
+            stacks: s.stacks,
+            to_call: 0.0,
+            actions: Vec::new(),
+            children: vec![child],
+        }
+    }
+
+    fn leaf(&self, kind: NodeKind, s: State) -> TreeNode {
+        TreeNode { kind, street: s.street, pot: s.pot, stacks: s.stacks, to_call: s.to_call, actions: Vec::new(), children: Vec::new() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StreetCount {
+    pub action: u64,
+    pub chance: u64,
+    pub terminal: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TreeEstimate {
+    // Per street, counting every dealt card.
+    pub streets: [StreetCount; 3],
+    // Action x combo pairs the solver stores, summed over action nodes.
+    pub entries: f64,
+    pub memory_bytes: f64,
+    pub seconds: f64,
+}
+
+impl TreeEstimate {
+    pub fn nodes(&self) -> u64 {
+        self.streets.iter().map(|c| c.action + c.chance + c.terminal).sum()
+    }
+}
+
+// Size of the tree the solver builds for `profile` on `board` ("Qs,Jh,2h").
+pub fn estimate(profile: &Profile, board: &str) -> TreeEstimate {
+    let cards = board.split(',').filter(|c| !c.is_empty()).count();
+    let root = build(profile, cards);
+    let combos = [&profile.range_oop, &profile.range_ip].map(|range| {
+        range_weights(range)
+            .iter()
+            .filter(|(_, w)| **w > 0.0)
+            .map(|(class, _)| live_combos(class, board) as f64)
+            .sum::<f64>()
+    });
+
+    let mut est = TreeEstimate { streets: [StreetCount::default(); 3], entries: 0.0, memory_bytes: 0.0, seconds: 0.0 };
+    count(&root, 1, &combos, &mut est);
+    est.memory_bytes = est.entries * BYTES_PER_ENTRY;
+    est.seconds = est.entries * profile.max_iteration as f64 * SECONDS_PER_ENTRY / profile.thread_num.max(1) as f64;
+    est
+}
+
+fn count(node: &TreeNode, times: u64, combos: &[f64; 2], est: &mut TreeEstimate) {
+    let street = &mut est.streets[node.street];
+    match &node.kind {
+        NodeKind::Action(player) => {
+            street.action += times;
+            est.entries += times as f64 * node.actions.len() as f64 * combos[player.index()];
+        }
+        NodeKind::Chance { cards } => {
+            street.chance += times;
+            for child in &node.children {
+                count(child, times * *cards as u64, combos, est);
+            }
+            return;
+        }
+        NodeKind::Showdown | NodeKind::Fold(_) => street.terminal += times,
+    }
+    for child in &node.children {
+        count(child, times, combos, est);
+    }
+}
+
+// Memory the OS could hand out now, where we know how to ask (Linux).
+pub fn available_memory() -> Option<f64> {
+    let text = fs::read_to_string("/proc/meminfo").ok()?;
+    let line = text.lines().find(|l| l.starts_with("MemAvailable:"))?;
+    let kb: f64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024.0)
+}
+
+// 1536 -> "1.5 KB"
+pub fn format_bytes(bytes: f64) -> String {
+    let units = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < units.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 { format!("{:.0} {}", value, units[unit]) } else { format!("{:.1} {}", value, units[unit]) }
+}
+
+// 95 -> "1.6 min"
+pub fn format_duration(seconds: f64) -> String {
+    if seconds < 60.0 {
+        format!("{:.0} s", seconds.max(1.0))
+    } else if seconds < 3600.0 {
+        format!("{:.1} min", seconds / 60.0)
+    } else {
+        format!("{:.1} h", seconds / 3600.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PlayerBetSizes;
+
+    // One bet (and lead) size, one raise, deep stacks so nothing is an all-in.
+    fn small_profile() -> Profile {
+        let street = StreetBetSizes { bet: vec![50.0], raise: vec![100.0], donk: vec![50.0], allin: false };
+        let sizes = PlayerBetSizes { flop: street.clone(), turn: street.clone(), river: street };
+        Profile {
+            pot: 100.0,
+            effective_stack: 10_000.0,
+            bet_sizes_oop: sizes.clone(),
+            bet_sizes_ip: sizes,
+            raise_limit: 1,
+            ..Profile::default()
+        }
+    }
+
+    #[test]
+    fn street_counts_of_a_small_tree() {
+        // One street: OOP checks or bets; IP checks or bets behind a check;
+        // a bet can be folded, called or raised once, a raise folded or
+        // called. That is 6 action nodes, 4 folds and 5 ways to end the
+        // street (check-check and four calls), each dealing the next card
+        // or, on the river, going to showdown.
+        let est = estimate(&small_profile(), "Qs,Jh,2c");
+        let [flop, turn, river] = est.streets;
+        assert_eq!((flop.action, flop.chance, flop.terminal), (6, 0, 4));
+        // 5 turn deals of 49 cards each.
+        assert_eq!((turn.action, turn.chance, turn.terminal), (6 * 5 * 49, 5, 4 * 5 * 49));
+        // 5 river deals of 48 cards below each of the 245 turn streets.
+        let rivers = 5 * 49 * 5 * 48;
+        assert_eq!((river.action, river.chance, river.terminal), (6 * rivers, 5 * 49 * 5, (4 + 5) * rivers));
+        assert_eq!(est.nodes(), 6 + 4 + 5 + 6 * 245 + 4 * 245 + 1225 + 15 * rivers);
+    }
+
+    #[test]
+    fn raise_limit_stops_reraises() {
+        let root = build(&small_profile(), 5);
+        let bet = &root.children[1];
+        let raise = &bet.children[2];
+        let kinds: Vec<ActionKind> = raise.actions.iter().map(|a| a.kind).collect();
+        assert_eq!(kinds, [ActionKind::Fold, ActionKind::Call]);
+    }
+}
@@ -0,0 +1,232 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use colored::*;
+
+use crate::batch::{
+    flop_list, format_flop_list, subset_flops, load_profile, load_progress, load_results, open_dir, record_flop, summary_csv,
+    weighted_average, FlopResult, NodeFrequencies, SUMMARY_FILE,
+};
+use crate::boards::{canonical_flops, flop_name, Game, Subset};
+use crate::config::{Config, Profile};
+use crate::solver::solve;
+use crate::texture::{aggregate, rows_csv, GroupRow, Grouping};
+use crate::ui::flow::convergence_lines;
+
+const USAGE: &str = "usage: batch-flops <flops.txt|all> [out-dir] [--subset N | --generated-subset N]";
+
+// `texas_solver_tui batch-flops <flops.txt|all> [out-dir] [--subset N | --generated-subset N]`
+//
+// Solves every flop with the selected profile (no hero hand: the ranges are
+// solved as configured) and writes the per-flop aggregates to out-dir
+// (default batch_<list name>, or batch_all_<N> for a subset). Run the same
+// command again to resume.
+pub fn run(
+    config: &Config,
+    profile: &Profile,
+    args: &[String],
+    subset: Option<Subset>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let source = args.first().ok_or(USAGE)?;
+    let flops = flop_list(source, subset, profile.game)?;
+    let dir = args.get(1).map(PathBuf::from).unwrap_or_else(|| default_dir(source, subset));
+
+    open_dir(&dir, profile, source)?;
+    let mut done = load_progress(&dir)?;
+    // Weights come from the list, whatever they were when a flop was solved.
+    for (flop, weight) in &flops {
+        if let Some(r) = done.get_mut(&flop_name(flop)) {
+            r.weight = *weight;
+        }
+    }
+    let remaining = flops.iter().filter(|(f, _)| !done.contains_key(&flop_name(f))).count();
+
+    println!("{}", format!("Batch: {} flops, profile '{}', output {}", flops.len(), profile.name, dir.display()).bold().cyan());
+    if remaining < flops.len() {
+        println!("{}", format!("Resuming: {} already solved, {} to go.", flops.len() - remaining, remaining).dimmed());
+    }
+
+    let mut failed = 0;
+    let mut solved_now = 0;
+    for (flop, weight) in &flops {
+        let name = flop_name(flop);
+        if done.contains_key(&name) {
+            continue;
+        }
+        solved_now += 1;
+        println!("{}", format!("[{}/{}] {}", solved_now, remaining, name).bold());
+
+        let solved = match solve(&name, "", profile, &config.solver) {
+            Ok(s) => s,
+            Err(e) => {
+                println!("{}", format!("  failed: {}", e).red());
+                failed += 1;
+                continue;
+            }
+        };
+        let meta = &solved.meta;
+        let result = record_flop(
+            &dir,
+            flop,
+            *weight,
+            &solved.tree,
+            profile,
+            meta.elapsed.as_secs_f64(),
+            meta.convergence.exploitability,
+        )?;
+        println!(
+            "  {} OOP bets {:.1}%, IP bets {:.1}% after a check ({:.1}s)",
+            "done".green(),
+            pct(result.oop.as_ref()),
+            pct(result.ip_vs_check.as_ref()),
+            result.elapsed_seconds
+        );
+        if let (_, Some(warning)) = convergence_lines(&meta.convergence, config.solver.max_exploitability()) {
+            println!("  {}", warning.yellow());
+        }
+        done.insert(name, result);
+    }
+
+    let ordered: Vec<&FlopResult> = flops.iter().filter_map(|(f, _)| done.get(&flop_name(f))).collect();
+    let summary_path = dir.join(SUMMARY_FILE);
+    fs::write(&summary_path, summary_csv(&ordered))?;
+
+    print_table(&ordered);
+    println!("{} {}", "wrote".green(), summary_path.display());
+
+    if failed > 0 {
+        return Err(format!("{} flop(s) failed; run the same command again to retry them", failed).into());
+    }
+    Ok(())
+}
+
+// flops/monotone.txt -> batch_monotone
+fn default_dir(source: &str, subset: Option<Subset>) -> PathBuf {
+    match subset {
+        Some(Subset::Standard(n)) => return PathBuf::from(format!("batch_all_{}", n)),
+        Some(Subset::Generated(n)) => return PathBuf::from(format!("batch_all_generated_{}", n)),
+        None => {}
+    }
+    let stem = Path::new(source).file_stem().and_then(|s| s.to_str()).unwrap_or("flops");
+    PathBuf::from(format!("batch_{}", stem))
+}
+
+fn pct(node: Option<&NodeFrequencies>) -> f64 {
+    node.map(|n| n.aggressive() * 100.0).unwrap_or(0.0)
+}
+
+fn print_table(results: &[&FlopResult]) {
+    println!();
+    println!(
+        "{}",
+        format!("{:<10}  {:>6}  {:>9}  {:>9}  {:>9}  {:>9}", "FLOP", "WEIGHT", "OOP CHECK", "OOP BET", "IP CHECK", "IP BET").bold()
+    );
+    let cell = |v: Option<f64>| v.map(|v| format!("{:.1}%", v * 100.0)).unwrap_or_else(|| "-".to_string());
+    let columns: [fn(&FlopResult) -> Option<f64>; 4] = [
+        |r| r.oop.as_ref().map(|n| n.of("CHECK")),
+        |r| r.oop.as_ref().map(NodeFrequencies::aggressive),
+        |r| r.ip_vs_check.as_ref().map(|n| n.of("CHECK")),
+        |r| r.ip_vs_check.as_ref().map(NodeFrequencies::aggressive),
+    ];
+    for r in results {
+        let [a, b, c, d] = columns.map(|f| cell(f(r)));
+        println!("{:<10}  {:>6}  {:>9}  {:>9}  {:>9}  {:>9}", r.flop.replace(',', ""), r.weight, a, b, c, d);
+    }
+    if results.len() > 1 {
+        let [a, b, c, d] = columns.map(|f| cell(weighted_average(results, f)));
+        let total: f64 = results.iter().map(|r| r.weight).sum();
+        println!("{}", format!("{:<10}  {:>6}  {:>9}  {:>9}  {:>9}  {:>9}", "WEIGHTED", total, a, b, c, d).bold());
+    }
+}
+
+// `texas_solver_tui flops [out.txt] [--subset N | --generated-subset N] [--profile NAME]`
+//
+// Lists the canonical flops (or a reduced subset) of the profile's game with
+// their weights, in the format batch-flops reads.
+pub fn export_list(args: &[String], subset: Option<Subset>, game: Game) -> Result<(), Box<dyn std::error::Error>> {
+    let flops = match subset {
+        Some(_) if game != Game::Holdem => return Err("flop subsets are only available for hold'em".into()),
+        Some(s) => subset_flops(s)?,
+        None => canonical_flops(game).into_iter().map(|(f, w)| (f, w as f64)).collect(),
+    };
+    let text = format_flop_list(&flops);
+    let total: f64 = flops.iter().map(|(_, w)| w).sum();
+
+    match args.first() {
+        Some(out) => {
+            fs::write(out, &text)?;
+            println!("{} {} ({} flops, total weight {})", "wrote".green(), out, flops.len(), total);
+        }
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+// `texas_solver_tui flop-report <batch-dir> [out.csv]`
+//
+// Aggregates a batch by texture class, high card, pairedness and suitedness:
+// how often OOP bets first and IP bets after a check, and how big, as tables
+// with bars. The same rows go to out.csv (default <batch-dir>/textures.csv).
+pub fn texture_report(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = PathBuf::from(args.first().ok_or("usage: flop-report <batch-dir> [out.csv]")?);
+    let profile = load_profile(&dir)?;
+    let results = load_results(&dir, &profile)?;
+    if results.is_empty() {
+        return Err(format!("{} has no solved flops yet", dir.display()).into());
+    }
+
+    println!(
+        "{}",
+        format!("Flop report: {} flops from {} (profile '{}', pot {})", results.len(), dir.display(), profile.name, profile.pot)
+            .bold()
+            .cyan()
+    );
+
+    let mut all_rows = Vec::new();
+    for grouping in Grouping::ALL {
+        let rows = aggregate(&results, grouping, profile.pot, profile.game);
+        print_group_table(grouping, &rows);
+        all_rows.extend(rows);
+    }
+
+    let out = args.get(1).map(PathBuf::from).unwrap_or_else(|| dir.join("textures.csv"));
+    fs::write(&out, rows_csv(&all_rows))?;
+    println!("\n{} {}", "wrote".green(), out.display());
+    Ok(())
+}
+
+const BAR_WIDTH: f64 = 20.0;
+
+fn bar(freq: Option<f64>) -> String {
+    let len = (freq.unwrap_or(0.0) * BAR_WIDTH).round() as usize;
+    format!("{:<w$}", "█".repeat(len), w = BAR_WIDTH as usize)
+}
+
+fn print_group_table(grouping: Grouping, rows: &[GroupRow]) {
+    let pct = |v: Option<f64>| v.map(|v| format!("{:.1}%", v * 100.0)).unwrap_or_else(|| "-".to_string());
+    let size = |v: Option<f64>| v.map(|v| format!("{:.0}%", v)).unwrap_or_else(|| "-".to_string());
+
+    println!("\n{}", format!("=== {} ===", grouping.title()).bold().white());
+    println!(
+        "{}",
+        format!(
+            "{:<19} {:>5} {:>6}  {:>6} {:<20} {:>5}  {:>6} {:<20} {:>5}",
+            "GROUP", "FLOPS", "WEIGHT", "OOP", "BET", "SIZE", "IP", "BET VS CHECK", "SIZE"
+        )
+        .dimmed()
+    );
+    for r in rows {
+        println!(
+            "{:<19} {:>5} {:>6}  {:>6} {} {:>5}  {:>6} {} {:>5}",
+            r.group,
+            r.flops,
+            r.weight,
+            pct(r.oop_bet),
+            bar(r.oop_bet).red(),
+            size(r.oop_size_pct),
+            pct(r.ip_bet),
+            bar(r.ip_bet).red(),
+            size(r.ip_size_pct)
+        );
+    }
+}
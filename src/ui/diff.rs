@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use colored::*;
+use serde_json::{json, Value};
+
+use crate::cli::OutputFormat;
+use crate::config::Profile;
+use crate::diff::{diff_trees, ComboDiff, NodeDiff, Side, TreeDiff};
+use crate::history::{self, find, load_all};
+use crate::json_out::parse::load_tree;
+use crate::solver::{activate_hand_in_range, get_generic_hand, range_weights};
+
+const USAGE: &str = "usage: diff <tree.json|id|#> <tree.json|id|#> [--format json]";
+const SHOWN_NODES: usize = 15;
+const SHOWN_COMBOS: usize = 15;
+const JSON_COMBOS: usize = 100;
+const SHOWN_MISSING: usize = 10;
+// Nodes whose actions all moved less than this are left out of the table.
+const MIN_NODE_CHANGE: f64 = 0.001;
+
+// A tree to compare, with a label for output.
+struct Loaded {
+    label: String,
+    tree: Value,
+    ranges: Option<(HashMap<String, f64>, HashMap<String, f64>)>,
+}
+
+impl Loaded {
+    fn side(&self) -> Side<'_> {
+        let (range_oop, range_ip) = self.ranges.clone().unzip();
+        Side { tree: &self.tree, range_oop, range_ip }
+    }
+}
+
+// `texas_solver_tui diff <a> <b> [--format json]`
+//
+// Each side is a dumped tree file or a history run (id prefix or # from
+// `history list`). Runs weight combos by the ranges they were solved with;
+// plain files weight every combo equally.
+pub fn run(args: &[String], format: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
+    let [a, b] = args else {
+        return Err(USAGE.into());
+    };
+    let a = load(a)?;
+    let b = load(b)?;
+    let top = match format {
+        OutputFormat::Text => SHOWN_COMBOS,
+        OutputFormat::Json => JSON_COMBOS,
+    };
+    let diff = diff_trees(&a.side(), &b.side(), top);
+
+    match format {
+        OutputFormat::Text => print_diff(&a, &b, &diff),
+        OutputFormat::Json => {
+            let doc = json!({
+                "a": a.label,
+                "b": b.label,
+                "nodes_compared": diff.nodes.len(),
+                "only_in_a": diff.only_in_a,
+                "only_in_b": diff.only_in_b,
+                "nodes": diff.nodes.iter().filter(|n| n.max_change() >= MIN_NODE_CHANGE).map(NodeDiff::to_json).collect::<Vec<_>>(),
+                "combos": diff.combos.iter().map(ComboDiff::to_json).collect::<Vec<_>>(),
+            });
+            println!("{}", serde_json::to_string_pretty(&doc)?);
+        }
+    }
+    Ok(())
+}
+
+fn load(key: &str) -> Result<Loaded, Box<dyn std::error::Error>> {
+    if Path::new(key).is_file() {
+        return Ok(Loaded { label: key.to_string(), tree: load_tree(key)?, ranges: None });
+    }
+    let (records, _) = load_all()?;
+    let record = find(&records, key).map_err(|e| format!("'{}' is neither a tree file nor a history run: {}", key, e))?;
+    let run_id = record["run_id"].as_str().unwrap_or("?");
+    let path = history::tree_path(record)
+        .filter(|p| p.is_file())
+        .ok_or_else(|| format!("run {} has no saved tree", run_id))?;
+    let tree = load_tree(&path.to_string_lossy())?;
+    // Runs are solved with hero's class switched on, as in reports.
+    let ranges = Profile::from_json("history", &record["job"]).ok().map(|p| {
+        let generic = get_generic_hand(record["inputs"]["hero"].as_str().unwrap_or(""));
+        (
+            range_weights(&activate_hand_in_range(&p.range_oop, &generic)),
+            range_weights(&activate_hand_in_range(&p.range_ip, &generic)),
+        )
+    });
+    Ok(Loaded { label: format!("run {} ({})", run_id, history::board_string(record)), tree, ranges })
+}
+
+fn print_diff(a: &Loaded, b: &Loaded, diff: &TreeDiff) {
+    println!("{}", format!("Diff: {}  ->  {}", a.label, b.label).bold().cyan());
+    println!(
+        "{} nodes compared, {} subtree(s) only in A, {} only in B",
+        diff.nodes.len(),
+        diff.only_in_a.len(),
+        diff.only_in_b.len()
+    );
+
+    println!("\n{}", "=== Nodes that moved most (range frequencies) ===".bold().white());
+    let moved: Vec<&NodeDiff> = diff.nodes.iter().filter(|n| n.max_change() >= MIN_NODE_CHANGE).take(SHOWN_NODES).collect();
+    if moved.is_empty() {
+        println!("No frequency changed.");
+    }
+    for node in moved {
+        let path = if node.path.is_empty() { "(root)" } else { &node.path };
+        println!("{} {}  {}", path.bold(), node.player.dimmed(), aggression(node.aggression_change()));
+        for (i, action) in node.actions.iter().enumerate() {
+            let (before, after) = (node.before[i], node.after[i]);
+            println!("    {:<16} {:>5.1}% -> {:>5.1}%  {}", action, before * 100.0, after * 100.0, change(after - before));
+        }
+    }
+
+    println!("\n{}", "=== Combos that moved most ===".bold().white());
+    if diff.combos.is_empty() {
+        println!("No combo changed.");
+    }
+    for c in &diff.combos {
+        let path = if c.path.is_empty() { "(root)" } else { &c.path };
+        let strategy = |probs: &[f64]| -> String {
+            c.actions
+                .iter()
+                .zip(probs)
+                .filter(|(_, p)| **p >= 0.005)
+                .map(|(a, p)| format!("{} {:.0}%", a, p * 100.0))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        println!(
+            "{:>5.1}%  {} {:<4} {}\n        {}  ->  {}",
+            c.distance * 100.0,
+            c.combo.bold(),
+            c.player.dimmed(),
+            path,
+            strategy(&c.before),
+            strategy(&c.after)
+        );
+    }
+
+    for (title, paths) in [("Only in A", &diff.only_in_a), ("Only in B", &diff.only_in_b)] {
+        if paths.is_empty() {
+            continue;
+        }
+        println!("\n{}", format!("=== {} ===", title).bold().white());
+        for p in paths.iter().take(SHOWN_MISSING) {
+            println!("  {}", p);
+        }
+        if paths.len() > SHOWN_MISSING {
+            println!("{}", format!("  ... and {} more", paths.len() - SHOWN_MISSING).dimmed());
+        }
+    }
+}
+
+fn change(delta: f64) -> ColoredString {
+    let text = format!("{:+.1}", delta * 100.0);
+    if delta.abs() < 0.05 {
+        text.dimmed()
+    } else if delta > 0.0 {
+        text.green()
+    } else {
+        text.red()
+    }
+}
+
+fn aggression(delta: f64) -> String {
+    if delta.abs() < MIN_NODE_CHANGE {
+        return String::new();
+    }
+    format!("bet/raise {}", change(delta))
+}
@@ -0,0 +1,160 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use colored::*;
+
+use crate::config::{Backend, Config};
+use crate::discovery::{
+    binary_format, discover_solver, find_library, find_resource_dir, format_matches_host, is_executable,
+    version_banner, HOLDEM_COMPAIRER, SHORTDECK_COMPAIRER,
+};
+use crate::library::SolverLibrary;
+use crate::solver::{job_file_path, output_json_path};
+
+fn ok(msg: &str) {
+    println!("  {} {}", "✔".green().bold(), msg);
+}
+
+fn warn(msg: &str) {
+    println!("  {} {}", "!".yellow().bold(), msg.yellow());
+}
+
+fn fail(msg: &str) {
+    println!("  {} {}", "✘".red().bold(), msg.red());
+}
+
+fn section(title: &str) {
+    println!("\n{}", title.bold().white());
+}
+
+// `texas_solver_tui doctor`: report which console_solver would be used, what
+// it prints about itself, and whether its resources directory is complete.
+// Returns Err only for I/O failures; problems found are printed, and the exit
+// status is non-zero if any blocking problem was found.
+pub fn run(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", "TexasSolver TUI - doctor".bold().cyan());
+    let mut problems = 0;
+
+    // === Backend ===
+    section("Backend");
+    match config.solver.backend {
+        Backend::Process => ok("process (spawns console_solver)"),
+        Backend::Library => {
+            ok("library (in-process)");
+            match find_library(&config.solver).and_then(|p| SolverLibrary::open(&p).map(|_| p)) {
+                Ok(p) => ok(&format!("loaded {}", p.display())),
+                Err(e) => {
+                    fail(&e.to_string());
+                    problems += 1;
+                }
+            }
+        }
+    }
+
+    // === Solver binary ===
+    section("Solver binary");
+    let report = discover_solver(&config.solver);
+    for (path, why) in &report.tried {
+        if let Some(why) = why {
+            println!("    {} {}", "tried".dimmed(), format!("{} ({})", path.display(), why).dimmed());
+        }
+    }
+    let solver_path = match &report.found {
+        Some(found) => {
+            ok(&format!("{} (via {})", found.path.display(), found.source));
+            let format = binary_format(&found.path);
+            if !format_matches_host(format) {
+                fail(&format!("{} binary cannot run on {}", format, std::env::consts::OS));
+                problems += 1;
+            } else if !is_executable(&found.path) {
+                fail("file is not executable (chmod +x?)");
+                problems += 1;
+            } else {
+                match version_banner(&found.path, Duration::from_secs(3)) {
+                    Ok(banner) => ok(&format!("banner: {}", banner)),
+                    Err(e) => {
+                        fail(&format!("could not run binary: {}", e));
+                        problems += 1;
+                    }
+                }
+            }
+            Some(found.path.clone())
+        }
+        None if config.solver.backend == Backend::Library => {
+            warn("console_solver not found (not needed by the library backend)");
+            None
+        }
+        None => {
+            fail("console_solver not found (set `solver_path` in the config or $TEXAS_SOLVER_BIN)");
+            problems += 1;
+            None
+        }
+    };
+
+    // === Resources ===
+    section("Resources directory (-r)");
+    let resources = find_resource_dir(&config.solver, solver_path.as_deref());
+    if resources.path.is_dir() {
+        ok(&format!("{} (via {})", resources.path.display(), resources.source));
+    } else {
+        fail(&format!("{} does not exist (via {})", resources.path.display(), resources.source));
+        problems += 1;
+    }
+    if resources.path.join(HOLDEM_COMPAIRER).is_file() {
+        ok(&format!("{} (holdem)", HOLDEM_COMPAIRER));
+    } else {
+        fail(&format!("{} missing: holdem solves will fail", HOLDEM_COMPAIRER));
+        problems += 1;
+    }
+    if resources.path.join(SHORTDECK_COMPAIRER).is_file() {
+        ok(&format!("{} (shortdeck)", SHORTDECK_COMPAIRER));
+    } else {
+        warn(&format!("{} missing: shortdeck solves will fail", SHORTDECK_COMPAIRER));
+    }
+
+    // === Working files ===
+    section("Working files");
+    for (label, path) in [("job file", job_file_path()), ("strategy dump", output_json_path())] {
+        match check_writable_dir(path.parent().unwrap_or(Path::new("."))) {
+            Ok(true) => ok(&format!("{}: {}", label, path.display())),
+            Ok(false) => ok(&format!("{}: {} (directory missing, will be created)", label, path.display())),
+            Err(e) => {
+                fail(&format!("{}: cannot write to {} ({})", label, path.display(), e));
+                problems += 1;
+            }
+        }
+    }
+
+    // === Config ===
+    section("Config");
+    if config.sources.is_empty() {
+        ok("no config files found, using built-in profile");
+    }
+    for src in &config.sources {
+        ok(&format!("loaded {}", src.display()));
+    }
+    ok(&format!("profiles: {}", config.profile_names().join(", ")));
+
+    println!();
+    if problems == 0 {
+        println!("{}", "All checks passed.".green().bold());
+        Ok(())
+    } else {
+        Err(format!("{} problem(s) found", problems).into())
+    }
+}
+
+// Whether `dir` exists, after making sure a file can be created in it or, when
+// it doesn't exist yet, in its nearest existing ancestor (where the solve
+// would create it). Nothing is left behind either way.
+fn check_writable_dir(dir: &Path) -> std::io::Result<bool> {
+    let existing = dir
+        .ancestors()
+        .find(|d| d.is_dir())
+        .unwrap_or(Path::new("."));
+    let probe = existing.join(".texas_solver_tui_doctor");
+    fs::write(&probe, b"")?;
+    fs::remove_file(&probe)?;
+    Ok(existing == dir)
+}
@@ -0,0 +1,70 @@
+use std::path::{Path, PathBuf};
+
+use colored::*;
+use serde_json::Value;
+
+use crate::config::Profile;
+use crate::history::{self, find, load_all};
+use crate::json_out::csv::export_node;
+use crate::json_out::parse::load_tree;
+use crate::solver::{activate_hand_in_range, get_generic_hand};
+
+const USAGE: &str = "usage: export-csv <tree.json|id|#> [node-path] [out.csv] [--per-card]";
+
+// `texas_solver_tui export-csv <tree.json|id|#> [node-path] [out.csv] [--per-card]`
+//
+// The node path uses the dump's own labels separated by '/', e.g.
+// "CHECK/BET 25.000000" or "CHECK/CHECK/9d". For a tree file the profile
+// (--profile) supplies the range weights and should be the one the tree was
+// solved with. A history run (id prefix or `#`) uses the ranges it was
+// recorded with, hero's class switched on as in the solve.
+pub fn run(profile: &Profile, args: &[String], per_card: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let key = args.first().ok_or(USAGE)?;
+    let node_path = args.get(1).map(String::as_str).unwrap_or("");
+    let out = args.get(2).map(PathBuf::from).unwrap_or_else(|| default_output(node_path));
+
+    let (tree, profile) = if Path::new(key).is_file() {
+        (load_tree(key)?, profile.clone())
+    } else {
+        let (records, _) = load_all()?;
+        let record = find(&records, key).map_err(|e| format!("'{}' is neither a tree file nor a history run: {}", key, e))?;
+        history_tree(record)?
+    };
+    let written = export_node(&tree, node_path, &out, per_card, &profile)?;
+
+    for (file, combos) in &written {
+        println!("{} {} ({} combos)", "wrote".green(), file.display(), combos);
+    }
+    Ok(())
+}
+
+// A run's saved tree and the profile it was solved with.
+fn history_tree(record: &Value) -> Result<(Value, Profile), Box<dyn std::error::Error>> {
+    let run_id = record["run_id"].as_str().unwrap_or("?");
+    let path = history::tree_path(record)
+        .filter(|p| p.is_file())
+        .ok_or_else(|| format!("run {} has no saved tree", run_id))?;
+    let tree = load_tree(&path.to_string_lossy())?;
+    let name = record["job"]["profile"].as_str().unwrap_or("history");
+    let mut profile = Profile::from_json(name, &record["job"])
+        .map_err(|e| format!("run {} has unusable job settings: {}", run_id, e))?;
+    let generic = get_generic_hand(record["inputs"]["hero"].as_str().unwrap_or(""));
+    profile.range_oop = activate_hand_in_range(&profile.range_oop, &generic);
+    profile.range_ip = activate_hand_in_range(&profile.range_ip, &generic);
+    Ok((tree, profile))
+}
+
+// "CHECK/BET 25.000000" -> strategy_CHECK_BET_25.000000.csv
+fn default_output(node_path: &str) -> PathBuf {
+    let slug: String = node_path
+        .split('/')
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| s.trim().replace(' ', "_"))
+        .collect::<Vec<_>>()
+        .join("_");
+    if slug.is_empty() {
+        PathBuf::from("strategy_root.csv")
+    } else {
+        PathBuf::from(format!("strategy_{}.csv", slug))
+    }
+}
@@ -1,17 +1,31 @@
-use std::fs::OpenOptions;
 use std::io::{self, Write};
 
 use crate::json_out::parse::{
+    extract_street_strategies,
     hero_strategy_flop_both,
     hero_strategy_river_both,
     hero_strategy_turn_both,
-    load_tree,
+    node_at_line,
+    line_player,
+    node_at_path,
+    short_action,
+    street_node,
     HeroStrategy,
+    Response,
+    StreetStrategies,
 };
-use crate::solver::run_sample_job;
+use crate::boards::{check_cards, Game};
+use crate::cli::OutputFormat;
+use crate::config::{Config, NodeLock, Profile};
+use crate::history;
+use crate::json_out::action::{chips, pot_after, Action};
+use crate::json_out::emit::{batch_document, responses_from_json, streets_json, strategy_from_json, StreetReport};
+use crate::solver::{solve, Convergence, Solved};
+use crate::tree::{available_memory, estimate, format_bytes, format_duration, STREETS};
 use colored::*;
+use serde_json::Value;
 
-fn colorize_card(card: &str) -> String {
+pub fn colorize_card(card: &str) -> String {
     if card.len() < 2 { return card.to_string(); }
     let suit = &card[1..2];
     match suit {
@@ -21,19 +35,214 @@ fn colorize_card(card: &str) -> String {
     }
 }
 
-fn colorize_board(board: &str) -> String {
+pub fn colorize_board(board: &str) -> String {
     board.split(',')
-        .map(|c| colorize_card(c))
+        .map(colorize_card)
         .collect::<Vec<String>>()
         .join(" ")
 }
 
+// How EVs are shown next to the frequencies: in big blinds and in % of the
+// pot at the box's node. The streets follow the check-check line, so that
+// starts as the profile's pot; boxes facing a bet or raise use `at` with the
+// pot after the line.
+#[derive(Clone, Copy)]
+struct EvUnits {
+    big_blind: f64,
+    pot: f64,
+}
+
+impl EvUnits {
+    fn of(profile: &Profile) -> EvUnits {
+        EvUnits { big_blind: profile.big_blind, pot: profile.pot }
+    }
+
+    fn at(&self, pot: f64) -> EvUnits {
+        EvUnits { pot, ..*self }
+    }
+
+    // " EV  16.1bb (64% pot)"
+    fn label(&self, evs: Option<&Vec<f64>>, i: usize) -> String {
+        match evs.and_then(|e| e.get(i)) {
+            Some(ev) => format!(
+                " EV {:>6.1}bb ({:>3.0}% pot)",
+                ev / self.big_blind,
+                ev / self.pot * 100.0
+            ),
+            None => String::new(),
+        }
+    }
+}
+
+// "Solver: 300 iterations, exploitability 0.42% pot", plus a warning when
+// the solve stopped above the configured limit.
+pub fn convergence_lines(convergence: &Convergence, limit: f64) -> (String, Option<String>) {
+    let iterations = convergence.iterations.map(|n| format!("{} iterations, ", n)).unwrap_or_default();
+    let Some(x) = convergence.exploitability else {
+        return (format!("Solver: {}exploitability not reported", iterations), None);
+    };
+    let summary = format!("Solver: {}exploitability {:.2}% pot", iterations, x);
+    let warning = (x > limit).then(|| {
+        format!(
+            "Warning: exploitability {:.2}% pot is above {}%; frequencies may be far from equilibrium (raise max_iteration or lower accuracy).",
+            x, limit
+        )
+    });
+    (summary, warning)
+}
+
+fn print_convergence(convergence: &Convergence, limit: f64, format: OutputFormat) {
+    let (summary, warning) = convergence_lines(convergence, limit);
+    if format == OutputFormat::Text {
+        println!("{}", summary.dimmed());
+    }
+    if let Some(w) = warning {
+        batch_notice(format, w.yellow().to_string());
+    }
+}
+
+// Size of the tree about to be solved (see tree.rs), and a warning when it
+// needs more memory than is available.
+fn print_estimate(profile: &Profile, board: &str, format: OutputFormat) {
+    let est = estimate(profile, board);
+    let actions: Vec<String> = STREETS
+        .iter()
+        .zip(&est.streets)
+        .filter(|(_, c)| c.action > 0)
+        .map(|(street, c)| format!("{} {}", street, thousands(c.action)))
+        .collect();
+    batch_notice(
+        format,
+        format!(
+            "Tree estimate: {} nodes (action nodes: {})\n  ~{} memory, ~{} for {} iterations on {} threads",
+            thousands(est.nodes()),
+            actions.join(", "),
+            format_bytes(est.memory_bytes),
+            format_duration(est.seconds),
+            profile.max_iteration,
+            profile.thread_num
+        )
+        .dimmed()
+        .to_string(),
+    );
+    if let Some(free) = available_memory().filter(|free| est.memory_bytes > *free) {
+        batch_notice(
+            format,
+            format!(
+                "Warning: that is more than the {} of memory available; remove bet sizes or lower raise_limit.",
+                format_bytes(free)
+            )
+            .yellow()
+            .to_string(),
+        );
+    }
+}
+
+// 1234567 -> "1,234,567"
+fn thousands(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+// Node locks of the solved profile: the locked nodes of the tree, and the
+// unlocked baseline solve hero's strategies are compared against.
+struct LockView<'a> {
+    locked: Vec<&'a Value>,
+    baseline: Option<&'a Value>,
+}
+
+impl<'a> LockView<'a> {
+    fn new(tree: &'a Value, locks: &[NodeLock], baseline: Option<&'a Value>) -> LockView<'a> {
+        let locked = locks.iter().filter_map(|l| node_at_path(tree, &l.path).ok()).collect();
+        LockView { locked, baseline }
+    }
+
+    // Which of a street's boxes show a locked node: OOP, IP, then one per
+    // response.
+    fn boxes(&self, tree: &Value, turn: Option<&str>, river: Option<&str>, street: &StreetStrategies) -> Vec<bool> {
+        let Some(start) = street_node(tree, turn, river) else {
+            return vec![false; 2 + street.responses.len()];
+        };
+        let lines = [Vec::new(), vec!["CHECK".to_string()]];
+        lines
+            .iter()
+            .chain(street.responses.iter().map(|r| &r.line))
+            .map(|line| node_at_line(start, line).is_some_and(|n| self.locked.iter().any(|l| std::ptr::eq(*l, n))))
+            .collect()
+    }
+
+    fn baseline_street(&self, hero_hand: &str, turn: Option<&str>, river: Option<&str>) -> Option<StreetStrategies> {
+        let start = street_node(self.baseline?, turn, river)?;
+        Some(extract_street_strategies(start, hero_hand))
+    }
+
+    // Hero's frequencies in the locked solve against the baseline, box by box.
+    fn print_impact(&self, hero_hand: &str, turn: Option<&str>, river: Option<&str>, shown: &StreetStrategies) {
+        let Some(baseline) = self.baseline_street(hero_hand, turn, river) else {
+            return;
+        };
+        println!("{}", "Against the unlocked baseline:".bold());
+        let mut boxes = vec![
+            ("OOP".to_string(), baseline.oop.as_ref(), shown.oop.as_ref()),
+            ("IP vs check".to_string(), baseline.ip.as_ref(), shown.ip.as_ref()),
+        ];
+        for response in &shown.responses {
+            let before = baseline.responses.iter().find(|r| r.line == response.line).map(|r| &r.strategy);
+            let name = format!("{} vs {}", response.player, response.line.last().map(|a| short_action(a)).unwrap_or_default());
+            boxes.push((name, before, Some(&response.strategy)));
+        }
+        for (name, before, after) in boxes {
+            let (Some(before), Some(after)) = (before, after) else { continue };
+            for (i, action) in after.actions.iter().enumerate() {
+                let Some(j) = before.actions.iter().position(|a| a == action) else { continue };
+                let (b, a) = (before.probs[j] * 100.0, after.probs[i] * 100.0);
+                let delta = format!("{:+.1}", a - b);
+                let delta = if (a - b).abs() < 5.0 { delta.dimmed() } else if a > b { delta.green() } else { delta.red() };
+                println!("  {:<12} {:<16} {:>5.1}% -> {:>5.1}%  ({})", name, action, b, a, delta);
+            }
+        }
+    }
+}
+
+// With locks, also solve the profile without them so the locked solve can be
+// compared; None without locks or when the baseline fails.
+fn solve_baseline(config: &Config, profile: &Profile, board: &str, hero_hand: &str, format: OutputFormat) -> Option<Solved> {
+    if profile.locks.is_empty() {
+        return None;
+    }
+    for lock in &profile.locks {
+        batch_notice(format, format!("Locked: {}", lock).magenta().to_string());
+    }
+    batch_notice(format, "Solving the unlocked baseline for comparison...".dimmed().to_string());
+    let mut unlocked = profile.clone();
+    unlocked.locks.clear();
+    match solve(board, hero_hand, &unlocked, &config.solver) {
+        Ok(solved) => Some(solved),
+        Err(e) => {
+            batch_notice(format, format!("Warning: baseline solve failed: {}", e).yellow().to_string());
+            None
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn print_strategy_section(
     title: &str,
     oop: Option<&HeroStrategy>,
     ip: Option<&HeroStrategy>,
-    oop_vs_bet: Option<&HeroStrategy>,
-    hand: &str
+    responses: &[Response],
+    pot: f64,
+    hand: &str,
+    game: Game,
+    units: &EvUnits,
+    locked: &[bool],
 ) {
     // Parse the title to extract the board cards if possible, or just print the title.
     // The title format is usually "FLOP (Ah,Kd,Qs)" or "TURN (..., ...)"
@@ -47,11 +256,12 @@ fn print_strategy_section(
     let h2 = &hand[2..4];
     
     // Evaluate hand strength
-    let strength = crate::evaluator::evaluate_hand(hand, title); // Title contains the board e.g. "FLOP (Ah,Kd,Qs)"
+    let strength = crate::evaluator::evaluate_hand(hand, title, game); // Title contains the board e.g. "FLOP (Ah,Kd,Qs)"
     // Actually title has "FLOP (...)", we need just the board.
     // But wait, evaluate_hand just scans for cards, so passing the title string works fine if it contains the cards!
     
     println!("Hero Hand: {} {}  ({})", colorize_card(h1), colorize_card(h2), strength.italic().yellow());
+    let is_locked = |i: usize| locked.get(i).copied().unwrap_or(false);
     
     // OOP Box
     print_educational_box(
@@ -60,7 +270,8 @@ fn print_strategy_section(
         "They raised, you called. Check to the raiser?",
         oop,
         true, // is_oop (red dot)
-        oop_vs_bet
+        units,
+        is_locked(0)
     );
 
     // IP Box
@@ -70,18 +281,154 @@ fn print_strategy_section(
         "You raised, they called. They checked to you.",
         ip,
         false, // is_ip (green dot)
-        None // IP doesn't face a bet immediately in this tree (since we removed donk bets)
+        units,
+        is_locked(1)
     );
+
+    // One box per bet or raise faced; the sizes of one bet share a table
+    for group in size_groups(responses) {
+        let (first, response) = group[0];
+        let is_oop = response.player == "OOP";
+        let position = if is_oop { "OUT OF POSITION (Big Blind)" } else { "IN POSITION (Button)" };
+        if group.len() == 1 {
+            print_educational_box(
+                position,
+                &facing_role(&response.line),
+                &line_context(&response.line, response.player),
+                Some(&response.strategy),
+                is_oop,
+                &units.at(pot_after(pot, &response.line)),
+                is_locked(2 + first)
+            );
+        } else {
+            let rows: Vec<(&Response, bool)> = group.iter().map(|(i, r)| (*r, is_locked(2 + i))).collect();
+            print_size_table(position, is_oop, &rows, pot);
+        }
+    }
+}
+
+// Responses grouped by the line before the last action, so each group is
+// one bet or raise in its different sizes. Indexes are into `responses`.
+fn size_groups(responses: &[Response]) -> Vec<Vec<(usize, &Response)>> {
+    let mut groups: Vec<Vec<(usize, &Response)>> = Vec::new();
+    for (i, response) in responses.iter().enumerate() {
+        let before = &response.line[..response.line.len() - 1];
+        match groups.iter_mut().find(|g| &g[0].1.line[..g[0].1.line.len() - 1] == before) {
+            Some(group) => group.push((i, response)),
+            None => groups.push(vec![(i, response)]),
+        }
+    }
+    groups
 }
 
+// "Facing a bet", "Facing a raise", "Facing a 3-bet", ...
+fn facing_role(line: &[String]) -> String {
+    match line.iter().filter(|a| Action::parse(a) != Some(Action::Check)).count() {
+        1 => "Facing a bet".to_string(),
+        2 => "Facing a raise".to_string(),
+        n => format!("Facing a {}-bet", n),
+    }
+}
+
+// The street so far from `player`'s side: "You checked, they bet 25."
+fn line_context(line: &[String], player: &str) -> String {
+    // Chips each side has in on the street, OOP first: a raise's label is
+    // what it adds, so the raise-to total is that plus what was in before.
+    let mut committed = [0.0; 2];
+    let steps: Vec<String> = line
+        .iter()
+        .enumerate()
+        .map(|(i, action)| {
+            let who = if line_player(&line[..i]) == player { "you" } else { "they" };
+            let parsed = Action::parse(action);
+            if let Some(amount) = parsed.and_then(|a| a.chips()) {
+                committed[i % 2] += amount;
+            }
+            let verb = match parsed {
+                Some(Action::Bet(amount)) => format!("bet {}", chips(amount)),
+                Some(Action::Raise(_)) => format!("raised to {}", chips(committed[i % 2])),
+                Some(Action::AllIn(_)) => "went all-in".to_string(),
+                Some(Action::Check) => "checked".to_string(),
+                Some(Action::Call) => "called".to_string(),
+                Some(Action::Fold) => "folded".to_string(),
+                // Records from before response lines were kept say just "BET".
+                None if action == "RAISE" => "raised".to_string(),
+                None => action.to_lowercase(),
+            };
+            format!("{} {}", who, verb)
+        })
+        .collect();
+    let text = steps.join(", ");
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(c) => format!("{}{}.", c.to_uppercase(), chars.as_str()),
+        None => String::new(),
+    }
+}
+
+// Hero's response to each size of one bet or raise: fold, call and raise
+// (all raise sizes together) per row. `pot` is the pot at the start of the
+// street; the size's share is of the pot it went into.
+fn print_size_table(position_title: &str, is_oop: bool, rows: &[(&Response, bool)], pot: f64) {
+    let horizontal_line = "─".repeat(70);
+    let (response, _) = rows[0];
+    let line = &response.line;
+    let last = &line[line.len() - 1];
+    let mut before = line[..line.len() - 1].to_vec();
+    before.push(Action::parse(last).map_or_else(|| last.clone(), |a| a.kind().to_string()));
+
+    println!("{}", horizontal_line.dimmed());
+    println!(
+        "{} {}   {}",
+        if is_oop { "🔴" } else { "🟢" },
+        position_title.bold(),
+        format!("(Role: {})", facing_role(line)).italic().dimmed()
+    );
+    println!("{}", horizontal_line.dimmed());
+    println!("📝 {}", format!("{} Your response by size:", line_context(&before, response.player)).yellow());
+    println!("{}", horizontal_line.dimmed());
+
+    println!("  {:<18} {:>7} {:>7} {:>7}", "SIZE", "FOLD".blue(), "CALL".yellow(), "RAISE".red());
+    for (response, locked) in rows {
+        let line = &response.line;
+        let into = pot_after(pot, &line[..line.len() - 1]);
+        let action = Action::parse(&line[line.len() - 1]);
+        let size = match (action.and_then(|a| a.chips()), action.and_then(|a| a.pct_of(into))) {
+            (Some(amount), Some(pct)) => format!("{} ({:.0}% pot)", chips(amount), pct),
+            _ => "all-in".to_string(),
+        };
+        let share = |keep: fn(Action) -> bool| -> f64 {
+            let s = &response.strategy;
+            s.actions
+                .iter()
+                .zip(&s.probs)
+                .filter(|(a, _)| Action::parse(a).is_some_and(keep))
+                .map(|(_, p)| p * 100.0)
+                .sum()
+        };
+        println!(
+            "  {:<18} {:>6.1}% {:>6.1}% {:>6.1}%{}",
+            size,
+            share(|a| a == Action::Fold),
+            share(|a| a == Action::Call),
+            share(|a| a.is_aggressive()),
+            if *locked { format!(" {}", "🔒 LOCKED".magenta().bold()) } else { String::new() }
+        );
+    }
+    println!("{}", horizontal_line.dimmed());
+}
+
+// `locked`: whether the box's node is locked.
 fn print_educational_box(
     position_title: &str,
     role: &str,
     context: &str,
     strategy: Option<&HeroStrategy>,
     is_oop: bool,
-    response_strategy: Option<&HeroStrategy>
+    units: &EvUnits,
+    locked: bool,
 ) {
+    let lock_mark = |l: bool| if l { format!(" {}", "🔒 LOCKED".magenta().bold()) } else { String::new() };
     let width = 70;
     let horizontal_line = "─".repeat(width);
     
@@ -92,7 +439,13 @@ fn print_educational_box(
     // Header Line: Dot + Title ...... Role
     // We calculate spacing based on visible length (stripping colors effectively)
     // But since we aren't using a right border anymore, we can just print them with a nice gap.
-    println!("{} {}   {}", dot, position_title.bold(), format!("(Role: {})", role).italic().dimmed());
+    println!(
+        "{} {}   {}{}",
+        dot,
+        position_title.bold(),
+        format!("(Role: {})", role).italic().dimmed(),
+        lock_mark(locked)
+    );
     
     println!("{}", horizontal_line.dimmed());
 
@@ -104,28 +457,27 @@ fn print_educational_box(
     if let Some(hero) = strategy {
         let max_action_len = hero.actions.iter().map(|s| s.len()).max().unwrap_or(0);
         
-        for (action, prob) in hero.actions.iter().zip(hero.probs.iter()) {
+        for (i, (action, prob)) in hero.actions.iter().zip(hero.probs.iter()).enumerate() {
             let percentage = prob * 100.0;
             if percentage < 0.1 { continue; }
 
-            let action_colored = if action.contains("CHECK") {
-                action.green()
-            } else if action.contains("BET") {
-                action.red()
-            } else if action.contains("FOLD") {
-                action.blue()
-            } else {
-                action.normal()
+            let action_colored = match Action::parse(action) {
+                Some(Action::Check) => action.green(),
+                Some(a) if a.is_aggressive() => action.red(),
+                Some(Action::Fold) => action.blue(),
+                Some(Action::Call) => action.yellow(),
+                _ => action.normal(),
             };
 
             let bar_len = (percentage / 2.5) as usize; // Scale down a bit to fit
             let bar = "█".repeat(bar_len);
             
-            // Format: CHECK : 52.4% |||||
+            // Format: CHECK : 52.4% [EV ...] |||||
             println!(
-                "  {:<w$} : {:>5.1}% {}", 
+                "  {:<w$} : {:>5.1}%{} {}", 
                 action_colored, 
                 percentage, 
+                units.label(hero.evs.as_ref(), i).dimmed(),
                 bar.truecolor(200, 200, 200), 
                 w = max_action_len
             );
@@ -134,48 +486,73 @@ fn print_educational_box(
         println!("  (No strategy found for this range)");
     }
 
-    // Response Strategy (e.g. vs Bet)
-    if let Some(resp) = response_strategy {
-        println!("{}", horizontal_line.dimmed());
-        println!("📝 {}", "If they BET, your response:".yellow());
-        println!("{}", horizontal_line.dimmed());
-        
-        let max_action_len = resp.actions.iter().map(|s| s.len()).max().unwrap_or(0);
-        for (action, prob) in resp.actions.iter().zip(resp.probs.iter()) {
-            let percentage = prob * 100.0;
-            if percentage < 0.1 { continue; }
+    println!("{}", horizontal_line.dimmed());
+}
 
-            let action_colored = if action.contains("CHECK") {
-                action.green()
-            } else if action.contains("BET") {
-                action.red()
-            } else if action.contains("FOLD") {
-                action.blue()
-            } else if action.contains("CALL") {
-                action.yellow()
-            } else {
-                action.normal()
-            };
+pub fn list_profiles(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let default_name = config.profile(None)?.name.clone();
+    println!("{}", "Profiles".bold().cyan());
+    for (name, p) in &config.profiles {
+        let marker = if *name == default_name { "*" } else { " " };
+        println!(
+            " {} {:<24} pot {:>6} stack {:>6} acc {:>4} iter {:>4}  {}",
+            marker, name.bold(), p.pot, p.effective_stack, p.accuracy, p.max_iteration,
+            p.description.dimmed()
+        );
+    }
+    if config.sources.is_empty() {
+        println!("{}", "(no config files found, using built-in profile)".dimmed());
+    }
+    for src in &config.sources {
+        println!("{}", format!("loaded {}", src.display()).dimmed());
+    }
+    Ok(())
+}
 
-            let bar_len = (percentage / 2.5) as usize;
-            let bar = "█".repeat(bar_len);
-            
-            println!(
-                "  {:<w$} : {:>5.1}% {}", 
-                action_colored, 
-                percentage, 
-                bar.truecolor(200, 200, 200), 
-                w = max_action_len
-            );
-        }
+// Ask which profile to use when the config offers more than one and none was
+// chosen on the command line. Empty input picks the default profile.
+fn select_profile<'a>(config: &'a Config, cli_choice: Option<&str>) -> Result<&'a Profile, Box<dyn std::error::Error>> {
+    if cli_choice.is_some() || config.profiles.len() < 2 {
+        return config.profile(cli_choice);
     }
 
-    println!("{}", horizontal_line.dimmed());
+    let default_name = config.profile(None)?.name.clone();
+    let names = config.profile_names();
+    println!("Available profiles:");
+    for (i, name) in names.iter().enumerate() {
+        let p = &config.profiles[name];
+        let marker = if *name == default_name { " (default)" } else { "" };
+        println!("  {}) {}{}  {}", i + 1, name.bold(), marker, p.description.dimmed());
+    }
+
+    loop {
+        print!("Select profile [{}]: ", default_name);
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let choice = input.trim();
+        if choice.is_empty() {
+            return config.profile(None);
+        }
+        // Accept either the list number or the profile name.
+        let by_index = choice
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| names.get(i.wrapping_sub(1)));
+        let name = by_index.map(String::as_str).unwrap_or(choice);
+        match config.profile(Some(name)) {
+            Ok(p) => return Ok(p),
+            Err(e) => println!("{}", e.to_string().red()),
+        }
+    }
 }
 
-pub fn run() -> Result<(), Box<dyn std::error::Error>> {
+pub fn run(config: &Config, profile_name: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
     println!("{}", "TexasSolver TUI (Rust) - prototype".bold().cyan());
 
+    let profile = select_profile(config, profile_name)?;
+    println!("{}", format!("Profile: {}", profile.name).dimmed());
+
     // Ask for hero hand
     print!("Enter hero hand (e.g. AhKd): ");
     io::stdout().flush()?;
@@ -215,6 +592,11 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
         .filter(|s| !s.is_empty())
         .collect();
 
+    if let Some(e) = card_error(profile.game, &hero_hand, &all_cards) {
+        println!("{}", format!("{} Aborting.", e).red());
+        return Ok(());
+    }
+
     let flop_board;
     let mut prefilled_turn = None;
     let mut prefilled_river = None;
@@ -236,29 +618,55 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
+    print_estimate(profile, &flop_board, OutputFormat::Text);
+    loop {
+        print!("Solve? [Y/n, t = browse the betting tree] ");
+        io::stdout().flush()?;
+        input.clear();
+        io::stdin().read_line(&mut input)?;
+        match input.trim().to_ascii_lowercase().chars().next() {
+            Some('n') => {
+                println!("Cancelled.");
+                return Ok(());
+            }
+            Some('t') => crate::ui::tree::browse(profile, 3)?,
+            _ => break,
+        }
+    }
+
     println!(
         "{}",
-        format!("Running C++ solver (single job) for flop {}... This may take some time.", flop_board).dimmed()
+        format!("Running solver (single job) for flop {}... This may take some time.", flop_board).dimmed()
     );
-    if let Err(e) = run_sample_job(&flop_board, &hero_hand) {
-        println!("{}", format!("Solver error: {}", e).red());
-        return Ok(());
-    }
-
-    let json_path = "./strategy_debug.json";
-    let tree = load_tree(json_path)?;
+    let solved = match solve(&flop_board, &hero_hand, profile, &config.solver) {
+        Ok(solved) => solved,
+        Err(e) => {
+            println!("{}", format!("Solver error: {}", e).red());
+            return Ok(());
+        }
+    };
+    let tree = &solved.tree;
+    let units = EvUnits::of(profile);
+    print_convergence(&solved.meta.convergence, config.solver.max_exploitability(), OutputFormat::Text);
+    let baseline = solve_baseline(config, profile, &flop_board, &hero_hand, OutputFormat::Text);
+    let locks = LockView::new(tree, &profile.locks, baseline.as_ref().map(|b| &b.tree));
 
-    let (flop_oop, flop_ip, flop_oop_vs_bet) = hero_strategy_flop_both(&tree, &hero_hand);
+    let flop: StreetStrategies = hero_strategy_flop_both(tree, &hero_hand);
     print_strategy_section(
         &format!("FLOP ({})", colorize_board(&flop_board)), 
-        flop_oop.as_ref(), 
-        flop_ip.as_ref(), 
-        flop_oop_vs_bet.as_ref(),
-        &hero_hand
+        flop.oop.as_ref(),
+        flop.ip.as_ref(),
+        &flop.responses,
+        profile.pot,
+        &hero_hand,
+        profile.game,
+        &units,
+        &locks.boxes(tree, None, None, &flop)
     );
+    locks.print_impact(&hero_hand, None, None, &flop);
 
     // === TURN ===
-    let turn_card = if let Some(t) = prefilled_turn {
+    let mut turn_card = if let Some(t) = prefilled_turn {
         println!("\nTurn card pre-filled: {}", colorize_card(&t));
         t
     } else {
@@ -269,28 +677,37 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
         let turn_raw = normalize_board_fragment(&input);
         turn_raw
             .split(',')
-            .filter(|s| !s.is_empty())
-            .next()
+            .find(|s| !s.is_empty())
             .unwrap_or("")
             .to_string()
     };
 
-    let (_turn_oop, turn_ip) = if !turn_card.is_empty() {
-        let (t_oop, t_ip, t_oop_vs_bet) = hero_strategy_turn_both(&tree, &hero_hand, &turn_card).unwrap_or((None, None, None));
+    let board: Vec<&str> = flop_board.split(',').collect();
+    if !turn_card.is_empty() && let Some(e) = card_error(profile.game, &hero_hand, &[&board[..], &[turn_card.as_str()]].concat()) {
+        println!("{}", format!("{} Skipping the turn.", e).red());
+        turn_card.clear();
+    }
+    let turn: Option<StreetStrategies> = if !turn_card.is_empty() {
+        let t = hero_strategy_turn_both(tree, &hero_hand, &turn_card).unwrap_or_default();
         print_strategy_section(
             &format!("TURN ({}, {})", colorize_board(&flop_board), colorize_card(&turn_card)), 
-            t_oop.as_ref(), 
-            t_ip.as_ref(), 
-            t_oop_vs_bet.as_ref(),
-            &hero_hand
+            t.oop.as_ref(),
+            t.ip.as_ref(),
+            &t.responses,
+            profile.pot,
+            &hero_hand,
+            profile.game,
+            &units,
+            &locks.boxes(tree, Some(&turn_card), None, &t)
         );
-        (t_oop, t_ip)
+        locks.print_impact(&hero_hand, Some(&turn_card), None, &t);
+        Some(t)
     } else {
-        (None, None)
+        None
     };
 
     // === RIVER ===
-    let river_card = if let Some(r) = prefilled_river {
+    let mut river_card = if let Some(r) = prefilled_river {
         println!("\nRiver card pre-filled: {}", colorize_card(&r));
         r
     } else {
@@ -301,64 +718,146 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
         let river_raw = normalize_board_fragment(&input);
         river_raw
             .split(',')
-            .filter(|s| !s.is_empty())
-            .next()
+            .find(|s| !s.is_empty())
             .unwrap_or("")
             .to_string()
     };
 
-    let (_river_oop, river_ip) = if !river_card.is_empty() {
+    if !river_card.is_empty()
+        && !turn_card.is_empty()
+        && let Some(e) = card_error(profile.game, &hero_hand, &[&board[..], &[turn_card.as_str(), river_card.as_str()]].concat())
+    {
+        println!("{}", format!("{} Skipping the river.", e).red());
+        river_card.clear();
+    }
+    let river: Option<StreetStrategies> = if !river_card.is_empty() {
         if turn_card.is_empty() {
             println!(
                 "{}",
                 format!("River '{}' given without a turn card. Please provide a turn to see river strategy.", river_card).red()
             );
-            (None, None)
+            None
         } else {
-            let (r_oop, r_ip, r_oop_vs_bet) = hero_strategy_river_both(&tree, &hero_hand, &turn_card, &river_card).unwrap_or((None, None, None));
+            let r = hero_strategy_river_both(tree, &hero_hand, &turn_card, &river_card).unwrap_or_default();
             print_strategy_section(
                 &format!("RIVER ({}, {}, {})", colorize_board(&flop_board), colorize_card(&turn_card), colorize_card(&river_card)), 
-                r_oop.as_ref(), 
-                r_ip.as_ref(), 
-                r_oop_vs_bet.as_ref(),
-                &hero_hand
+                r.oop.as_ref(),
+                r.ip.as_ref(),
+                &r.responses,
+                profile.pot,
+                &hero_hand,
+                profile.game,
+                &units,
+                &locks.boxes(tree, Some(&turn_card), Some(&river_card), &r)
             );
-            (r_oop, r_ip)
+            locks.print_impact(&hero_hand, Some(&turn_card), Some(&river_card), &r);
+            Some(r)
         }
     } else {
-        (None, None)
+        None
     };
 
-    if let Err(e) = append_summary(
-        &hero_hand,
-        &flop_board,
-        if turn_card.is_empty() {
-            None
-        } else {
-            Some(turn_card.as_str())
-        },
-        if river_card.is_empty() {
-            None
-        } else {
-            Some(river_card.as_str())
-        },
-        flop_ip.as_ref(), // Default to IP for summary, or we could expand this later
-        turn_ip.as_ref(),
-        river_ip.as_ref(),
-    ) {
-        println!("Warning: failed to write summary file: {}", e);
+    let flop_cards: Vec<String> = flop_board.split(',').map(String::from).collect();
+    let streets = street_reports(&flop_cards, &turn_card, &river_card, &flop, turn.as_ref(), river.as_ref());
+    let turn_opt = turn.as_ref().map(|_| turn_card.as_str());
+    let river_opt = river.as_ref().map(|_| river_card.as_str());
+    match history::record(profile, &hero_hand, &flop_cards, turn_opt, river_opt, &streets, &solved.meta, tree, config.history_trees) {
+        Ok(run_id) => println!("{}", format!("\nSaved to history as {}", run_id).dimmed()),
+        Err(e) => println!("Warning: failed to write history: {}", e),
     }
 
     Ok(())
 }
 
+// The streets that were shown, in the shape JSON output and history use.
+fn street_reports<'a>(
+    flop_cards: &[String],
+    turn_card: &str,
+    river_card: &str,
+    flop: &'a StreetStrategies,
+    turn: Option<&'a StreetStrategies>,
+    river: Option<&'a StreetStrategies>,
+) -> Vec<StreetReport<'a>> {
+    let mut board = flop_cards.to_vec();
+    let mut streets = vec![StreetReport {
+        street: "flop",
+        board: board.clone(),
+        oop: flop.oop.as_ref(),
+        ip: flop.ip.as_ref(),
+        responses: &flop.responses,
+    }];
+    if let Some(t) = turn {
+        board.push(turn_card.to_string());
+        streets.push(StreetReport { street: "turn", board: board.clone(), oop: t.oop.as_ref(), ip: t.ip.as_ref(), responses: &t.responses });
+    }
+    if let Some(r) = river {
+        board.push(river_card.to_string());
+        streets.push(StreetReport { street: "river", board: board.clone(), oop: r.oop.as_ref(), ip: r.ip.as_ref(), responses: &r.responses });
+    }
+    streets
+}
+
+// Re-open a history record: print its streets exactly as they were shown.
+pub fn show_record(record: &Value) {
+    let hero_hand = record["inputs"]["hero"].as_str().unwrap_or("");
+    if hero_hand.len() != 4 {
+        println!("{}", "Record has no usable hero hand.".red());
+        return;
+    }
+    let profile = Profile::from_json("history", &record["job"]).unwrap_or_default();
+    let units = EvUnits::of(&profile);
+    let empty = Vec::new();
+    for street in record["streets"].as_array().unwrap_or(&empty) {
+        let board: Vec<&str> = street["board"]
+            .as_array()
+            .map(|a| a.iter().filter_map(|c| c.as_str()).collect())
+            .unwrap_or_default();
+        if board.len() < 3 {
+            continue;
+        }
+        let flop = colorize_board(&board[..3].join(","));
+        let title = match board.len() {
+            3 => format!("FLOP ({})", flop),
+            4 => format!("TURN ({}, {})", flop, colorize_card(board[3])),
+            _ => format!("RIVER ({}, {}, {})", flop, colorize_card(board[3]), colorize_card(board[4])),
+        };
+        let oop = strategy_from_json(&street["oop"]);
+        let ip = strategy_from_json(&street["ip"]);
+        let responses = responses_from_json(street);
+        print_strategy_section(&title, oop.as_ref(), ip.as_ref(), &responses, profile.pot, hero_hand, profile.game, &units, &[]);
+    }
+}
+
+// In JSON mode stdout carries only the document, so progress and warnings go
+// to stderr and problems that text mode reports inline become errors.
+fn batch_notice(format: OutputFormat, msg: String) {
+    match format {
+        OutputFormat::Text => println!("{}", msg),
+        OutputFormat::Json => eprintln!("{}", msg),
+    }
+}
+
+fn batch_abort(format: OutputFormat, msg: String) -> Result<(), Box<dyn std::error::Error>> {
+    match format {
+        OutputFormat::Text => {
+            println!("{}", msg.red());
+            Ok(())
+        }
+        OutputFormat::Json => Err(msg.into()),
+    }
+}
+
 pub fn run_batch(
+    config: &Config,
+    profile: &Profile,
     hero_input: &str,
     flop_input: &str,
     turn_input: Option<&str>,
     river_input: Option<&str>,
+    format: OutputFormat,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    println!("{}", "TexasSolver TUI (Rust) - prototype (batch mode)".bold().cyan());
+    batch_notice(format, "TexasSolver TUI (Rust) - prototype (batch mode)".bold().cyan().to_string());
+    batch_notice(format, format!("Profile: {}", profile.name).dimmed().to_string());
 
     // Normalize hero hand in the same way as interactive mode
     let hero_hand_raw = hero_input.trim();
@@ -375,181 +874,158 @@ pub fn run_batch(
     };
 
     if hero_hand.len() != 4 {
-        println!(
-            "{}",
-            format!("Warning: hero hand '{}' does not look like a 4-char hand string (e.g. AhKd)", hero_hand_raw).red()
+        batch_notice(
+            format,
+            format!("Warning: hero hand '{}' does not look like a 4-char hand string (e.g. AhKd)", hero_hand_raw).red().to_string()
         );
     }
 
     // === FLOP ===
     let flop_board = normalize_board_fragment(flop_input);
-    let flop_cards: Vec<&str> = flop_board
+    let flop_cards: Vec<String> = flop_board
         .split(',')
         .filter(|s| !s.is_empty())
+        .map(String::from)
         .collect();
     if flop_cards.len() != 3 {
-        println!(
-            "{}",
-            format!("Flop '{}' is invalid (need exactly 3 cards like 'Qs,Jh,2h'). Aborting.", flop_board).red()
+        return batch_abort(
+            format,
+            format!("Flop '{}' is invalid (need exactly 3 cards like 'Qs,Jh,2h'). Aborting.", flop_board)
         );
-        return Ok(());
     }
 
-    println!(
-        "{}",
-        format!("Running C++ solver (single job) for flop {}... This may take some time.", flop_board).dimmed()
-    );
-    if let Err(e) = run_sample_job(&flop_board, &hero_hand) {
-        println!("{}", format!("Solver error: {}", e).red());
-        return Ok(());
+    // === TURN / RIVER inputs ===
+    let turn_card = turn_input
+        .map(|raw| normalize_board_fragment(raw).split(',').find(|s| !s.is_empty()).unwrap_or("").to_string())
+        .unwrap_or_default();
+    let river_card = river_input
+        .map(|raw| normalize_board_fragment(raw).split(',').find(|s| !s.is_empty()).unwrap_or("").to_string())
+        .unwrap_or_default();
+    // Text mode still shows the flop before complaining, as it always has.
+    if format == OutputFormat::Json && !river_card.is_empty() && turn_card.is_empty() {
+        return batch_abort(
+            format,
+            format!("River '{}' given without a turn card. Please provide a turn to see river strategy.", river_card)
+        );
+    }
+    let board: Vec<&str> = flop_cards
+        .iter()
+        .map(String::as_str)
+        .chain([turn_card.as_str(), river_card.as_str()].into_iter().filter(|c| !c.is_empty()))
+        .collect();
+    if let Some(e) = card_error(profile.game, &hero_hand, &board) {
+        return batch_abort(format, e);
     }
 
-    let json_path = "./strategy_debug.json";
-    let tree = load_tree(json_path)?;
-
-    let (flop_oop, flop_ip, flop_oop_vs_bet) = hero_strategy_flop_both(&tree, &hero_hand);
-    print_strategy_section(
-        &format!("FLOP ({})", colorize_board(&flop_board)), 
-        flop_oop.as_ref(), 
-        flop_ip.as_ref(), 
-        flop_oop_vs_bet.as_ref(),
-        &hero_hand
+    print_estimate(profile, &flop_board, format);
+    batch_notice(
+        format,
+        format!("Running solver (single job) for flop {}... This may take some time.", flop_board).dimmed().to_string()
     );
+    let solved = match solve(&flop_board, &hero_hand, profile, &config.solver) {
+        Ok(solved) => solved,
+        Err(e) => return batch_abort(format, format!("Solver error: {}", e)),
+    };
+    let tree = &solved.tree;
+    let units = EvUnits::of(profile);
+    print_convergence(&solved.meta.convergence, config.solver.max_exploitability(), format);
+    let baseline = solve_baseline(config, profile, &flop_board, &hero_hand, format);
+    let locks = LockView::new(tree, &profile.locks, baseline.as_ref().map(|b| &b.tree));
 
-    // === TURN ===
-    let mut turn_card = String::new();
-    if let Some(raw_turn) = turn_input {
-        let turn_raw = normalize_board_fragment(raw_turn);
-        turn_card = turn_raw
-            .split(',')
-            .filter(|s| !s.is_empty())
-            .next()
-            .unwrap_or("")
-            .to_string();
-    }
-
-    let (_turn_oop, turn_ip) = if !turn_card.is_empty() {
-        let (t_oop, t_ip, t_oop_vs_bet) = hero_strategy_turn_both(&tree, &hero_hand, &turn_card).unwrap_or((None, None, None));
-        print_strategy_section(
-            &format!("TURN ({}, {})", colorize_board(&flop_board), colorize_card(&turn_card)), 
-            t_oop.as_ref(), 
-            t_ip.as_ref(), 
-            t_oop_vs_bet.as_ref(),
-            &hero_hand
-        );
-        (t_oop, t_ip)
+    let flop: StreetStrategies = hero_strategy_flop_both(tree, &hero_hand);
+    let turn: Option<StreetStrategies> = if !turn_card.is_empty() {
+        Some(hero_strategy_turn_both(tree, &hero_hand, &turn_card).unwrap_or_default())
     } else {
-        (None, None)
+        None
     };
-
-    // === RIVER ===
-    let mut river_card = String::new();
-    if let Some(raw_river) = river_input {
-        let river_raw = normalize_board_fragment(raw_river);
-        river_card = river_raw
-            .split(',')
-            .filter(|s| !s.is_empty())
-            .next()
-            .unwrap_or("")
-            .to_string();
-    }
-
-    let (_river_oop, river_ip) = if !river_card.is_empty() {
-        if turn_card.is_empty() {
-            println!(
-                "{}",
-                format!("River '{}' given without a turn card. Please provide a turn to see river strategy.", river_card).red()
-            );
-            (None, None)
-        } else {
-            let (r_oop, r_ip, r_oop_vs_bet) = hero_strategy_river_both(&tree, &hero_hand, &turn_card, &river_card).unwrap_or((None, None, None));
-            print_strategy_section(
-                &format!("RIVER ({}, {}, {})", colorize_board(&flop_board), colorize_card(&turn_card), colorize_card(&river_card)), 
-                r_oop.as_ref(), 
-                r_ip.as_ref(), 
-                r_oop_vs_bet.as_ref(),
-                &hero_hand
-            );
-            (r_oop, r_ip)
-        }
+    let river: Option<StreetStrategies> = if !river_card.is_empty() && !turn_card.is_empty() {
+        Some(hero_strategy_river_both(tree, &hero_hand, &turn_card, &river_card).unwrap_or_default())
     } else {
-        (None, None)
+        None
     };
 
-    if let Err(e) = append_summary(
-        &hero_hand,
-        &flop_board,
-        if turn_card.is_empty() {
-            None
-        } else {
-            Some(turn_card.as_str())
-        },
-        if river_card.is_empty() {
-            None
-        } else {
-            Some(river_card.as_str())
-        },
-        flop_ip.as_ref(),
-        turn_ip.as_ref(),
-        river_ip.as_ref(),
-    ) {
-        println!("Warning: failed to write summary file: {}", e);
-    }
-
-    Ok(())
-}
+    let streets = street_reports(&flop_cards, &turn_card, &river_card, &flop, turn.as_ref(), river.as_ref());
+    let turn_opt = turn.as_ref().map(|_| turn_card.as_str());
+    let river_opt = river.as_ref().map(|_| river_card.as_str());
 
-fn append_summary(
-    hero_hand: &str,
-    flop_board: &str,
-    turn_card: Option<&str>,
-    river_card: Option<&str>,
-    flop: Option<&HeroStrategy>,
-    turn: Option<&HeroStrategy>,
-    river: Option<&HeroStrategy>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let summary_path = "resources/outputs/tui_summary.txt";
-    let mut file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(summary_path)?;
-
-    writeln!(file, "=== TUI RUN ===")?;
-    writeln!(file, "Hero: {}", hero_hand)?;
-    writeln!(file, "Flop: {}", flop_board)?;
-    if let Some(t) = turn_card {
-        writeln!(file, "Turn: {}", t)?;
-    }
-    if let Some(r) = river_card {
-        writeln!(file, "River: {}", r)?;
-    }
-
-    if let Some(h) = flop {
-        writeln!(file, "Flop strategy:")?;
-        for (action, p) in h.actions.iter().zip(h.probs.iter()) {
-            writeln!(file, "  {}: {:.4}", action, p)?;
-        }
-    }
-    if let Some(h) = turn {
-        writeln!(file, "Turn strategy:")?;
-        for (action, p) in h.actions.iter().zip(h.probs.iter()) {
-            writeln!(file, "  {}: {:.4}", action, p)?;
+    match format {
+        OutputFormat::Text => {
+            print_strategy_section(
+                &format!("FLOP ({})", colorize_board(&flop_board)), 
+                flop.oop.as_ref(),
+                flop.ip.as_ref(),
+                &flop.responses,
+                profile.pot,
+                &hero_hand,
+                profile.game,
+                &units,
+                &locks.boxes(tree, None, None, &flop)
+            );
+            locks.print_impact(&hero_hand, None, None, &flop);
+            if let Some(t) = &turn {
+                print_strategy_section(
+                    &format!("TURN ({}, {})", colorize_board(&flop_board), colorize_card(&turn_card)), 
+                    t.oop.as_ref(),
+                    t.ip.as_ref(),
+                    &t.responses,
+                    profile.pot,
+                    &hero_hand,
+                    profile.game,
+                    &units,
+                    &locks.boxes(tree, Some(&turn_card), None, t)
+                );
+                locks.print_impact(&hero_hand, Some(&turn_card), None, t);
+            }
+            if !river_card.is_empty() && turn_card.is_empty() {
+                println!(
+                    "{}",
+                    format!("River '{}' given without a turn card. Please provide a turn to see river strategy.", river_card).red()
+                );
+            }
+            if let Some(r) = &river {
+                print_strategy_section(
+                    &format!("RIVER ({}, {}, {})", colorize_board(&flop_board), colorize_card(&turn_card), colorize_card(&river_card)), 
+                    r.oop.as_ref(),
+                    r.ip.as_ref(),
+                    &r.responses,
+                    profile.pot,
+                    &hero_hand,
+                    profile.game,
+                    &units,
+                    &locks.boxes(tree, Some(&turn_card), Some(&river_card), r)
+                );
+                locks.print_impact(&hero_hand, Some(&turn_card), Some(&river_card), r);
+            }
         }
-    }
-    if let Some(h) = river {
-        writeln!(file, "River strategy:")?;
-        for (action, p) in h.actions.iter().zip(h.probs.iter()) {
-            writeln!(file, "  {}: {:.4}", action, p)?;
+        OutputFormat::Json => {
+            let mut doc = batch_document(
+                profile,
+                &hero_hand,
+                &flop_cards,
+                turn_opt,
+                river_opt,
+                &streets,
+                &solved.meta,
+            );
+            if locks.baseline.is_some() {
+                let b_flop = locks.baseline_street(&hero_hand, None, None).unwrap_or_default();
+                let b_turn = turn.as_ref().and_then(|_| locks.baseline_street(&hero_hand, Some(&turn_card), None));
+                let b_river = river.as_ref().and_then(|_| locks.baseline_street(&hero_hand, Some(&turn_card), Some(&river_card)));
+                let b_streets = street_reports(&flop_cards, &turn_card, &river_card, &b_flop, b_turn.as_ref(), b_river.as_ref());
+                doc["baseline_streets"] = streets_json(&hero_hand, &b_streets, profile.game);
+            }
+            println!("{}", serde_json::to_string_pretty(&doc)?);
         }
     }
 
-    writeln!(file)?;
+    match history::record(profile, &hero_hand, &flop_cards, turn_opt, river_opt, &streets, &solved.meta, tree, config.history_trees) {
+        Ok(run_id) => batch_notice(format, format!("Saved to history as {}", run_id).dimmed().to_string()),
+        Err(e) => batch_notice(format, format!("Warning: failed to write history: {}", e)),
+    }
 
     Ok(())
 }
 
-
-
 fn normalize_board_fragment(raw: &str) -> String {
     let t = raw.trim();
     if t.is_empty() {
@@ -571,7 +1047,7 @@ fn normalize_board_fragment(raw: &str) -> String {
     // Otherwise, treat a continuous string like "QsJh2h" as 3 consecutive 2-char cards.
     let cleaned: String = t.chars().filter(|c| !c.is_whitespace()).collect();
     let bytes = cleaned.as_bytes();
-    if bytes.len() % 2 != 0 {
+    if !bytes.len().is_multiple_of(2) {
         return cleaned;
     }
     let mut cards = Vec::new();
@@ -584,9 +1060,37 @@ fn normalize_board_fragment(raw: &str) -> String {
     cards.join(",")
 }
 
+// Hero's cards (when the hand looks like one) and the board must come from
+// the profile's deck, each card once.
+fn card_error(game: Game, hero_hand: &str, board: &[&str]) -> Option<String> {
+    let mut cards = board.to_vec();
+    if hero_hand.len() == 4 {
+        cards.extend([&hero_hand[0..2], &hero_hand[2..4]]);
+    }
+    check_cards(game, &cards).err().map(|e| format!("Invalid cards: {}.", e))
+}
+
 fn normalize_card(card: &str) -> String {
     let mut chars = card.chars();
     let rank = chars.next().unwrap_or('X');
     let suit = chars.next().unwrap_or('x');
     format!("{}{}", rank.to_ascii_uppercase(), suit.to_ascii_lowercase())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_out::parse::tests::street_fixture;
+
+    #[test]
+    fn sizes_of_one_bet_share_a_group() {
+        let streets = extract_street_strategies(&street_fixture(), "AhKd");
+        assert_eq!(streets.responses.len(), 5);
+        let groups: Vec<Vec<usize>> = size_groups(&streets.responses).iter().map(|g| g.iter().map(|(i, _)| *i).collect()).collect();
+        // Both IP bets after the check, then the check-raise, the lead and
+        // the raise of the lead on their own.
+        assert_eq!(groups, vec![vec![0, 2], vec![1], vec![3], vec![4]]);
+        let players: Vec<&str> = streets.responses.iter().map(|r| r.player).collect();
+        assert_eq!(players, vec!["OOP", "IP", "OOP", "IP", "OOP"]);
+    }
+}
@@ -0,0 +1,214 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use colored::*;
+use serde_json::{json, Value};
+
+use crate::cli::OutputFormat;
+use crate::config::Config;
+use crate::discovery::find_resource_dir;
+use crate::gametree::{action_label, load, summarize, GameNode, NodeType, Payoff, TreeSummary, ROUNDS};
+use crate::json_out::action::chips;
+
+const USAGE: &str = "usage: gametree [<file.km|name> [depth]] [--format json]";
+const DEFAULT_DEPTH: usize = 4;
+// Lines listed under "Final pots" before the rest are summed up.
+const SHOWN_POTS: usize = 12;
+
+// `texas_solver_tui gametree [<file.km|name> [depth]]`
+//
+// Without arguments, lists the game trees in the resources directory. With
+// one, summarizes it (nodes by street and type, depth, pots) and draws the
+// tree `depth` actions deep. A name without a path is looked up in
+// <resources>/gametree/, with or without ".km".
+pub fn run(config: &Config, args: &[String], format: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = find_resource_dir(&config.solver, None).path.join("gametree");
+    let Some(key) = args.first() else {
+        return list(&dir, format);
+    };
+    let depth = match args.get(1) {
+        Some(d) => d.parse::<usize>().map_err(|_| format!("'{}' is not a depth; {}", d, USAGE))?,
+        None => DEFAULT_DEPTH,
+    };
+    if args.len() > 2 {
+        return Err(USAGE.into());
+    }
+
+    let path = resolve(&dir, key)?;
+    let root = load(&path)?;
+    let summary = summarize(&root);
+    match format {
+        OutputFormat::Text => {
+            println!("{}", format!("Game tree: {}", path.display()).bold().cyan());
+            print_summary(&root, &summary);
+            println!("\n{}", format!("=== Tree ({} actions deep) ===", depth).bold().white());
+            println!("{}", node_text(&root));
+            print_children(&root, "", depth);
+        }
+        OutputFormat::Json => {
+            let mut doc = summary_json(&root, &summary);
+            doc["file"] = json!(path.display().to_string());
+            println!("{}", serde_json::to_string_pretty(&doc)?);
+        }
+    }
+    Ok(())
+}
+
+fn resolve(dir: &Path, key: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let candidates = [PathBuf::from(key), dir.join(key), dir.join(format!("{}.km", key))];
+    candidates
+        .into_iter()
+        .find(|p| p.is_file())
+        .ok_or_else(|| format!("no game tree '{}' (looked in the current directory and {})", key, dir.display()).into())
+}
+
+fn list(dir: &Path, format: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("cannot read {}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|x| x == "km"))
+        .collect();
+    files.sort();
+
+    let mut docs = Vec::new();
+    if format == OutputFormat::Text {
+        println!("{}", format!("Game trees in {}", dir.display()).bold().cyan());
+        println!("{:<32} {:>7} {:>7} {:>6}  STREETS", "NAME", "NODES", "LINES", "DEPTH");
+    }
+    for path in &files {
+        let name = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let root = match load(path) {
+            Ok(root) => root,
+            Err(e) => {
+                eprintln!("{}", format!("Warning: {}", e).yellow());
+                continue;
+            }
+        };
+        let summary = summarize(&root);
+        let streets: Vec<&str> = ROUNDS
+            .iter()
+            .enumerate()
+            .filter(|(r, _)| summary.counts.keys().any(|(round, _)| round == r))
+            .map(|(_, name)| *name)
+            .collect();
+        match format {
+            OutputFormat::Text => println!(
+                "{:<32} {:>7} {:>7} {:>6}  {}",
+                name,
+                summary.nodes,
+                summary.lines,
+                summary.max_depth,
+                streets.join(", ")
+            ),
+            OutputFormat::Json => {
+                let mut doc = summary_json(&root, &summary);
+                doc["file"] = json!(path.display().to_string());
+                docs.push(doc);
+            }
+        }
+    }
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&docs)?);
+    } else if files.is_empty() {
+        println!("No .km files.");
+    }
+    Ok(())
+}
+
+fn print_summary(root: &GameNode, summary: &TreeSummary) {
+    println!(
+        "{} nodes, {} lines, at most {} actions deep; starting pot {}",
+        summary.nodes,
+        summary.lines,
+        summary.max_depth,
+        chips(root.pot)
+    );
+
+    println!("\n{:<8}{}{:>8}", "", NodeType::ALL.map(|t| format!("{:>10}", t.as_str())).concat(), "TOTAL");
+    for (r, round) in ROUNDS.iter().enumerate() {
+        let row = NodeType::ALL.map(|t| summary.counts.get(&(r, t)).copied().unwrap_or(0));
+        let total: usize = row.iter().sum();
+        if total == 0 {
+            continue;
+        }
+        println!("{:<8}{}{:>8}", round, row.map(|n| format!("{:>10}", n)).concat(), total);
+    }
+
+    println!("\n{}", "Final pots (lines ending with each):".bold());
+    let pots: Vec<String> = summary.final_pots.iter().take(SHOWN_POTS).map(|(pot, n)| format!("{} x{}", chips(*pot), n)).collect();
+    let mut text = pots.join(", ");
+    if summary.final_pots.len() > SHOWN_POTS {
+        text.push_str(&format!(", ... ({} more sizes)", summary.final_pots.len() - SHOWN_POTS));
+    }
+    println!("  {}", text);
+
+    if !summary.largest.is_empty() {
+        let steps: Vec<String> = summary
+            .largest
+            .iter()
+            .map(|(label, pot)| format!("{} ({})", label, chips(*pot)))
+            .collect();
+        println!("{}\n  {}", "Largest pot line:".bold(), steps.join(" -> "));
+    }
+}
+
+fn print_children(node: &GameNode, indent: &str, depth: usize) {
+    if depth == 0 {
+        if !node.is_leaf() {
+            println!("{}{}", indent, format!("... {} more node(s)", count(node) - 1).dimmed());
+        }
+        return;
+    }
+    let last = node.children.len().saturating_sub(1);
+    for (i, (label, child)) in node.children.iter().enumerate() {
+        let (branch, next) = if i == last { ("└─ ", "   ") } else { ("├─ ", "│  ") };
+        println!("{}{}{}  {}", indent, branch, action_label(label, child).bold(), node_text(child));
+        let depth = if node.node_type == NodeType::Chance { depth } else { depth - 1 };
+        print_children(child, &format!("{}{}", indent, next), depth);
+    }
+}
+
+fn count(node: &GameNode) -> usize {
+    1 + node.children.iter().map(|(_, c)| count(c)).sum::<usize>()
+}
+
+// What a node is: who acts, or how the hand ends, and the pot.
+fn node_text(node: &GameNode) -> String {
+    let what = match (&node.node_type, &node.payoff) {
+        (NodeType::Action, _) => format!("{} to act", player_name(node.player)),
+        (NodeType::Chance, _) => "chance".to_string(),
+        (NodeType::Terminal, Some(Payoff::Fold(p))) => {
+            let winner = if p[0] > p[1] { 0 } else { 1 };
+            format!("{} wins {}", player_name(winner), chips(p[winner as usize]))
+        }
+        (NodeType::Terminal, _) => "end".to_string(),
+        (NodeType::Showdown, _) => "showdown".to_string(),
+    };
+    format!("{}  {}", what, format!("pot {} [{}]", chips(node.pot), node.round_name()).dimmed())
+}
+
+fn player_name(player: u8) -> &'static str {
+    if player == 1 { "OOP" } else { "IP" }
+}
+
+fn summary_json(root: &GameNode, summary: &TreeSummary) -> Value {
+    let mut counts = serde_json::Map::new();
+    for (r, round) in ROUNDS.iter().enumerate() {
+        let row: serde_json::Map<String, Value> = NodeType::ALL
+            .iter()
+            .filter_map(|t| summary.counts.get(&(r, *t)).map(|n| (t.as_str().to_string(), json!(n))))
+            .collect();
+        if !row.is_empty() {
+            counts.insert(round.to_string(), Value::Object(row));
+        }
+    }
+    json!({
+        "nodes": summary.nodes,
+        "lines": summary.lines,
+        "max_depth": summary.max_depth,
+        "starting_pot": root.pot,
+        "counts": counts,
+        "final_pots": summary.final_pots.iter().map(|(pot, n)| json!({ "pot": pot, "lines": n })).collect::<Vec<_>>(),
+        "largest_pot_line": summary.largest.iter().map(|(a, pot)| json!({ "action": a, "pot": pot })).collect::<Vec<_>>(),
+    })
+}
@@ -0,0 +1,141 @@
+use colored::*;
+use serde_json::Value;
+
+use crate::cli::OutputFormat;
+use crate::config::{Config, Profile};
+use crate::history::{board_string, find, format_timestamp, history_path, load_all, matches, tree_path};
+use crate::json_out::emit::convergence_from_json;
+use crate::ui::flow::{convergence_lines, run_batch, show_record};
+
+const USAGE: &str = "usage: history [list [N] | search <term> | show <id|#> | rerun <id|#>]";
+const DEFAULT_LIST_LEN: usize = 20;
+
+// `texas_solver_tui history ...`
+//
+// Records are addressed by run id (any unique prefix) or by the number shown
+// in `history list`, where 1 is the most recent run.
+pub fn run(config: &Config, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (records, skipped) = load_all()?;
+    if skipped > 0 {
+        eprintln!(
+            "{}",
+            format!("Warning: skipped {} unreadable line(s) in {}", skipped, history_path().display()).yellow()
+        );
+    }
+
+    let sub = args.first().map(String::as_str).unwrap_or("list");
+    let arg = args.get(1).map(String::as_str);
+    match (sub, arg) {
+        ("list", n) => {
+            let n = match n {
+                Some(n) => n.parse::<usize>().map_err(|_| format!("'{}' is not a number; {}", n, USAGE))?,
+                None => DEFAULT_LIST_LEN,
+            };
+            let shown: Vec<(usize, &Value)> = numbered(&records).take(n).collect();
+            print_table(&shown, records.len());
+            Ok(())
+        }
+        ("search", Some(term)) => {
+            let shown: Vec<(usize, &Value)> = numbered(&records).filter(|(_, r)| matches(r, term)).collect();
+            print_table(&shown, records.len());
+            Ok(())
+        }
+        ("show", Some(key)) => {
+            let record = find(&records, key)?;
+            print_header(config, record);
+            show_record(record);
+            Ok(())
+        }
+        ("rerun", Some(key)) => {
+            let record = find(&records, key)?;
+            rerun(config, record)
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+// Most recent first, numbered the way `find` understands.
+fn numbered(records: &[Value]) -> impl Iterator<Item = (usize, &Value)> {
+    records.iter().rev().enumerate().map(|(i, r)| (i + 1, r))
+}
+
+fn print_table(rows: &[(usize, &Value)], total: usize) {
+    if total == 0 {
+        println!("No history yet ({}).", history_path().display());
+        return;
+    }
+    if rows.is_empty() {
+        println!("No matching runs.");
+        return;
+    }
+    println!(
+        "{}",
+        format!("{:>4}  {:<16}  {:<23}  {:<12}  {:<6}  {}", "#", "RUN ID", "DATE", "PROFILE", "HERO", "BOARD").bold()
+    );
+    for (n, r) in rows {
+        println!(
+            "{:>4}  {:<16}  {:<23}  {:<12}  {:<6}  {}",
+            n,
+            r["run_id"].as_str().unwrap_or("?"),
+            format_timestamp(r["timestamp"].as_u64().unwrap_or(0)),
+            r["job"]["profile"].as_str().unwrap_or("?"),
+            r["inputs"]["hero"].as_str().unwrap_or("?"),
+            board_string(r)
+        );
+    }
+    if rows.len() < total {
+        println!("{}", format!("({} of {} runs)", rows.len(), total).dimmed());
+    }
+}
+
+fn print_header(config: &Config, record: &Value) {
+    println!(
+        "{}",
+        format!(
+            "Run {} - {} - profile {}",
+            record["run_id"].as_str().unwrap_or("?"),
+            format_timestamp(record["timestamp"].as_u64().unwrap_or(0)),
+            record["job"]["profile"].as_str().unwrap_or("?")
+        )
+        .bold()
+        .cyan()
+    );
+    println!("Hero: {}   Board: {}", record["inputs"]["hero"].as_str().unwrap_or("?"), board_string(record));
+    if let Ok(profile) = Profile::from_json("history", &record["job"]) {
+        for lock in &profile.locks {
+            println!("{}", format!("Locked: {}", lock).magenta());
+        }
+    }
+    let (summary, warning) = convergence_lines(&convergence_from_json(&record["solver"]), config.solver.max_exploitability());
+    println!("{}", summary.dimmed());
+    if let Some(w) = warning {
+        println!("{}", w.yellow());
+    }
+    match tree_path(record) {
+        Some(p) if p.is_file() => println!("{}", format!("Tree: {}", p.display()).dimmed()),
+        _ => println!("{}", "Tree: not saved".dimmed()),
+    }
+}
+
+// Solve again with the settings the run was recorded with, not whatever the
+// profile of that name says today.
+fn rerun(config: &Config, record: &Value) -> Result<(), Box<dyn std::error::Error>> {
+    let name = record["job"]["profile"].as_str().unwrap_or("history");
+    let profile = Profile::from_json(name, &record["job"])
+        .map_err(|e| format!("run {} has unusable job settings: {}", record["run_id"], e))?;
+    let inputs = &record["inputs"];
+    let hero = inputs["hero"].as_str().ok_or("record has no hero hand")?;
+    let flop = inputs["flop"]
+        .as_array()
+        .map(|a| a.iter().filter_map(|c| c.as_str()).collect::<Vec<_>>().join(","))
+        .ok_or("record has no flop")?;
+    run_batch(
+        config,
+        &profile,
+        hero,
+        &flop,
+        inputs["turn"].as_str(),
+        inputs["river"].as_str(),
+        OutputFormat::Text,
+    )
+}
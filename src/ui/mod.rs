@@ -1,3 +1,12 @@
 pub mod flow;
+pub mod doctor;
+pub mod export;
+pub mod history;
+pub mod report;
+pub mod batch;
+pub mod trainer;
+pub mod diff;
+pub mod gametree;
+pub mod tree;
 pub mod input;
 pub mod output;
@@ -0,0 +1,44 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use colored::*;
+
+use crate::history::{find, load_all, tree_path};
+use crate::json_out::parse::load_tree;
+use crate::report::{html, markdown, spot};
+
+// `texas_solver_tui report <id|#> [out.md|out.html]`
+//
+// Writes a study report for a run from the history. The format follows the
+// output extension (.html/.htm for HTML, anything else Markdown); the
+// default is report_<run id>.md in the current directory.
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let key = args.first().ok_or("usage: report <id|#> [out.md|out.html]")?;
+    let (records, _) = load_all()?;
+    let record = find(&records, key)?;
+    let run_id = record["run_id"].as_str().unwrap_or("run");
+
+    let tree_file = tree_path(record)
+        .filter(|p| p.is_file())
+        .ok_or_else(|| format!("run {} has no saved tree; rerun it with `history rerun {}`", run_id, key))?;
+    let tree = load_tree(&tree_file.to_string_lossy())?;
+
+    let out = args
+        .get(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(format!("report_{}.md", run_id)));
+
+    eprintln!("{}", "Computing equities...".dimmed());
+    let spot = spot::build(record, &tree)?;
+    let document = if is_html(&out) { html::render(&spot) } else { markdown::render(&spot) };
+    fs::write(&out, document)?;
+
+    println!("{} {}", "wrote".green(), out.display());
+    Ok(())
+}
+
+fn is_html(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("html") || e.eq_ignore_ascii_case("htm"))
+}
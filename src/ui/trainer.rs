@@ -0,0 +1,298 @@
+use std::io::{self, Write};
+use std::path::Path;
+
+use colored::*;
+use serde_json::Value;
+
+use crate::batch::MANIFEST_FILE;
+use crate::evaluator::evaluate_hand;
+use crate::history::{find, load_all};
+use crate::json_out::action::Action;
+use crate::json_out::parse::short_action;
+use crate::trainer::records::{self, accuracy_by, buckets, category, day, due_count, pick_review, user_name};
+use crate::trainer::{Drill, Grade, Pool, Rng, Session, CORRECT_SCORE};
+use crate::ui::flow::{colorize_board, colorize_card};
+
+const USAGE: &str = "usage: train [<id|#> | <batch-dir> | review | stats] [--user NAME]";
+const STATS_DAYS: usize = 14;
+const STATS_WEAK_SPOTS: usize = 10;
+
+// Where drills come from: fresh deals from solved trees, or recorded drills
+// picked by error rate.
+enum Deck {
+    Trees(Box<Pool>),
+    Review(Vec<Value>),
+}
+
+impl Deck {
+    fn next(&mut self, rng: &mut Rng) -> Result<Option<Drill>, Box<dyn std::error::Error>> {
+        match self {
+            Deck::Trees(pool) => pool.deal(rng).map(Some),
+            Deck::Review(records) => Ok(pick_review(records, rng)),
+        }
+    }
+}
+
+// `texas_solver_tui train [<id|#> | <batch-dir> | review | stats] [--user NAME]`
+//
+// Deals decisions from a history run's tree (default: the most recent run
+// with a saved tree) or from every tree of a batch directory until the user
+// types q. Answers are recorded per user; `review` replays the weakest
+// spots and `stats` summarizes the records.
+pub fn run(args: &[String], user: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let user = user_name(user);
+    let (records, skipped) = records::load(&user)?;
+    if skipped > 0 {
+        eprintln!(
+            "{}",
+            format!("Warning: skipped {} unreadable line(s) in {}", skipped, records::records_path(&user).display()).yellow()
+        );
+    }
+
+    let deck = match args.first().map(String::as_str) {
+        Some("stats") => return print_stats(&user, &records),
+        Some("review") => {
+            if due_count(&records) == 0 {
+                println!("Nothing to review for {}: no spot with recent mistakes.", user.bold());
+                return Ok(());
+            }
+            println!(
+                "{}",
+                format!("Review for {}: {} spot/hand-category pair(s) with recent mistakes", user, due_count(&records)).bold().cyan()
+            );
+            Deck::Review(records)
+        }
+        Some(arg) if Path::new(arg).join(MANIFEST_FILE).is_file() => Deck::Trees(Box::new(Pool::from_batch(Path::new(arg))?)),
+        Some(key) => {
+            let (history, _) = load_all()?;
+            Deck::Trees(Box::new(Pool::from_history(find(&history, key)?)?))
+        }
+        None => {
+            let (history, _) = load_all()?;
+            let latest = history
+                .iter()
+                .rev()
+                .find(|r| Pool::from_history(r).is_ok())
+                .ok_or_else(|| format!("no history run with a saved tree to train on; {}", USAGE))?;
+            Deck::Trees(Box::new(Pool::from_history(latest)?))
+        }
+    };
+    let pool_name = match &deck {
+        Deck::Trees(pool) => {
+            println!(
+                "{}",
+                format!("Trainer: {} ({} tree(s), profile '{}')", pool.name, pool.tree_count(), pool.profile.name).bold().cyan()
+            );
+            pool.name.clone()
+        }
+        Deck::Review(_) => "review".to_string(),
+    };
+    println!("{}", "Answer with the action's number or name; q to stop.".dimmed());
+
+    let session = practice(deck, &user, &pool_name)?;
+    print_session(&session);
+    Ok(())
+}
+
+fn practice(mut deck: Deck, user: &str, pool_name: &str) -> Result<Session, Box<dyn std::error::Error>> {
+    let mut rng = Rng::from_time();
+    let mut session = Session::default();
+    let review = matches!(deck, Deck::Review(_));
+    while let Some(drill) = deck.next(&mut rng)? {
+        print_drill(&drill, session.drills + 1);
+        let Some(choice) = ask(&drill)? else { break };
+        let grade = drill.grade(choice);
+        print_grade(&drill, choice, &grade);
+        session.add(&grade);
+
+        match records::append(user, pool_name, &drill, choice, &grade, review) {
+            Ok(record) => {
+                if let Deck::Review(records) = &mut deck {
+                    records.push(record);
+                }
+            }
+            Err(e) => println!("Warning: failed to save the answer: {}", e),
+        }
+    }
+    if let Deck::Review(records) = &deck
+        && due_count(records) == 0
+    {
+        println!("{}", "All reviewed spots answered well recently.".green());
+    }
+    Ok(session)
+}
+
+fn print_drill(drill: &Drill, n: usize) {
+    println!("\n{}", format!("=== Drill {} - {} ({}) ===", n, drill.street.to_uppercase(), drill.source).bold().white());
+    println!("Board: {}", colorize_board(&drill.board.join(",")));
+    let position = if drill.oop { "OUT OF POSITION (Big Blind)" } else { "IN POSITION (Button)" };
+    println!("You are {}", position.bold());
+    let line = if drill.line.is_empty() {
+        "first to act".to_string()
+    } else {
+        drill.line.iter().map(|a| short_action(a)).collect::<Vec<_>>().join(" -> ")
+    };
+    println!("Action so far this street: {}", line.yellow());
+    println!(
+        "Hand: {} {}  ({})",
+        colorize_card(&drill.combo[0..2]),
+        colorize_card(&drill.combo[2..4]),
+        evaluate_hand(&drill.combo, &drill.board.join(","), drill.game).italic().yellow()
+    );
+    for (i, action) in drill.actions.iter().enumerate() {
+        println!("  {}) {}", i + 1, short_action(action));
+    }
+}
+
+// The chosen action, or None to stop (q or end of input).
+fn ask(drill: &Drill) -> Result<Option<usize>, Box<dyn std::error::Error>> {
+    loop {
+        print!("Your action: ");
+        io::stdout().flush()?;
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            return Ok(None);
+        }
+        let answer = input.trim().to_ascii_uppercase();
+        if answer == "Q" || answer == "QUIT" {
+            return Ok(None);
+        }
+        if let Ok(n) = answer.parse::<usize>()
+            && (1..=drill.actions.len()).contains(&n)
+        {
+            return Ok(Some(n - 1));
+        }
+        // An action and amount however it is spelled ("bet 25.0"), else a
+        // unique prefix of the label: "c", "bet", "bet 2".
+        if let Some(action) = Action::parse(&answer)
+            && action.chips().is_some()
+            && let Some(i) = drill.actions.iter().position(|a| Action::parse(a).is_some_and(|a| a.matches(&action)))
+        {
+            return Ok(Some(i));
+        }
+        let matches: Vec<usize> = (0..drill.actions.len())
+            .filter(|&i| !answer.is_empty() && short_action(&drill.actions[i]).starts_with(&answer))
+            .collect();
+        match matches.as_slice() {
+            [i] => return Ok(Some(*i)),
+            [] => println!("{}", format!("'{}' is not one of the actions.", input.trim()).red()),
+            _ => println!("{}", format!("'{}' matches more than one action; use its number.", input.trim()).red()),
+        }
+    }
+}
+
+fn print_grade(drill: &Drill, choice: usize, grade: &Grade) {
+    let verdict = if choice == grade.best {
+        "Best play".green().bold()
+    } else if grade.score >= CORRECT_SCORE {
+        "Fine (mixed spot)".green()
+    } else if grade.score >= 0.5 {
+        "Inaccuracy".yellow()
+    } else {
+        "Mistake".red().bold()
+    };
+    println!("{}  score {:.0}%", verdict, grade.score * 100.0);
+    if let Some(loss) = grade.ev_loss {
+        println!("EV lost: {:.2} chips ({:.1}% of the pot)", loss, loss / drill.pot * 100.0);
+    }
+
+    println!("{}", "Solver strategy for this hand:".dimmed());
+    let labels: Vec<String> = drill.actions.iter().map(|a| short_action(a)).collect();
+    let width = labels.iter().map(String::len).max().unwrap_or(0);
+    for (i, (label, p)) in labels.iter().zip(&drill.probs).enumerate() {
+        let marker = if i == choice { ">" } else { " " };
+        let ev = drill.evs.as_ref().map(|e| format!("  EV {:.2}", e[i])).unwrap_or_default();
+        println!(
+            " {} {:<w$} : {:>5.1}% {}{}",
+            marker,
+            label,
+            p * 100.0,
+            "█".repeat((p * 40.0) as usize).truecolor(200, 200, 200),
+            ev,
+            w = width
+        );
+    }
+    println!("{}", format!("Node: {}", if drill.path.is_empty() { "(root)" } else { &drill.path }).dimmed());
+}
+
+fn print_session(session: &Session) {
+    if session.drills == 0 {
+        println!("No drills answered.");
+        return;
+    }
+    println!("\n{}", "=== Session ===".bold().white());
+    println!("Drills:   {}", session.drills);
+    println!(
+        "Correct:  {} ({:.0}%)",
+        session.correct,
+        session.correct as f64 / session.drills as f64 * 100.0
+    );
+    println!("Accuracy: {:.1}%", session.accuracy() * 100.0);
+    if session.ev_drills > 0 {
+        println!("EV lost:  {:.2} chips over {} drill(s) with EVs", session.total_ev_loss, session.ev_drills);
+    }
+}
+
+fn print_stats(user: &str, records: &[Value]) -> Result<(), Box<dyn std::error::Error>> {
+    if records.is_empty() {
+        println!("No trainer records for {} yet ({}).", user.bold(), records::records_path(user).display());
+        return Ok(());
+    }
+    let session = records.iter().fold(Session::default(), |mut s, r| {
+        s.add(&Grade {
+            score: r["score"].as_f64().unwrap_or(0.0),
+            best: 0,
+            ev_loss: r["ev_loss"].as_f64(),
+        });
+        s
+    });
+    println!("{}", format!("Trainer stats for {}", user).bold().cyan());
+    println!(
+        "Drills: {}   correct: {:.0}%   accuracy: {:.1}%   since {}",
+        session.drills,
+        session.correct as f64 / session.drills as f64 * 100.0,
+        session.accuracy() * 100.0,
+        day(&records[0])
+    );
+
+    let by_day = accuracy_by(records, day);
+    print_groups("By day", &by_day[by_day.len().saturating_sub(STATS_DAYS)..]);
+    print_groups("By street", &accuracy_by(records, |r| r["drill"]["street"].as_str().unwrap_or("?").to_string()));
+    print_groups(
+        "By position",
+        &accuracy_by(records, |r| if r["drill"]["oop"].as_bool() == Some(true) { "OOP" } else { "IP" }.to_string()),
+    );
+    let mut by_category = accuracy_by(records, category);
+    by_category.sort_by(|a, b| a.2.total_cmp(&b.2));
+    print_groups("By hand category (weakest first)", &by_category);
+
+    println!("\n{}", "=== Weakest spots (last answers) ===".bold().white());
+    let weak: Vec<_> = buckets(records).into_iter().filter(|b| b.recent_error > 0.0).take(STATS_WEAK_SPOTS).collect();
+    if weak.is_empty() {
+        println!("None: every spot was answered well recently.");
+    }
+    for b in weak {
+        println!(
+            "{:>5.1}% error  {:>3} drill(s)  {:<16}  {}",
+            b.recent_error * 100.0,
+            b.records.len(),
+            b.category,
+            b.spot
+        );
+    }
+    println!("{}", format!("{} due for review (train review)", due_count(records)).dimmed());
+    Ok(())
+}
+
+fn print_groups(title: &str, groups: &[(String, usize, f64)]) {
+    println!("\n{}", format!("=== {} ===", title).bold().white());
+    for (group, drills, accuracy) in groups {
+        println!(
+            "{:<16} {:>5} drill(s)  {:>5.1}%  {}",
+            group,
+            drills,
+            accuracy * 100.0,
+            "█".repeat((accuracy * 20.0).round() as usize).truecolor(200, 200, 200)
+        );
+    }
+}
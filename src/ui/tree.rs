@@ -0,0 +1,201 @@
+use std::io::{self, Write};
+
+use colored::*;
+use serde_json::{json, Value};
+
+use crate::cli::OutputFormat;
+use crate::config::Profile;
+use crate::json_out::action::chips;
+use crate::tree::{build, ActionKind, NodeKind, TreeAction, TreeNode, STREETS};
+
+const USAGE: &str = "usage: tree [flop|turn|river] [depth] [--profile NAME] [--format json]";
+const DEFAULT_DEPTH: usize = 3;
+
+// `texas_solver_tui tree [flop|turn|river] [depth]`
+//
+// Draws the betting tree the profile gives the solver, starting on the given
+// street (default flop), `depth` actions deep. Deals are shown once, with the
+// number of cards they stand for.
+pub fn run(profile: &Profile, args: &[String], format: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
+    let mut street = 0;
+    let mut depth = DEFAULT_DEPTH;
+    for arg in args {
+        if let Some(i) = STREETS.iter().position(|s| s == arg) {
+            street = i;
+        } else {
+            depth = arg.parse().map_err(|_| format!("'{}' is neither a street nor a depth; {}", arg, USAGE))?;
+        }
+    }
+    let root = build(profile, street + 3);
+
+    match format {
+        OutputFormat::Text => {
+            println!(
+                "{}",
+                format!("Betting tree: profile {}, from the {} ({} actions deep)", profile.name, STREETS[street], depth)
+                    .bold()
+                    .cyan()
+            );
+            println!("{}", node_text(&root));
+            print_children(&root, "", depth);
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&node_json(&root, depth))?),
+    }
+    Ok(())
+}
+
+fn print_children(node: &TreeNode, indent: &str, depth: usize) {
+    if depth == 0 {
+        if !node.children.is_empty() {
+            println!("{}{}", indent, "...".dimmed());
+        }
+        return;
+    }
+    let last = node.children.len().saturating_sub(1);
+    for (i, child) in node.children.iter().enumerate() {
+        let (branch, next) = if i == last { ("└─ ", "   ") } else { ("├─ ", "│  ") };
+        let edge = match node.actions.get(i) {
+            Some(action) => format!("{} -> ", action_text(action, node.pot)),
+            None => String::new(),
+        };
+        println!("{}{}{}{}", indent, branch, edge, node_text(child));
+        let depth = if node.actions.is_empty() { depth } else { depth - 1 };
+        print_children(child, &format!("{}{}", indent, next), depth);
+    }
+}
+
+// "BET 25 (50% pot)", "RAISE 200 ALL-IN (60%, 100% pot -> all-in)"
+fn action_text(action: &TreeAction, pot: f64) -> String {
+    let name = match action.kind {
+        ActionKind::Check => "CHECK",
+        ActionKind::Bet => "BET",
+        ActionKind::Call => "CALL",
+        ActionKind::Raise => "RAISE",
+        ActionKind::Fold => "FOLD",
+    };
+    let mut text = name.to_string();
+    if action.chips > 0.0 {
+        text.push_str(&format!(" {}", chips(action.chips)));
+    }
+    if action.all_in {
+        text.push_str(" ALL-IN");
+    }
+    if matches!(action.kind, ActionKind::Bet | ActionKind::Raise) {
+        let share = format!("{:.0}% pot", action.chips / pot * 100.0);
+        // Sizes that did not come out as configured were collapsed.
+        let collapsed = action.sizes.len() > 1 || (action.all_in && !action.sizes.is_empty());
+        if collapsed {
+            let sizes: Vec<String> = action.sizes.iter().map(|s| format!("{}%", chips(*s))).collect();
+            text.push_str(&format!(" ({}; {} -> all-in)", share, sizes.join(", ")));
+        } else {
+            text.push_str(&format!(" ({})", share));
+        }
+    }
+    let colored = match action.kind {
+        ActionKind::Bet | ActionKind::Raise => text.red(),
+        ActionKind::Check => text.green(),
+        ActionKind::Call => text.yellow(),
+        ActionKind::Fold => text.blue(),
+    };
+    colored.bold().to_string()
+}
+
+// Who acts (or how the hand ends), pot and stack-to-pot ratio.
+fn node_text(node: &TreeNode) -> String {
+    let what = match &node.kind {
+        NodeKind::Action(player) => format!("{} to act", player.name()),
+        NodeKind::Chance { cards } => format!("{} card ({} ways)", STREETS[node.street], cards),
+        NodeKind::Showdown => "showdown".to_string(),
+        NodeKind::Fold(player) => format!("{} folds", player.name()),
+    };
+    let behind = node.stacks[0].min(node.stacks[1]);
+    let spr = if node.children.is_empty() { String::new() } else { format!("  SPR {:.1}", behind / node.pot) };
+    format!("{}  {}", what, format!("pot {}{}", chips(node.pot), spr).dimmed())
+}
+
+fn node_json(node: &TreeNode, depth: usize) -> Value {
+    let kind = match &node.kind {
+        NodeKind::Action(player) => json!({ "type": "action", "player": player.name() }),
+        NodeKind::Chance { cards } => json!({ "type": "chance", "cards": cards }),
+        NodeKind::Showdown => json!({ "type": "showdown" }),
+        NodeKind::Fold(player) => json!({ "type": "fold", "player": player.name() }),
+    };
+    let mut v = json!({
+        "node": kind,
+        "street": STREETS[node.street],
+        "pot": node.pot,
+        "stacks": { "oop": node.stacks[0], "ip": node.stacks[1] },
+    });
+    if depth == 0 {
+        return v;
+    }
+    let next = if node.actions.is_empty() { depth } else { depth - 1 };
+    if node.actions.is_empty() {
+        if let Some(child) = node.children.first() {
+            v["next"] = node_json(child, next);
+        }
+    } else {
+        v["actions"] = node
+            .actions
+            .iter()
+            .zip(&node.children)
+            .map(|(a, child)| {
+                json!({
+                    "action": a.label(),
+                    "chips": a.chips,
+                    "pct_pot": a.chips / node.pot * 100.0,
+                    "all_in": a.all_in,
+                    "sizes": a.sizes,
+                    "child": node_json(child, next),
+                })
+            })
+            .collect();
+    }
+    v
+}
+
+// Walk the tree one node at a time: pick an action to follow, `b` to go
+// back. Deals are followed through.
+pub fn browse(profile: &Profile, board_cards: usize) -> io::Result<()> {
+    let root = build(profile, board_cards);
+    let mut line: Vec<usize> = Vec::new();
+    let mut input = String::new();
+    loop {
+        let mut node = &root;
+        let mut labels = Vec::new();
+        for &i in &line {
+            if let Some(action) = node.actions.get(i) {
+                labels.push(action.label());
+            }
+            node = &node.children[i];
+            while node.actions.is_empty() && node.children.len() == 1 {
+                node = &node.children[0];
+            }
+        }
+
+        println!("\n{} {}", "Line:".bold(), if labels.is_empty() { "(root)".to_string() } else { labels.join(" / ") });
+        println!("{}  [{}]", node_text(node), STREETS[node.street]);
+        for (i, (action, child)) in node.actions.iter().zip(&node.children).enumerate() {
+            println!("  {}) {} -> {}", i + 1, action_text(action, node.pot), node_text(child));
+        }
+
+        if node.actions.is_empty() {
+            print!("b = back, empty = done: ");
+        } else {
+            print!("Follow 1-{}, b = back, empty = done: ", node.actions.len());
+        }
+        io::stdout().flush()?;
+        input.clear();
+        io::stdin().read_line(&mut input)?;
+        match input.trim() {
+            "" => return Ok(()),
+            "b" => {
+                line.pop();
+            }
+            choice => match choice.parse::<usize>() {
+                Ok(n) if n >= 1 && n <= node.actions.len() => line.push(n - 1),
+                _ => println!("{}", format!("'{}' is not a choice.", choice).red()),
+            },
+        }
+    }
+}
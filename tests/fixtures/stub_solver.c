@@ -0,0 +1,97 @@
+/*
+ * Stand-in for a solver library implementing the ABI in src/library.rs.
+ * It does not solve anything: it remembers the board and ranges from the
+ * commands, reports three fake iterations and dumps a one-node tree (with
+ * EVs when asked, and the number of locked nodes).
+ */
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+typedef void (*ts_progress_fn)(void *user, uint32_t iteration, double exploitability);
+
+typedef struct {
+    char board[64];
+    char mode[16];
+    int lines;
+    int dump_evs;
+    int locks;
+    const char *error;
+} stub_solver;
+
+uint32_t ts_abi_version(void) { return 1; }
+
+void *ts_create(const char *resource_dir, const char *mode) {
+    (void)resource_dir;
+    stub_solver *s = calloc(1, sizeof(stub_solver));
+    if (!s) return NULL;
+    strncpy(s->mode, mode, sizeof(s->mode) - 1);
+    return s;
+}
+
+int ts_build_tree(void *handle, const char *commands) {
+    stub_solver *s = handle;
+    const char *line = commands;
+    while (line && *line) {
+        const char *end = strchr(line, '\n');
+        size_t len = end ? (size_t)(end - line) : strlen(line);
+        if (len > 10 && strncmp(line, "set_board ", 10) == 0) {
+            size_t n = len - 10 < sizeof(s->board) - 1 ? len - 10 : sizeof(s->board) - 1;
+            memcpy(s->board, line + 10, n);
+            s->board[n] = '\0';
+        }
+        if (len > 0) s->lines++;
+        line = end ? end + 1 : NULL;
+    }
+    if (s->board[0] == '\0') {
+        s->error = "no set_board command";
+        return 2;
+    }
+    return 0;
+}
+
+int ts_solve(void *handle, ts_progress_fn progress, void *user) {
+    (void)handle;
+    for (uint32_t i = 1; i <= 3; i++) {
+        if (progress) progress(user, i, 10.0 / i);
+    }
+    return 0;
+}
+
+int ts_set_dump_evs(void *handle, int enabled) {
+    ((stub_solver *)handle)->dump_evs = enabled;
+    return 0;
+}
+
+int ts_lock_node(void *handle, const char *path, const char *strategy) {
+    stub_solver *s = handle;
+    if (strcmp(path, "CHECK") != 0 || strstr(strategy, "BET") == NULL) {
+        s->error = "no such node";
+        return 3;
+    }
+    s->locks++;
+    return 0;
+}
+
+char *ts_dump(void *handle, uint32_t dump_rounds) {
+    stub_solver *s = handle;
+    char *out = malloc(1024);
+    if (!out) return NULL;
+    const char *evs = s->dump_evs
+        ? ",\"evs\":{\"actions\":[\"CHECK\",\"BET 25.000000\"],\"evs\":{\"AhKd\":[30.5,32.25]}}"
+        : "";
+    snprintf(out, 1024,
+             "{\"node_type\":\"action_node\",\"player\":1,\"board\":\"%s\",\"mode\":\"%s\","
+             "\"commands\":%d,\"locks\":%d,\"dump_rounds\":%u,\"actions\":[\"CHECK\",\"BET 25.000000\"],"
+             "\"strategy\":{\"actions\":[\"CHECK\",\"BET 25.000000\"],"
+             "\"strategy\":{\"AhKd\":[0.25,0.75]}}%s}",
+             s->board, s->mode, s->lines, s->locks, dump_rounds, evs);
+    return out;
+}
+
+void ts_free_string(char *str) { free(str); }
+
+const char *ts_last_error(void *handle) { return ((stub_solver *)handle)->error; }
+
+void ts_destroy(void *handle) { free(handle); }
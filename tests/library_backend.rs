@@ -0,0 +1,97 @@
+#![cfg(unix)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use texas_solver_tui::config::NodeLock;
+use texas_solver_tui::json_out::parse::hero_strategy_from_node;
+use texas_solver_tui::library::SolverLibrary;
+
+// Compile tests/fixtures/stub_solver.c into a shared library with the system C
+// compiler. Each test gets its own output name so they can run in parallel.
+fn build_stub(name: &str) -> PathBuf {
+    let ext = if cfg!(target_os = "macos") { "dylib" } else { "so" };
+    let out = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("lib{}.{}", name, ext));
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/stub_solver.c");
+    let cc = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(cc)
+        .args(["-shared", "-fPIC", "-o"])
+        .arg(&out)
+        .arg(&src)
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success(), "compiling the stub solver failed");
+    out
+}
+
+#[test]
+fn solves_and_dumps_through_the_library() {
+    let lib = SolverLibrary::open(&build_stub("stub_solve")).unwrap();
+    let mut progress = Vec::new();
+    let commands = "set_pot 50\nset_effective_stack 200\nset_board Qs,Jh,2h\n";
+
+    let tree = lib
+        .solve(Path::new("resources"), "holdem", commands, &[], 1, false, &mut |i, e| progress.push((i, e)))
+        .unwrap();
+
+    assert_eq!(progress, vec![(1, 10.0), (2, 5.0), (3, 10.0 / 3.0)]);
+    assert_eq!(tree["board"], "Qs,Jh,2h");
+    assert_eq!(tree["mode"], "holdem");
+    assert_eq!(tree["commands"], 3);
+    assert_eq!(tree["locks"], 0);
+    assert_eq!(tree["dump_rounds"], 1);
+
+    let hero = hero_strategy_from_node(&tree, "AhKd").unwrap();
+    assert_eq!(hero.actions, vec!["CHECK", "BET 25.000000"]);
+    assert_eq!(hero.probs, vec![0.25, 0.75]);
+    assert_eq!(hero.evs, None);
+}
+
+#[test]
+fn dumps_evs_when_asked() {
+    let lib = SolverLibrary::open(&build_stub("stub_evs")).unwrap();
+    assert!(lib.supports_evs());
+    let tree = lib
+        .solve(Path::new("resources"), "holdem", "set_board Qs,Jh,2h\n", &[], 1, true, &mut |_, _| {})
+        .unwrap();
+
+    // Hero typed the cards in the other order.
+    let hero = hero_strategy_from_node(&tree, "KdAh").unwrap();
+    assert_eq!(hero.evs, Some(vec![30.5, 32.25]));
+}
+
+#[test]
+fn passes_node_locks() {
+    let lib = SolverLibrary::open(&build_stub("stub_locks")).unwrap();
+    assert!(lib.supports_locks());
+    let stab = NodeLock { path: "CHECK".to_string(), strategy: vec![("BET".to_string(), 1.0)] };
+    let tree = lib
+        .solve(Path::new("resources"), "holdem", "set_board Qs,Jh,2h\n", &[stab], 1, false, &mut |_, _| {})
+        .unwrap();
+    assert_eq!(tree["locks"], 1);
+
+    let typo = NodeLock { path: "CHEK".to_string(), strategy: vec![("BET".to_string(), 1.0)] };
+    let msg = lib
+        .solve(Path::new("resources"), "holdem", "set_board Qs,Jh,2h\n", &[typo], 1, false, &mut |_, _| {})
+        .unwrap_err()
+        .to_string();
+    assert!(msg.contains("ts_lock_node at 'CHEK'"), "{}", msg);
+    assert!(msg.contains("no such node"), "{}", msg);
+}
+
+#[test]
+fn reports_library_errors() {
+    let lib = SolverLibrary::open(&build_stub("stub_error")).unwrap();
+    let err = lib
+        .solve(Path::new("resources"), "holdem", "set_pot 50\n", &[], 1, false, &mut |_, _| {})
+        .unwrap_err();
+    let msg = err.to_string();
+    assert!(msg.contains("ts_build_tree"), "{}", msg);
+    assert!(msg.contains("no set_board command"), "{}", msg);
+}
+
+#[test]
+fn rejects_missing_library() {
+    let err = SolverLibrary::open(Path::new("/nonexistent/libsolver.so")).err().unwrap();
+    assert!(err.to_string().contains("cannot load solver library"));
+}